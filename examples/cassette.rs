@@ -0,0 +1,47 @@
+//! # Cassette Example
+//!
+//! This example demonstrates how to record requests to a cassette and replay them afterwards
+//! without sending anything over the network. Requests are recorded against the mock server
+//! provided by the `testing` feature, the same is done against Discord by using
+//! `Cassette::record` instead.
+//!
+//! ## Example
+//!
+//! cargo run --example cassette --features examples,testing
+
+use yadwh::cassette::{Cassette, Matching};
+use yadwh::message::MessageBuilder;
+use yadwh::testing::MockServer;
+use yadwh::transport::HyperTransport;
+use yadwh::webhook::WebhookApi;
+
+#[tokio::main]
+async fn main() -> Result<(), yadwh::WebhookError> {
+    let path = std::env::temp_dir().join("yadwh_cassette.json");
+    let server = MockServer::start().await?;
+
+    // Message to be sent.
+    let message = MessageBuilder::new()
+        .username("Webhook Example")?
+        .content("Content portion of the message.")?;
+
+    // Record the requests and responses.
+    let cassette = Cassette::record_with(&path, HyperTransport::new());
+    let webhook = server.client().transport(cassette);
+    let created = webhook.message.create(&message, None).await?;
+    webhook.message.get(created.id).await?;
+    println!("Recorded to {}", path.display());
+
+    // Replay them, the base URL must match the one recorded.
+    let cassette = Cassette::replay(&path, Matching::Strict)?;
+    let webhook = WebhookApi::new(server.webhook_id(), yadwh::testing::MOCK_TOKEN)
+        .base_url(&server.base_url())
+        .transport(cassette);
+    drop(server);
+
+    let replayed = webhook.message.create(&message, None).await?;
+    let obtained = webhook.message.get(replayed.id).await?;
+    println!("Replayed message: {:#?}", obtained);
+
+    Ok(())
+}
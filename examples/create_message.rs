@@ -12,7 +12,9 @@
 //!     Token:      aaaabbbb
 
 use std::{env, process};
+use yadwh::color::Color;
 use yadwh::message::MessageBuilder;
+use yadwh::snowflake::WebhookId;
 use yadwh::webhook::WebhookApi;
 
 #[tokio::main]
@@ -26,16 +28,19 @@ async fn main() -> Result<(), yadwh::WebhookError> {
     }
 
     // Parse the arguments.
-    let webhook_id: String = args[1].to_string();
+    let webhook_id: WebhookId = args[1].parse()?;
     let token: String = args[2].to_string();
 
+    // Color of the embed.
+    let color = Color::from_hex("#cba6f7")?;
+
     // Message to be sent.
     let message = MessageBuilder::new()
         .username("Webhook Example")?
         .content("Content portion of the message.")?
         .embed(|embed| {
             embed
-                .color("#cba6f7")
+                .color(color)
                 .author("Author Here", None, None, None)
                 .title("Title Here")
                 .description("Description Here\n```rust\nprintln!(\"Hello World!\");```")
@@ -48,7 +53,7 @@ async fn main() -> Result<(), yadwh::WebhookError> {
 
     // Create the message.
     println!("Creating message.");
-    let webhook = WebhookApi::new(&webhook_id, &token);
+    let webhook = WebhookApi::new(webhook_id, &token);
     match webhook.message.create(&message, None).await {
         Ok(resp) => println!("\nMessage created:\n{:#?}", resp),
         Err(error) => println!("Error while creating: {}", error),
@@ -13,7 +13,9 @@
 //!     Thread ID:  22223333
 
 use std::{env, process};
+use yadwh::color::Color;
 use yadwh::message::MessageBuilder;
+use yadwh::snowflake::{ChannelId, WebhookId};
 use yadwh::webhook::WebhookApi;
 
 #[tokio::main]
@@ -27,9 +29,12 @@ async fn main() -> Result<(), yadwh::WebhookError> {
     }
 
     // Parse the arguments.
-    let webhook_id: String = args[1].to_string();
+    let webhook_id: WebhookId = args[1].parse()?;
     let token: String = args[2].to_string();
-    let thread_id: String = args[3].to_string();
+    let thread_id: ChannelId = args[3].parse()?;
+
+    // Color of the embed.
+    let color = Color::from_hex("#cba6f7")?;
 
     // Message to be sent.
     let message = MessageBuilder::new()
@@ -37,7 +42,7 @@ async fn main() -> Result<(), yadwh::WebhookError> {
         .content("Content portion of the message.")?
         .embed(|embed| {
             embed
-                .color("#cba6f7")
+                .color(color)
                 .author("Author Here", None, None, None)
                 .title("Title Here")
                 .description("Description Here\n```rust\nprintln!(\"Hello World!\");```")
@@ -50,8 +55,8 @@ async fn main() -> Result<(), yadwh::WebhookError> {
 
     // Create the message.
     println!("Creating message.");
-    let webhook = WebhookApi::new(&webhook_id, &token);
-    match webhook.message.create(&message, Some(&thread_id)).await {
+    let webhook = WebhookApi::new(webhook_id, &token);
+    match webhook.message.create(&message, Some(thread_id)).await {
         Ok(resp) => println!("\nMessage created:\n{:#?}", resp),
         Err(error) => println!("Error while creating: {}", error),
     }
@@ -13,10 +13,11 @@
 //!     Message ID: 22223333
 
 use std::{env, process};
+use yadwh::snowflake::{MessageId, WebhookId};
 use yadwh::webhook::WebhookApi;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), yadwh::WebhookError> {
     // Verify enough arguments were passed.
     let args: Vec<String> = env::args().collect();
     if args.len() < 4 {
@@ -26,15 +27,17 @@ async fn main() {
     }
 
     // Parse the arguments.
-    let webhook_id: String = args[1].to_string();
+    let webhook_id: WebhookId = args[1].parse()?;
     let token: String = args[2].to_string();
-    let message_id: String = args[3].to_string();
+    let message_id: MessageId = args[3].parse()?;
 
     // Delete the message.
     println!("Deleting message {}.", message_id);
-    let webhook = WebhookApi::new(&webhook_id, &token);
-    match webhook.message.delete(&message_id).await {
+    let webhook = WebhookApi::new(webhook_id, &token);
+    match webhook.message.delete(message_id).await {
         Ok(_) => println!("Deleted message {}", message_id),
         Err(error) => println!("Error while deleting: {}", error),
     }
+
+    Ok(())
 }
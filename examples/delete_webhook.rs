@@ -12,10 +12,11 @@
 //!     Token:      aaaabbbb
 
 use std::{env, process};
+use yadwh::snowflake::WebhookId;
 use yadwh::webhook::WebhookApi;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), yadwh::WebhookError> {
     // Verify enough arguments were passed.
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
@@ -25,14 +26,16 @@ async fn main() {
     }
 
     // Parse the arguments.
-    let webhook_id: String = args[1].to_string();
+    let webhook_id: WebhookId = args[1].parse()?;
     let token: String = args[2].to_string();
 
     // Delete the webhook.
     println!("Deleting webhook {}.", webhook_id);
-    let webhook = WebhookApi::new(&webhook_id, &token);
+    let webhook = WebhookApi::new(webhook_id, &token);
     match webhook.delete().await {
         Ok(_) => println!("Deleted webhook {}.", webhook_id),
         Err(error) => println!("Error while deleting: {}", error),
     }
+
+    Ok(())
 }
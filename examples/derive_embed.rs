@@ -0,0 +1,67 @@
+//! # Derive Embed Example
+//!
+//! This example demonstrates how to derive `IntoEmbed` for an event, converting it into an embed
+//! that is previewed within the terminal. Nothing is sent.
+//!
+//! ## Example
+//!
+//! cargo run --example derive_embed --features examples,derive
+
+use std::time::SystemTime;
+use yadwh::color::Color;
+use yadwh::embed::IntoEmbed;
+use yadwh::message::MessageBuilder;
+use yadwh::preview::Terminal;
+
+/// Event reported when a deployment finishes.
+#[derive(IntoEmbed)]
+#[embed(author = "Deploy Bot", color = "#57F287", inline)]
+struct Deployment {
+    /// Used as the title of the embed.
+    #[embed(title, format = "Deployed {}")]
+    service: String,
+    /// Used as the description, only when provided.
+    #[embed(description)]
+    notes: Option<String>,
+    /// Field named "Version", formatted with a prefix.
+    #[embed(rename = "Version", format = "v{}")]
+    version: String,
+    /// Field named "Duration secs".
+    #[embed(format = "{:.1}s")]
+    duration_secs: f64,
+    /// Field that is not inline.
+    #[embed(inline = false)]
+    commit: String,
+    /// Overrides the color set on the struct.
+    #[embed(color)]
+    color: Color,
+    /// Timestamp of the embed.
+    #[embed(timestamp)]
+    finished: SystemTime,
+    /// Left out of the embed.
+    #[embed(skip)]
+    #[allow(dead_code)]
+    internal_id: u64,
+}
+
+fn main() -> Result<(), yadwh::WebhookError> {
+    let event = Deployment {
+        service: "payments-api".to_string(),
+        notes: Some("Rolled out to **all** regions.".to_string()),
+        version: "1.4.2".to_string(),
+        duration_secs: 83.25,
+        commit: "9f3c2ab".to_string(),
+        color: Color::new(0x5865F2),
+        finished: SystemTime::now(),
+        internal_id: 42,
+    };
+
+    // The embed always passes validation, values that are too long are clipped.
+    let embed = event.to_embed();
+    embed.validate()?;
+
+    let message = MessageBuilder::new().embeds(&[embed]);
+    Terminal::new().print(&message);
+
+    Ok(())
+}
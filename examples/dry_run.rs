@@ -0,0 +1,63 @@
+//! # Dry Run Example
+//!
+//! This example demonstrates how to validate and serialize messages without sending anything to
+//! Discord. Synthetic messages are returned and every would-be request is captured. Any Webhook ID
+//! and Token can be provided, they are never used.
+//!
+//! ## Example
+//!
+//! cargo run --example dry_run --features examples -- 00001111 aaaabbbb
+//! where:
+//!     Webhook ID: 00001111
+//!     Token:      aaaabbbb
+
+use std::{env, process};
+use yadwh::dryrun::DryRun;
+use yadwh::message::MessageBuilder;
+use yadwh::snowflake::WebhookId;
+use yadwh::webhook::WebhookApi;
+
+#[tokio::main]
+async fn main() -> Result<(), yadwh::WebhookError> {
+    // Verify enough arguments were passed.
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        println!("error:  not enough arguments supplied.");
+        println!("usage:  dry_run [webhook_id] [token]");
+        process::exit(-1);
+    }
+
+    // Parse the arguments.
+    let webhook_id: WebhookId = args[1].parse()?;
+    let token: String = args[2].to_string();
+
+    // Keep a clone of the dry run to inspect the captured requests.
+    let dry_run = DryRun::new();
+    let webhook = WebhookApi::new(webhook_id, &token).transport(dry_run.clone());
+
+    // Message to be sent.
+    let message = MessageBuilder::new()
+        .username("Webhook Example")?
+        .content("Content portion of the message.")?
+        .embed(|embed| embed.title("Title Here").description("Description Here"));
+
+    // Create, edit, and delete the message without sending anything.
+    let created = webhook.message.create(&message, None).await?;
+    println!("Message created:\n{:#?}", created);
+
+    let edited = MessageBuilder::new().content("Edited content.")?;
+    webhook.message.edit(created.id, &edited).await?;
+    webhook.message.delete(created.id).await?;
+
+    println!("\nRequests captured:");
+    for request in dry_run.captured() {
+        println!(
+            "{} {}\n  {}",
+            request.operation.name(),
+            request.url,
+            request.body
+        );
+    }
+
+    Ok(())
+}
@@ -13,7 +13,9 @@
 //!     Message ID: 22223333
 
 use std::{env, process};
+use yadwh::color::Color;
 use yadwh::message::MessageBuilder;
+use yadwh::snowflake::{MessageId, WebhookId};
 use yadwh::webhook::WebhookApi;
 
 #[tokio::main]
@@ -27,14 +29,14 @@ async fn main() -> Result<(), yadwh::WebhookError> {
     }
 
     // Parse the arguments.
-    let webhook_id: String = args[1].to_string();
+    let webhook_id: WebhookId = args[1].parse()?;
     let token: String = args[2].to_string();
-    let message_id: String = args[3].to_string();
+    let message_id: MessageId = args[3].parse()?;
 
     // Get the original message.
     println!("Obtaining message {}.", message_id);
-    let webhook = WebhookApi::new(&webhook_id, &token);
-    let message = match webhook.message.get(&message_id).await {
+    let webhook = WebhookApi::new(webhook_id, &token);
+    let message = match webhook.message.get(message_id).await {
         Ok(resp) => {
             println!("Message obtained.");
             resp
@@ -45,13 +47,16 @@ async fn main() -> Result<(), yadwh::WebhookError> {
         }
     };
 
+    // Color of the embed.
+    let color = Color::from_hex("#cba6f7")?;
+
     // Create a builder from the message to make changes.
     let message = MessageBuilder::from(&message)?
         .username("Webhook Example")?
         .content("New content portion of the message.")?
         .embed(|embed| {
             embed
-                .color("#cba6f7")
+                .color(color)
                 .author("Author Changed Here", None, None, None)
                 .title("Title Changed Here")
                 .description("Description Changed Here\n```rust\nprintln!(\"Hello World!\");```")
@@ -64,8 +69,8 @@ async fn main() -> Result<(), yadwh::WebhookError> {
 
     // Edit the message.
     println!("Editing message {}.", message_id);
-    let webhook = WebhookApi::new(&webhook_id, &token);
-    match webhook.message.edit(&message_id, &message).await {
+    let webhook = WebhookApi::new(webhook_id, &token);
+    match webhook.message.edit(message_id, &message).await {
         Ok(resp) => println!("\nMessage edited:\n{:#?}", resp),
         Err(error) => println!("Error while editing: {}", error),
     }
@@ -0,0 +1,59 @@
+//! # Failover Example
+//!
+//! This example demonstrates how to fail over from a primary webhook to a backup when the primary
+//! is deleted, how the primary is skipped while it cools down, and how it is used again afterwards.
+//! Each webhook is an in-process mock server provided by the `testing` feature.
+//!
+//! ## Example
+//!
+//! cargo run --example failover --features examples,testing
+
+use std::time::Duration;
+use yadwh::failover::Failover;
+use yadwh::group::Target;
+use yadwh::message::MessageBuilder;
+use yadwh::testing::{MockServer, Scripted};
+
+#[tokio::main]
+async fn main() -> Result<(), yadwh::WebhookError> {
+    let primary = MockServer::start().await?;
+    let backup = MockServer::start().await?;
+
+    let failover = Failover::new(Target::new("primary", primary.client()))
+        .backup(Target::new("backup", backup.client()).username("Alerts (backup)"))
+        .cooldown(Duration::from_millis(500));
+    let message = MessageBuilder::new().content("Disk usage above 90% on db-1.")?;
+
+    // The primary webhook was deleted, the backup delivers the message.
+    primary.script(Scripted::Error {
+        status: 404,
+        code: 10015,
+        message: "Unknown Webhook".to_string(),
+    });
+    for attempt in 1..=3 {
+        if attempt == 3 {
+            println!("Waiting for the cool-down to pass.");
+            tokio::time::sleep(Duration::from_millis(600)).await;
+        }
+
+        let routed = failover.send(&message).await?;
+        println!(
+            "Send {}: delivered by {} (backup: {}), message {}",
+            attempt,
+            routed.target,
+            routed.is_backup(),
+            routed.message.id
+        );
+        for attempt in &routed.attempts {
+            println!(
+                "  {} failed ({:?}): {}",
+                attempt.target, attempt.class, attempt.error
+            );
+        }
+        for (target, remaining) in failover.unhealthy() {
+            println!("  {} cooling down for {:?}", target, remaining);
+        }
+    }
+
+    Ok(())
+}
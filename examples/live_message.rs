@@ -0,0 +1,74 @@
+//! # Live Message Example
+//!
+//! This example demonstrates how to post a status message and keep editing it as a deploy
+//! progresses. Rapid updates are coalesced so only a few edits reach the API. The message is sent
+//! to the in-process mock server provided by the `testing` feature.
+//!
+//! ## Example
+//!
+//! cargo run --example live_message --features examples,testing
+
+use std::time::Duration;
+use yadwh::color::Color;
+use yadwh::message::MessageBuilder;
+use yadwh::testing::MockServer;
+
+#[tokio::main]
+async fn main() -> Result<(), yadwh::WebhookError> {
+    let server = MockServer::start().await?;
+    let webhook = server.client();
+
+    let message = MessageBuilder::new().embed(|embed| {
+        embed
+            .title("Deploying api v2.4.0")
+            .description("Starting...")
+            .color(Color::new(0xFEE75C))
+    });
+    let mut live = webhook
+        .message
+        .create_live(&message, None)
+        .await?
+        .interval(Duration::from_millis(200));
+    println!("Posted message {}.", live.id());
+
+    // Steps finishing faster than the interval are coalesced into fewer edits.
+    let steps = [
+        "Building",
+        "Testing",
+        "Uploading",
+        "Migrating",
+        "Restarting",
+    ];
+    for (i, step) in steps.iter().enumerate() {
+        tokio::time::sleep(Duration::from_millis(70)).await;
+        let description = format!("{} ({}/{})", step, i + 1, steps.len());
+        let edited = live
+            .update(|message| {
+                message.embeds[0].description(&description);
+            })
+            .await?;
+        println!("{:<16} edited: {}", description, edited);
+    }
+
+    let message = live
+        .finish(|message| {
+            message.embeds[0]
+                .description("Deployed.")
+                .color(Color::new(0x57F287));
+        })
+        .await?;
+    println!("Final description: {:?}", message.embeds[0].description);
+
+    let edits = server
+        .requests()
+        .iter()
+        .filter(|request| request.method == "PATCH")
+        .count();
+    println!(
+        "{} updates were sent with {} edits.",
+        steps.len() + 1,
+        edits
+    );
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! # Load Message Example
+//!
+//! This example demonstrates how to load a message from a JSON, TOML, or YAML document and preview
+//! it within the terminal. Documents use the same shape as Discord's webhook JSON.
+//!
+//! ## Example
+//!
+//! cargo run --example load_message --features examples,toml,yaml -- message.yaml
+//! where:
+//!     Document: message.yaml
+
+use std::{env, process};
+use yadwh::load::Format;
+use yadwh::message::MessageBuilder;
+use yadwh::preview::Terminal;
+
+/// Message used when no document is provided, as pasted from another tool.
+const PAYLOAD: &str = r##"{
+    "username": "Status",
+    "content": "Nightly build finished.",
+    "embeds": [{
+        "title": "Build **#512**",
+        "color": "#57F287",
+        "fields": [
+            { "name": "Duration", "value": "12m 4s", "inline": true },
+            { "name": "Tests", "value": "1,204 passed", "inline": true }
+        ],
+        "footer": { "text": "ci.example.com" }
+    }],
+    "allowed_mentions": { "parse": [] }
+}"##;
+
+fn main() -> Result<(), yadwh::WebhookError> {
+    // Load the document provided, or the built-in payload.
+    let args: Vec<String> = env::args().collect();
+    let message = match args.get(1) {
+        Some(path) => MessageBuilder::load(path),
+        None => MessageBuilder::parse(PAYLOAD, Format::Json),
+    };
+
+    match message {
+        Ok(message) => Terminal::new().print(&message),
+        Err(error) => {
+            println!("Error while loading: {}", error);
+            process::exit(-1);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! # Markdown Example
+//!
+//! This example demonstrates how to build message content from untrusted values with Discord
+//! markdown, escaping the values and staying within the content limit. Nothing is sent.
+//!
+//! ## Example
+//!
+//! cargo run --example markdown --features examples
+
+use yadwh::markdown::{self, Markdown};
+use yadwh::message::MessageBuilder;
+use yadwh::preview::Terminal;
+
+fn main() -> Result<(), yadwh::WebhookError> {
+    // Values from users that contain markdown characters.
+    let user = "__dev_ops__";
+    let comment = "restarting *now*, ~5 min";
+    let failures = [
+        "disk *full* on db-1",
+        "> 90% CPU on api-2",
+        "`cron` stalled",
+    ];
+
+    let mut content = Markdown::new()
+        .line(&markdown::header(2, "Incident report"))
+        .line("Opened by ")
+        .push(&markdown::bold(&markdown::escape(user)))
+        .line(&markdown::subtext("Generated automatically"));
+
+    // Add as many failures as fit within the limit.
+    for failure in failures {
+        let item = markdown::list(&[markdown::escape(failure)]);
+        if !content.fits(&item) {
+            break;
+        }
+        content = content.line(&item);
+    }
+
+    let content = content
+        .line(&markdown::code_block(Some("sh"), "systemctl restart api"))
+        .line("Last comment: ")
+        .text(comment)
+        .line(&markdown::link("Runbook", "https://example.com/runbook"))
+        .build()?;
+
+    println!("{}\n", content);
+    Terminal::new().print(&MessageBuilder::new().content(&content)?);
+
+    Ok(())
+}
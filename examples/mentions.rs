@@ -0,0 +1,47 @@
+//! # Mentions Example
+//!
+//! This example demonstrates how to mention users, roles, channels, and commands, display custom
+//! emoji and timestamps, and audit who a message will ping before it is sent. Nothing is sent.
+//!
+//! ## Example
+//!
+//! cargo run --example mentions --features examples
+
+use std::time::{Duration, SystemTime};
+use yadwh::mention::{self, TimestampStyle};
+use yadwh::message::MessageBuilder;
+use yadwh::snowflake::{ChannelId, CommandId, EmojiId, RoleId, UserId};
+
+fn main() -> Result<(), yadwh::WebhookError> {
+    let maintenance = SystemTime::now() + Duration::from_secs(2 * 60 * 60);
+
+    let content = format!(
+        "{} {} maintenance starts {} ({}).\nFollow along in {} or run {} for details.\nSpoilers still ping ||@everyone||, code such as `@here` and escaped mentions such as \\<@1> do not.",
+        mention::emoji("warning_light", EmojiId::from(112233445566778899), true),
+        mention::role(RoleId::from(223344556677889900)),
+        mention::timestamp(maintenance, TimestampStyle::Relative),
+        mention::timestamp(maintenance, TimestampStyle::LongDateTime),
+        mention::channel(ChannelId::from(334455667788990011)),
+        mention::command("status show", CommandId::from(445566778899001122)),
+    );
+    let message = MessageBuilder::new().content(&format!(
+        "{} {}",
+        mention::user(UserId::from(556677889900112233)),
+        content
+    ))?;
+
+    println!("{}\n", message.content.as_deref().unwrap_or_default());
+
+    // Every style for the same time.
+    for style in TimestampStyle::ALL {
+        println!("{:?}: {}", style, mention::timestamp(maintenance, style));
+    }
+
+    println!();
+    for found in message.mentions() {
+        let pings = if found.pings() { "pings" } else { "" };
+        println!("{:<60} {}", found.to_string(), pings);
+    }
+
+    Ok(())
+}
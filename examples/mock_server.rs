@@ -0,0 +1,55 @@
+//! # Mock Server Example
+//!
+//! This example demonstrates how to exercise the client against the in-process mock of the
+//! Discord webhook API provided by the `testing` feature, without sending anything to Discord.
+//!
+//! ## Example
+//!
+//! cargo run --example mock_server --features examples,testing
+
+use std::time::Duration;
+use yadwh::message::MessageBuilder;
+use yadwh::testing::{MockServer, Scripted};
+
+#[tokio::main]
+async fn main() -> Result<(), yadwh::WebhookError> {
+    // Start the server, the client is already pointed at it.
+    let server = MockServer::start().await?;
+    let webhook = server.client().max_retries(1);
+
+    // Message to be sent.
+    let message = MessageBuilder::new()
+        .username("Webhook Example")?
+        .content("Content portion of the message.")?
+        .embed(|embed| embed.title("Title Here").description("Description Here"));
+
+    // Create, edit, and obtain the message.
+    let created = webhook.message.create(&message, None).await?;
+    let edited = MessageBuilder::new().content("Edited content.")?;
+    webhook.message.edit(created.id, &edited).await?;
+    let obtained = webhook.message.get(created.id).await?;
+    println!("Message obtained:\n{:#?}", obtained);
+
+    // Scripted responses are returned before the request is handled.
+    server.script(Scripted::RateLimited {
+        retry_after: Duration::from_millis(100),
+        global: false,
+    });
+    server.script(Scripted::Error {
+        status: 404,
+        code: 10008,
+        message: "Unknown Message".to_string(),
+    });
+    match webhook.message.delete(created.id).await {
+        Ok(_) => println!("\nMessage deleted."),
+        Err(error) => println!("\nScripted error: {}", error),
+    }
+
+    // Requests are recorded, with the token masked.
+    println!("\nRequests received:");
+    for request in server.requests() {
+        println!("{} {} -> {}", request.method, request.path, request.status);
+    }
+
+    Ok(())
+}
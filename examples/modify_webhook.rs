@@ -12,10 +12,11 @@
 //!     Token:      aaaabbbb
 
 use std::{env, process};
+use yadwh::snowflake::WebhookId;
 use yadwh::webhook::WebhookApi;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), yadwh::WebhookError> {
     // Verify enough arguments were passed.
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
@@ -25,12 +26,12 @@ async fn main() {
     }
 
     // Parse the arguments.
-    let webhook_id: String = args[1].to_string();
+    let webhook_id: WebhookId = args[1].parse()?;
     let token: String = args[2].to_string();
 
     // Get the webhook.
     println!("Obtaining webhook {}.", webhook_id);
-    let webhook = WebhookApi::new(&webhook_id, &token);
+    let webhook = WebhookApi::new(webhook_id, &token);
     let mut old = match webhook.get().await {
         Ok(resp) => {
             println!("Webhook obtained.");
@@ -51,4 +52,6 @@ async fn main() {
         Ok(resp) => println!("\nWebhook edited:\n{:#?}", resp),
         Err(error) => println!("Error while editing: {}", error),
     }
+
+    Ok(())
 }
@@ -0,0 +1,46 @@
+//! # Preview Example
+//!
+//! This example demonstrates how to preview a message within the terminal before sending it, and
+//! how to export the same preview as HTML. Nothing is sent to Discord.
+//!
+//! ## Example
+//!
+//! cargo run --example preview --features examples
+
+use yadwh::color::Color;
+use yadwh::component::Component;
+use yadwh::message::MessageBuilder;
+use yadwh::preview::{Html, Terminal};
+use yadwh::timestamp::Timestamp;
+
+fn main() -> Result<(), yadwh::WebhookError> {
+    // Message to be previewed.
+    let message = MessageBuilder::new()
+        .username("Webhook Example")?
+        .content("Content with **bold**, *italics*, and `inline code`.")?
+        .embed(|embed| {
+            embed
+                .color(Color::BLURPLE)
+                .author("Author Here", None, None, None)
+                .title("Title Here")
+                .description("Description Here\n```rust\nprintln!(\"Hello World!\");\n```")
+                .field("Field1", "Value1", None)
+                .field("Inline Field1", "Value1", Some(true))
+                .field("Inline Field2", "Value2", Some(true))
+                .field("Inline Field3", "Value3", Some(true))
+                .field("Inline Field4", "A longer value that wraps.", Some(true))
+                .footer("Footer Here", None, None)
+                .timestamp(Timestamp::now())
+        })
+        .row(vec![Component::link("Open", "https://discord.com")]);
+
+    Terminal::new().print(&message);
+
+    // Export the preview as an HTML document.
+    let path = std::env::temp_dir().join("yadwh_preview.html");
+    match std::fs::write(&path, Html::new().document(&message)) {
+        Ok(_) => println!("\nHTML preview written to {}", path.display()),
+        Err(error) => println!("\nUnable to write HTML preview: {}", error),
+    }
+    Ok(())
+}
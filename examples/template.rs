@@ -0,0 +1,76 @@
+//! # Template Example
+//!
+//! This example demonstrates how to render an alert from a template, expanding a list of failures
+//! into embed fields and previewing the result within the terminal. Nothing is sent.
+//!
+//! ## Example
+//!
+//! cargo run --example template --features examples
+
+use serde::Serialize;
+use yadwh::load::Format;
+use yadwh::preview::Terminal;
+use yadwh::template::Template;
+
+/// Layout of the alert, in the same shape as Discord's webhook JSON.
+const LAYOUT: &str = r##"{
+    "username": "{{service}} monitor",
+    "content": "**{{service}}** is failing {{failures.length}} checks.",
+    "embeds": [{
+        "title": "{{service}} ({{region}})",
+        "color": "{{color}}",
+        "description": "Affected hosts:\n{{#each hosts}}- `{{this}}`\n{{/each}}",
+        "fields": [
+            { "{{#each failures}}": { "name": "{{check}}", "value": "{{reason}}", "inline": true } }
+        ],
+        "footer": { "text": "Alert #{{id}}" }
+    }]
+}"##;
+
+/// Values for the placeholders within the layout.
+#[derive(Serialize)]
+struct Alert {
+    id: u32,
+    service: &'static str,
+    region: &'static str,
+    color: u32,
+    hosts: Vec<&'static str>,
+    failures: Vec<Failure>,
+}
+
+/// Single failing check.
+#[derive(Serialize)]
+struct Failure {
+    check: &'static str,
+    reason: &'static str,
+}
+
+fn main() -> Result<(), yadwh::WebhookError> {
+    let template = Template::parse(LAYOUT, Format::Json)?;
+
+    let alert = Alert {
+        id: 512,
+        service: "payments_api",
+        region: "us-east",
+        color: 0xED4245,
+        hosts: vec!["api-1", "api-2"],
+        failures: vec![
+            Failure {
+                check: "Latency",
+                reason: "p99 at *1.8s*",
+            },
+            Failure {
+                check: "Errors",
+                reason: "5xx rate 4%",
+            },
+        ],
+    };
+
+    // Values are escaped, so `payments_api` and `*1.8s*` are displayed as written.
+    match template.render(&alert) {
+        Ok(message) => Terminal::new().print(&message),
+        Err(error) => println!("Error while rendering: {}", error),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! # Webhook Group Example
+//!
+//! This example demonstrates how to mirror a message into several webhooks at once, with
+//! per-target overrides and both failure policies. Each webhook is an in-process mock server
+//! provided by the `testing` feature, one of which is scripted to fail.
+//!
+//! ## Example
+//!
+//! cargo run --example webhook_group --features examples,testing
+
+use yadwh::group::{GroupPolicy, Outcome, Target, WebhookGroup};
+use yadwh::message::MessageBuilder;
+use yadwh::testing::{MockServer, Scripted};
+
+#[tokio::main]
+async fn main() -> Result<(), yadwh::WebhookError> {
+    let primary = MockServer::start().await?;
+    let partner = MockServer::start().await?;
+    let archive = MockServer::start().await?;
+
+    let group = || {
+        WebhookGroup::new()
+            .concurrency(2)
+            .webhook("primary", primary.client())
+            .target(Target::new("partner", partner.client()).username("Partner Alerts"))
+            .target(Target::new("archive", archive.client()).thread(archive.channel_id()))
+    };
+    let message = MessageBuilder::new().content("Database failover completed.")?;
+
+    // Every target succeeds.
+    let report = group().send(&message).await;
+    println!("Delivered to all: {}", report.is_success());
+    for (target, message) in report.delivered() {
+        println!("  {:<8} message {}", target, message.id);
+    }
+
+    // Best effort, the partner webhook was deleted.
+    let deleted = || Scripted::Error {
+        status: 404,
+        code: 10015,
+        message: "Unknown Webhook".to_string(),
+    };
+    partner.script(deleted());
+    let report = group().send(&message).await;
+    println!("\nBest effort, delivered to all: {}", report.is_success());
+    for (target, error) in report.failed() {
+        println!("  {:<8} failed: {}", target, error);
+    }
+
+    // All or nothing, deliveries are deleted when any target fails.
+    partner.script(deleted());
+    let report = group()
+        .policy(GroupPolicy::AllOrNothing)
+        .send(&message)
+        .await;
+    println!("\nAll or nothing:");
+    for delivery in &report.deliveries {
+        let outcome = match &delivery.outcome {
+            Outcome::Delivered(message) => format!("delivered {}", message.id),
+            Outcome::Failed(error) => format!("failed: {}", error),
+            Outcome::Skipped => "skipped".to_string(),
+            Outcome::RolledBack(message) => format!("rolled back {}", message.id),
+        };
+        println!("  {:<8} {}", delivery.target, outcome);
+    }
+    println!("Messages left on primary: {}", primary.messages().len());
+
+    Ok(())
+}
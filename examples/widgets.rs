@@ -0,0 +1,70 @@
+//! # Widgets Example
+//!
+//! This example demonstrates the progress bar, checklist, and status table widgets by rendering a
+//! deploy report at each step and previewing it within the terminal. Nothing is sent, with a
+//! `LiveMessage` the same embed would be pushed on every change.
+//!
+//! ## Example
+//!
+//! cargo run --example widgets --features examples
+
+use std::time::Duration;
+use yadwh::embed::Embed;
+use yadwh::message::MessageBuilder;
+use yadwh::preview::Terminal;
+use yadwh::widget::{Checklist, ProgressBar, StatusTable, StepStatus};
+
+/// Creates the embed for the current state of the deploy.
+fn report(progress: &ProgressBar, checklist: &Checklist, status: &StatusTable) -> Embed {
+    let mut embed = Embed::new();
+    embed
+        .title("Deploying api v2.4.0")
+        .description(&checklist.render())
+        .field("Progress", &progress.render(), None);
+    status.apply(&mut embed);
+    embed
+}
+
+fn main() -> Result<(), yadwh::WebhookError> {
+    let steps = ["Build", "Test", "Upload", "Migrate", "Restart"];
+
+    let mut checklist = Checklist::new();
+    for step in steps {
+        checklist.step(step);
+    }
+
+    let mut progress = ProgressBar::new(steps.len() as u64);
+    progress.label("Overall");
+
+    let mut status = StatusTable::new();
+    status
+        .columns(2)
+        .set("Environment", "production")
+        .set("Commit", "`9f3c2ab`")
+        .set("Region", "eu-west-1");
+
+    for (i, _) in steps.iter().enumerate() {
+        checklist.set(i, StepStatus::Running);
+        std::thread::sleep(Duration::from_millis(50));
+
+        match i {
+            3 => {
+                checklist
+                    .set(i, StepStatus::Failed)
+                    .note(i, "lock timeout, retrying");
+                status.set("Retries", "1");
+            }
+            _ => {
+                checklist.set(i, StepStatus::Done).note(i, "50ms");
+            }
+        }
+        progress.advance(1);
+
+        println!("Step {}:", i + 1);
+        let embed = report(&progress, &checklist, &status);
+        embed.validate()?;
+        Terminal::new().print(&MessageBuilder::new().embeds(&[embed]));
+    }
+
+    Ok(())
+}
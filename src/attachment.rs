@@ -0,0 +1,145 @@
+//! Files uploaded with messages, and the attachments Discord returns for them.
+//!
+//! `attachment` contains `File`, which is added to a `MessageBuilder` to be uploaded alongside the
+//! message as `multipart/form-data`, and `Attachment`, which describes an uploaded file within a
+//! `Message`. Up to 10 files can be uploaded per message.
+//!
+//! ## References / Documentation
+//!
+//! <https://discord.com/developers/docs/reference#uploading-files>
+
+use crate::client::{Result, WebhookError};
+use crate::snowflake::Snowflake;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// File to be uploaded with a message.
+#[derive(Clone, PartialEq, Eq)]
+pub struct File {
+    /// Name of the file, such as `report.png`.
+    pub filename: String,
+    /// MIME type of the file, such as `image/png`.
+    pub content_type: String,
+    /// Description of the file (alt text).
+    pub description: Option<String>,
+    /// Contents of the file.
+    pub data: Vec<u8>,
+}
+
+impl File {
+    /// Creates a file from its contents, guessing the MIME type from the extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - Name of the file, such as `report.png`.
+    /// * `data` - Contents of the file.
+    pub fn new(filename: &str, data: Vec<u8>) -> Self {
+        Self {
+            filename: filename.to_string(),
+            content_type: content_type(filename).to_string(),
+            description: None,
+            data,
+        }
+    }
+
+    /// Reads a file from disk, guessing the MIME type from the extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the file to read.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(|error| {
+            WebhookError::Unknown(format!("reading {}, {}", path.display(), error))
+        })?;
+
+        let filename = match path.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => {
+                return Err(WebhookError::BadParse(format!(
+                    "file name of {}",
+                    path.display()
+                )))
+            }
+        };
+
+        Ok(Self::new(&filename, data))
+    }
+
+    /// Sets the description of the file (alt text).
+    ///
+    /// # Arguments
+    ///
+    /// * `description` - Description of the file.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+}
+
+/// Contents of the file are omitted.
+impl fmt::Debug for File {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("File")
+            .field("filename", &self.filename)
+            .field("content_type", &self.content_type)
+            .field("description", &self.description)
+            .field("size", &self.data.len())
+            .finish()
+    }
+}
+
+/// File attached to a message received from the Discord API.
+///
+/// ## References / Documentation
+///
+/// <https://discord.com/developers/docs/resources/channel#attachment-object>
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Attachment {
+    /// ID of the attachment.
+    pub id: Snowflake,
+    /// Name of the file.
+    pub filename: String,
+    /// Description of the file (alt text).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type of the file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Source URL of the file.
+    pub url: String,
+    /// A proxied URL of the file.
+    pub proxy_url: String,
+}
+
+/// Guesses the MIME type of a file from its extension.
+fn content_type(filename: &str) -> &'static str {
+    let extension = match filename.rsplit_once('.') {
+        Some((_, extension)) => extension.to_ascii_lowercase(),
+        None => return "application/octet-stream",
+    };
+
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
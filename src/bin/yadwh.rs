@@ -0,0 +1,553 @@
+//! # yadwh
+//!
+//! Command-line tool for managing Discord webhooks and their messages.
+//!
+//! The webhook is resolved in the following order: `--url`, `--profile`, the `YADWH_WEBHOOK_URL`
+//! environment variable, then the `default` profile of the configuration file. The configuration
+//! file is read from `--config`, `$XDG_CONFIG_HOME/yadwh/config.toml`, or
+//! `~/.config/yadwh/config.toml`:
+//!
+//! ```toml
+//! default = "alerts"
+//!
+//! [profiles.alerts]
+//! url = "https://discord.com/api/webhooks/111122223333/AAAABBBBCCCC"
+//! thread_id = "444455556666" # Optional.
+//! ```
+//!
+//! ## Example
+//!
+//! yadwh message send --content "Deployed." --title "Release" --field "Version=1.2.3" --attach notes.md
+//! yadwh --json message get 555566667777 | jq .content
+//! echo "Build failed." | yadwh --profile ci message send --stdin
+
+use clap::{Args, Parser, Subcommand};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use yadwh::attachment::File;
+use yadwh::color::Color;
+use yadwh::embed::Embed;
+use yadwh::load::Format;
+use yadwh::message::{Message, MessageBuilder};
+use yadwh::preview::Terminal;
+use yadwh::secret::REDACTED;
+use yadwh::snowflake::{ChannelId, MessageId};
+use yadwh::webhook::{Webhook, WebhookApi};
+use yadwh::{Result, WebhookError, WebhookUrl};
+
+/// Environment variable containing the URL of the webhook.
+const URL_VARIABLE: &str = "YADWH_WEBHOOK_URL";
+
+/// Manage Discord webhooks and their messages.
+#[derive(Parser)]
+#[command(name = "yadwh", version, about)]
+struct Cli {
+    /// URL of the webhook, takes priority over profiles and the environment.
+    #[arg(long, global = true)]
+    url: Option<String>,
+
+    /// Profile within the configuration file to use.
+    #[arg(long, short, global = true)]
+    profile: Option<String>,
+
+    /// Path of the configuration file.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Print responses as JSON for scripting.
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Base URL of the API, such as a proxy or mock server used in place of Discord.
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// Maximum amount of retries when rate limited.
+    #[arg(long, global = true, default_value_t = 3)]
+    retries: u32,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Get, modify, or delete the webhook.
+    #[command(subcommand)]
+    Webhook(WebhookCommand),
+    /// Send, get, edit, or delete messages.
+    #[command(subcommand)]
+    Message(MessageCommand),
+}
+
+#[derive(Subcommand)]
+enum WebhookCommand {
+    /// Obtain the webhook.
+    Get,
+    /// Change the default name or avatar of the webhook.
+    Modify {
+        /// New default name of the webhook.
+        #[arg(long)]
+        name: Option<String>,
+        /// Image used as the new default avatar of the webhook.
+        #[arg(long)]
+        avatar: Option<PathBuf>,
+    },
+    /// Delete the webhook, this cannot be undone.
+    Delete {
+        /// Confirm the deletion.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum MessageCommand {
+    /// Send a new message.
+    Send {
+        #[command(flatten)]
+        message: Box<MessageArgs>,
+        /// Thread to send the message within.
+        #[arg(long)]
+        thread: Option<ChannelId>,
+    },
+    /// Obtain a message, previewing it unless `--json` is used.
+    Get {
+        /// ID of the message.
+        id: MessageId,
+    },
+    /// Edit a message, replacing only the values provided.
+    Edit {
+        /// ID of the message.
+        id: MessageId,
+        #[command(flatten)]
+        message: Box<MessageArgs>,
+    },
+    /// Delete a message.
+    Delete {
+        /// ID of the message.
+        id: MessageId,
+    },
+}
+
+/// Contents of a message, flags are applied on top of `--file`.
+#[derive(Args)]
+struct MessageArgs {
+    /// JSON, TOML, or YAML file containing the message, `-` reads JSON or TOML from standard input.
+    #[arg(long, short)]
+    file: Option<PathBuf>,
+
+    /// Contents of the message.
+    #[arg(long, short)]
+    content: Option<String>,
+
+    /// Read the contents of the message from standard input.
+    #[arg(long, conflicts_with = "content")]
+    stdin: bool,
+
+    /// Overrides the default username of the webhook.
+    #[arg(long, short)]
+    username: Option<String>,
+
+    /// Send as a Text-to-Speech message.
+    #[arg(long)]
+    tts: bool,
+
+    /// Title of the embed.
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Description of the embed.
+    #[arg(long)]
+    description: Option<String>,
+
+    /// Color of the embed, such as `#5865F2`.
+    #[arg(long)]
+    color: Option<Color>,
+
+    /// Field of the embed as `name=value`, repeatable.
+    #[arg(long = "field", value_name = "NAME=VALUE")]
+    fields: Vec<String>,
+
+    /// Inline field of the embed as `name=value`, repeatable.
+    #[arg(long = "inline-field", value_name = "NAME=VALUE")]
+    inline_fields: Vec<String>,
+
+    /// Footer of the embed.
+    #[arg(long)]
+    footer: Option<String>,
+
+    /// URL of the embed image.
+    #[arg(long)]
+    image: Option<String>,
+
+    /// URL of the embed thumbnail.
+    #[arg(long)]
+    thumbnail: Option<String>,
+
+    /// File to upload with the message, repeatable.
+    #[arg(long = "attach", value_name = "PATH")]
+    attachments: Vec<PathBuf>,
+}
+
+impl MessageArgs {
+    /// Builds the message from the file and flags provided.
+    fn build(&self) -> Result<MessageBuilder> {
+        let mut message = match &self.file {
+            Some(path) => load(path)?,
+            None => MessageBuilder::new(),
+        };
+
+        if let Some(content) = &self.content {
+            message = message.content(content)?;
+        }
+        if self.stdin {
+            let content = read_stdin()?;
+            message = message.content(content.trim_end())?;
+        }
+        if let Some(username) = &self.username {
+            message = message.username(username)?;
+        }
+        if self.tts {
+            message = message.tts(true);
+        }
+
+        if let Some(embed) = self.embed()? {
+            message.embeds.push(embed);
+        }
+
+        for path in self.attachments.iter() {
+            message = message.file(File::open(path)?);
+        }
+
+        Ok(message)
+    }
+
+    /// Builds the embed described by the flags, if any were provided.
+    fn embed(&self) -> Result<Option<Embed>> {
+        let mut embed = Embed::new();
+        let mut used = false;
+
+        if let Some(title) = &self.title {
+            embed.title(title);
+            used = true;
+        }
+        if let Some(description) = &self.description {
+            embed.description(description);
+            used = true;
+        }
+        if let Some(color) = self.color {
+            embed.color(color);
+            used = true;
+        }
+
+        let fields = self.fields.iter().map(|field| (field, None));
+        let inline = self.inline_fields.iter().map(|field| (field, Some(true)));
+        for (field, inline) in fields.chain(inline) {
+            let (name, value) = match field.split_once('=') {
+                Some(value) => value,
+                None => {
+                    return Err(WebhookError::BadParse(format!(
+                        "field '{}', expected name=value",
+                        field
+                    )))
+                }
+            };
+            embed.field(name, value, inline);
+            used = true;
+        }
+
+        if let Some(footer) = &self.footer {
+            embed.footer(footer, None, None);
+            used = true;
+        }
+        if let Some(image) = &self.image {
+            embed.image(Some(image.clone()), None, None, None);
+            used = true;
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            embed.thumbnail(Some(thumbnail.clone()), None, None, None);
+            used = true;
+        }
+
+        Ok(used.then_some(embed))
+    }
+}
+
+/// Configuration file containing named webhooks.
+#[derive(Deserialize, Default)]
+struct Config {
+    /// Profile used when none is selected.
+    default: Option<String>,
+    /// Webhooks by name.
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// Webhook within the configuration file.
+#[derive(Deserialize)]
+struct Profile {
+    /// URL of the webhook.
+    url: WebhookUrl,
+    /// Thread that messages are sent to, overrides one within the URL.
+    thread_id: Option<ChannelId>,
+}
+
+impl Config {
+    /// Loads the configuration file, missing files are only an error if the path was provided.
+    fn load(path: Option<&Path>) -> Result<Self> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match config_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) if !required && error.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default())
+            }
+            Err(error) => {
+                return Err(WebhookError::Unknown(format!(
+                    "reading {}, {}",
+                    path.display(),
+                    error
+                )))
+            }
+        };
+
+        toml::from_str(&text)
+            .map_err(|error| WebhookError::BadParse(format!("{}, {}", path.display(), error)))
+    }
+
+    /// Obtains a profile by name.
+    fn profile(&self, name: &str) -> Result<WebhookUrl> {
+        match self.profiles.get(name) {
+            Some(profile) => match profile.thread_id {
+                Some(thread_id) => Ok(profile.url.clone().with_thread(Some(thread_id))),
+                None => Ok(profile.url.clone()),
+            },
+            None => Err(WebhookError::Unknown(format!("unknown profile '{}'", name))),
+        }
+    }
+}
+
+/// Default location of the configuration file.
+fn config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|value| !value.is_empty()) {
+        Some(value) => PathBuf::from(value),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("yadwh").join("config.toml"))
+}
+
+impl Cli {
+    /// Resolves the webhook from the flags, environment, or configuration file.
+    fn webhook(&self) -> Result<WebhookUrl> {
+        if let Some(url) = &self.url {
+            return url.parse();
+        }
+
+        let config = Config::load(self.config.as_deref())?;
+        if let Some(profile) = &self.profile {
+            return config.profile(profile);
+        }
+
+        if let Some(url) = std::env::var(URL_VARIABLE)
+            .ok()
+            .filter(|url| !url.is_empty())
+        {
+            return url.parse();
+        }
+
+        match &config.default {
+            Some(profile) => config.profile(profile),
+            None => Err(WebhookError::Unknown(format!(
+                "no webhook provided, use --url, --profile, or {}",
+                URL_VARIABLE
+            ))),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli).await {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Performs the command.
+async fn run(cli: &Cli) -> Result<()> {
+    let mut api = WebhookApi::from_webhook_url(&cli.webhook()?).max_retries(cli.retries);
+    if let Some(url) = &cli.base_url {
+        api = api.base_url(url);
+    }
+
+    match &cli.command {
+        Command::Webhook(WebhookCommand::Get) => {
+            let webhook = api.get().await?;
+            print_webhook(&webhook, cli.json);
+        }
+        Command::Webhook(WebhookCommand::Modify { name, avatar }) => {
+            let mut webhook = api.get().await?;
+            if let Some(name) = name {
+                webhook.name = name.clone();
+            }
+            if let Some(path) = avatar {
+                let file = File::open(path)?;
+                let data = format!("data:{};base64,{}", file.content_type, base64(&file.data));
+                webhook.avatar = Some(data);
+            }
+
+            let webhook = api.modify(&webhook).await?;
+            print_webhook(&webhook, cli.json);
+        }
+        Command::Webhook(WebhookCommand::Delete { yes }) => {
+            if !yes {
+                return Err(WebhookError::Unknown(
+                    "deleting the webhook cannot be undone, confirm with --yes".to_string(),
+                ));
+            }
+
+            api.delete().await?;
+            match cli.json {
+                true => println!("{}", json!({ "deleted": true })),
+                false => println!("Deleted webhook."),
+            }
+        }
+        Command::Message(MessageCommand::Send { message, thread }) => {
+            let message = api.message.create(&message.build()?, *thread).await?;
+            print_message(&message, "Sent", cli.json);
+        }
+        Command::Message(MessageCommand::Get { id }) => {
+            let message = api.message.get(*id).await?;
+            match cli.json {
+                true => print_json(&message),
+                false => {
+                    let stdout = std::io::stdout();
+                    Terminal::new().ansi(stdout.is_terminal()).print(&message);
+                    for attachment in message.attachments.iter() {
+                        println!("[attachment: {}]", attachment.url);
+                    }
+                }
+            }
+        }
+        Command::Message(MessageCommand::Edit { id, message }) => {
+            let message = api.message.edit(*id, &message.build()?).await?;
+            print_message(&message, "Edited", cli.json);
+        }
+        Command::Message(MessageCommand::Delete { id }) => {
+            api.message.delete(*id).await?;
+            match cli.json {
+                true => println!("{}", json!({ "deleted": id })),
+                false => println!("Deleted message {}.", id),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Loads a message from a JSON, TOML, or YAML file, `-` reads JSON or TOML from standard input.
+fn load(path: &Path) -> Result<MessageBuilder> {
+    if path.to_str() != Some("-") {
+        return MessageBuilder::load(path);
+    }
+
+    // Standard input is parsed as JSON if it looks like it.
+    let text = read_stdin()?;
+    let format = match text.trim_start().starts_with('{') {
+        true => Format::Json,
+        false => Format::Toml,
+    };
+    MessageBuilder::parse(&text, format)
+}
+
+/// Reads all of standard input.
+fn read_stdin() -> Result<String> {
+    let mut text = String::new();
+    match std::io::stdin().read_to_string(&mut text) {
+        Ok(_) => Ok(text),
+        Err(error) => Err(WebhookError::Unknown(format!("reading stdin, {}", error))),
+    }
+}
+
+/// Prints the webhook, the token is never printed.
+fn print_webhook(webhook: &Webhook, json: bool) {
+    if json {
+        print_json(&json!({
+            "id": webhook.id,
+            "type": webhook.r#type,
+            "guild_id": webhook.guild_id,
+            "channel_id": webhook.channel_id,
+            "name": webhook.name,
+            "avatar": webhook.avatar,
+            "token": REDACTED,
+        }));
+        return;
+    }
+
+    println!("Webhook {}", webhook.id);
+    println!("  Name:    {}", webhook.name);
+    println!("  Channel: {}", webhook.channel_id);
+    if let Some(guild_id) = webhook.guild_id {
+        println!("  Guild:   {}", guild_id);
+    }
+    if let Some(avatar) = &webhook.avatar {
+        println!("  Avatar:  {}", avatar);
+    }
+}
+
+/// Prints a message that was sent or edited.
+fn print_message(message: &Message, action: &str, json: bool) {
+    match json {
+        true => print_json(message),
+        false => println!(
+            "{} message {} in channel {}.",
+            action, message.id, message.channel_id
+        ),
+    }
+}
+
+/// Prints a value as pretty JSON.
+fn print_json<T: serde::Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(text) => println!("{}", text),
+        Err(error) => eprintln!("error: serializing output, {}", error),
+    }
+}
+
+/// Encodes bytes as standard base64 with padding, used for avatar data URIs.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut text = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or_default(),
+            chunk.get(2).copied().unwrap_or_default(),
+        ];
+        let value = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => text.push(ALPHABET[(value >> (18 - 6 * index) & 0x3F) as usize] as char),
+                false => text.push('='),
+            }
+        }
+    }
+    text
+}
@@ -0,0 +1,310 @@
+//! Record and replay of requests for deterministic tests.
+//!
+//! `cassette` contains the `Cassette` transport. While recording, every request is sent through
+//! another transport and the method, redacted URL, body, and response are written to a JSON file.
+//! While replaying, the recorded responses are returned in order without touching the network.
+//! Tokens are never written to the file, they are masked within the URL, response body, and
+//! response headers.
+//!
+//! A cassette is assigned with `WebhookApi::transport`, such as:
+//! `WebhookApi::new(id, token).transport(Cassette::replay("tests/create.json", Matching::Strict)?)`
+
+use crate::client::{Result, WebhookError};
+use crate::middleware::{Request, Response};
+use crate::secret::REDACTED;
+use crate::transport::{HyperTransport, Transport};
+use futures::future::BoxFuture;
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// How requests are matched against recorded interactions while replaying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Matching {
+    /// The next interaction must have the same method, URL, and body. JSON bodies are compared by
+    /// value, ignoring formatting and key order.
+    #[default]
+    Strict,
+    /// The next interaction with the same method and path is used, ignoring the query and body.
+    /// Interactions that are skipped over are not replayed.
+    Lenient,
+}
+
+/// Single request and response pair recorded within a cassette.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// HTTP method of the request.
+    pub method: String,
+    /// URL of the request with the token masked.
+    pub url: String,
+    /// Body of the request.
+    pub body: String,
+    /// Response received for the request.
+    pub response: RecordedResponse,
+}
+
+/// Response recorded within a cassette.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// HTTP status of the response.
+    pub status: u16,
+    /// Headers of the response.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Body of the response.
+    pub body: String,
+}
+
+/// Whether the cassette is recording or replaying.
+enum Mode {
+    /// Requests are sent through the transport and recorded.
+    Record(Arc<dyn Transport>),
+    /// Requests are answered from the recording.
+    Replay(Matching),
+}
+
+/// Transport that records requests to a file, or replays them from one.
+pub struct Cassette {
+    /// File the interactions are written to or read from.
+    path: PathBuf,
+    /// Whether the cassette is recording or replaying.
+    mode: Mode,
+    /// Interactions recorded, or remaining to be replayed.
+    interactions: Arc<Mutex<Vec<Interaction>>>,
+    /// Held while the file is being written so recordings are written in order.
+    writing: Arc<Mutex<()>>,
+    /// Position of the next interaction to replay.
+    cursor: Mutex<usize>,
+}
+
+impl Cassette {
+    /// Creates a cassette that sends requests with `HyperTransport` and records them to `path`,
+    /// replacing any existing recording. The file is written after every request.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to write the interactions to.
+    pub fn record<P: AsRef<Path>>(path: P) -> Self {
+        Self::record_with(path, HyperTransport::new())
+    }
+
+    /// Creates a cassette that sends requests with the transport provided and records them to
+    /// `path`, replacing any existing recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to write the interactions to.
+    /// * `transport` - Transport used to send the requests.
+    pub fn record_with<P: AsRef<Path>, T: Transport + 'static>(path: P, transport: T) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: Mode::Record(Arc::new(transport)),
+            interactions: Arc::new(Mutex::new(vec![])),
+            writing: Arc::new(Mutex::new(())),
+            cursor: Mutex::new(0),
+        }
+    }
+
+    /// Loads a recording from `path` and replays it in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - File to read the interactions from.
+    /// * `matching` - How requests are matched against the recording.
+    pub fn replay<P: AsRef<Path>>(path: P, matching: Matching) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = std::fs::read_to_string(&path).map_err(|error| {
+            WebhookError::Unknown(format!("reading cassette {}, {}", path.display(), error))
+        })?;
+        let interactions: Vec<Interaction> = serde_json::from_str(&text).map_err(|error| {
+            WebhookError::BadParse(format!("cassette {}, {}", path.display(), error))
+        })?;
+
+        Ok(Self {
+            path,
+            mode: Mode::Replay(matching),
+            interactions: Arc::new(Mutex::new(interactions)),
+            writing: Arc::new(Mutex::new(())),
+            cursor: Mutex::new(0),
+        })
+    }
+
+    /// Interactions recorded, or loaded to be replayed.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        lock(&self.interactions).clone()
+    }
+
+    /// Amount of interactions that have not been replayed yet.
+    pub fn remaining(&self) -> usize {
+        match self.mode {
+            Mode::Record(_) => 0,
+            Mode::Replay(_) => lock(&self.interactions).len() - *lock(&self.cursor),
+        }
+    }
+
+    /// Sends the request and records the interaction.
+    async fn record_request(
+        &self,
+        transport: &dyn Transport,
+        request: &Request,
+    ) -> Result<Response> {
+        let response = transport.send(request).await?;
+
+        // Responses such as those for getting a webhook contain the token itself.
+        let token = request.token.expose();
+        let mask = |text: &str| match token.is_empty() {
+            true => text.to_string(),
+            false => text.replace(token, REDACTED),
+        };
+
+        let headers = response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), mask(value.to_str().ok()?))))
+            .collect();
+
+        let interaction = Interaction {
+            method: request.method.to_string(),
+            url: request.redacted_url(),
+            body: request.body.clone(),
+            response: RecordedResponse {
+                status: response.status.as_u16(),
+                headers,
+                body: mask(&response.body),
+            },
+        };
+        lock(&self.interactions).push(interaction);
+
+        // Write the file on a blocking thread, the recording is taken once the previous write has
+        // finished so the latest interactions are always written last.
+        let path = self.path.clone();
+        let interactions = Arc::clone(&self.interactions);
+        let writing = Arc::clone(&self.writing);
+        tokio::task::spawn_blocking(move || {
+            let _writing = lock(&writing);
+            let text = serde_json::to_string_pretty(&*lock(&interactions))
+                .map_err(|error| WebhookError::BadParse(format!("cassette, {}", error)))?;
+            std::fs::write(&path, text).map_err(|error| {
+                WebhookError::Unknown(format!("writing cassette {}, {}", path.display(), error))
+            })
+        })
+        .await
+        .map_err(|error| WebhookError::Unknown(format!("writing cassette, {}", error)))??;
+
+        Ok(response)
+    }
+
+    /// Finds the recorded response for the request.
+    fn replay_request(&self, matching: Matching, request: &Request) -> Result<Response> {
+        let interactions = lock(&self.interactions);
+        let mut cursor = lock(&self.cursor);
+
+        let method = request.method.to_string();
+        let url = request.redacted_url();
+        let mismatch = |reason: &str| {
+            WebhookError::Unknown(format!(
+                "cassette {}, {} for {} {}",
+                self.path.display(),
+                reason,
+                method,
+                url
+            ))
+        };
+
+        let index = match matching {
+            Matching::Strict => {
+                let next = interactions
+                    .get(*cursor)
+                    .ok_or_else(|| mismatch("no interactions remain"))?;
+                if next.method != method || next.url != url || !same_body(&next.body, &request.body)
+                {
+                    return Err(mismatch(&format!("expected {} {}", next.method, next.url)));
+                }
+                *cursor
+            }
+            Matching::Lenient => {
+                let path = url.split('?').next().unwrap_or_default();
+                interactions[*cursor..]
+                    .iter()
+                    .position(|next| {
+                        next.method == method && next.url.split('?').next() == Some(path)
+                    })
+                    .map(|offset| *cursor + offset)
+                    .ok_or_else(|| mismatch("no matching interaction remains"))?
+            }
+        };
+
+        *cursor = index + 1;
+        response(&interactions[index].response)
+    }
+}
+
+impl fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mode = match self.mode {
+            Mode::Record(_) => "record".to_string(),
+            Mode::Replay(matching) => format!("replay {:?}", matching),
+        };
+
+        f.debug_struct("Cassette")
+            .field("path", &self.path)
+            .field("mode", &mode)
+            .field("interactions", &lock(&self.interactions).len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Transport for Cassette {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+        match &self.mode {
+            Mode::Record(transport) => Box::pin(self.record_request(transport.as_ref(), request)),
+            Mode::Replay(matching) => {
+                let response = self.replay_request(*matching, request);
+                Box::pin(async move { response })
+            }
+        }
+    }
+}
+
+/// Locks a mutex, ignoring poisoning from a panicked test.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Compares two bodies, by value if both are JSON.
+fn same_body(recorded: &str, actual: &str) -> bool {
+    match (
+        serde_json::from_str::<Value>(recorded),
+        serde_json::from_str::<Value>(actual),
+    ) {
+        (Ok(recorded), Ok(actual)) => recorded == actual,
+        _ => recorded == actual,
+    }
+}
+
+/// Converts a recorded response back into a response.
+fn response(recorded: &RecordedResponse) -> Result<Response> {
+    let status = StatusCode::from_u16(recorded.status)
+        .map_err(|_| WebhookError::BadParse(format!("cassette status {}", recorded.status)))?;
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in recorded.headers.iter() {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            headers.insert(name, value);
+        }
+    }
+
+    Ok(Response {
+        status,
+        headers,
+        body: recorded.body.clone(),
+    })
+}
@@ -3,20 +3,30 @@
 //! `webhook` bundles up the required authentication parameters and creates a HTTP client that is
 //! used to interact with the Discord API. All authentication for each request is handled for the user.
 
-use hyper::body::Buf;
-use hyper::client::{Client as HyperClient, HttpConnector};
-use hyper::{Body, Method, Request, StatusCode};
-use hyper_tls::HttpsConnector;
+use crate::attachment::File;
+use crate::middleware::{self, Middleware};
+use crate::observe::{Observer, Outcome, RequestEvent};
+use crate::secret::Token;
+use crate::snowflake::WebhookId;
+use crate::transport::{HyperTransport, Transport};
+use crate::truncate::{self, Truncated};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{HeaderMap, Method, StatusCode};
+use serde::Deserialize;
 use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-/// Base URI for the Webhook API.
-pub(crate) const ROOT_URI: &str = "https://discord.com/api/v10/webhooks";
+/// Base URI for the Discord API, webhooks are accessed beneath `/webhooks`.
+pub(crate) const ROOT_URI: &str = "https://discord.com/api/v10";
 
 /// Used to return either objects or errors.
 pub type Result<T> = std::result::Result<T, WebhookError>;
 
-/// Enum for handling the expected errors for processing webhook messages.
+/// Enum for handling the expected errors for processing webhook messages. New variants may be
+/// added, matches require a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum WebhookError {
     /// Non-200 status obtained from the API.
     BadStatus(String),
@@ -24,10 +34,18 @@ pub enum WebhookError {
     NoContent,
     /// Unknown error, details provided.
     Unknown(String),
+    /// The request could not be sent or its response could not be received, details provided.
+    Transport(String),
     /// Unable to parse an object.
     BadParse(String),
     /// Content or Embed character count is too large.
     TooBig(String, usize, usize),
+    /// One or more limits were exceeded while validating, every violation is provided.
+    Invalid(Vec<Violation>),
+    /// Error response from the Discord API, including its JSON error code.
+    Discord(DiscordError),
+    /// Rate limited by the Discord API (429) and no retries remained.
+    RateLimited(RateLimit),
 }
 
 impl fmt::Display for WebhookError {
@@ -36,16 +54,201 @@ impl fmt::Display for WebhookError {
             WebhookError::BadStatus(value) => write!(f, "bad status: {}", value),
             WebhookError::BadParse(value) => write!(f, "bad parse: {}", value),
             WebhookError::Unknown(value) => write!(f, "unknown: {}", value),
+            WebhookError::Transport(value) => write!(f, "transport: {}", value),
             WebhookError::NoContent => f.write_str("no content."),
             WebhookError::TooBig(value, size, max) => write!(
                 f,
                 "{} exceeded max character count, {} of {}",
                 value, size, max
             ),
+            WebhookError::Invalid(violations) => {
+                let violations: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+                write!(f, "invalid: {}", violations.join("; "))
+            }
+            WebhookError::Discord(error) => write!(f, "discord: {}", error),
+            WebhookError::RateLimited(limit) => match limit.retry_after {
+                Some(value) => write!(f, "rate limited, retry after {:?}", value),
+                None => f.write_str("rate limited"),
+            },
         }
     }
 }
 
+impl std::error::Error for WebhookError {}
+
+/// Error response returned by the Discord API.
+///
+/// ## References / Documentation
+///
+/// <https://discord.com/developers/docs/topics/opcodes-and-status-codes#json>
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DiscordError {
+    /// HTTP status code of the response.
+    #[serde(skip)]
+    pub status: u16,
+    /// JSON error code, such as 10015 for "Unknown Webhook".
+    #[serde(default)]
+    pub code: u32,
+    /// Human readable description of the error.
+    #[serde(default)]
+    pub message: String,
+}
+
+impl fmt::Display for DiscordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (code {}, status {})",
+            self.message, self.code, self.status
+        )
+    }
+}
+
+/// Rate limit information obtained from the headers and body of a response.
+///
+/// ## References / Documentation
+///
+/// <https://discord.com/developers/docs/topics/rate-limits#header-format>
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimit {
+    /// Unique string identifying the rate limit bucket.
+    pub bucket: Option<String>,
+    /// Number of requests that can be made within the bucket.
+    pub limit: Option<u32>,
+    /// Number of remaining requests that can be made.
+    pub remaining: Option<u32>,
+    /// Time until the bucket resets.
+    pub reset_after: Option<Duration>,
+    /// Time to wait before retrying, only present when rate limited.
+    pub retry_after: Option<Duration>,
+    /// Whether the global rate limit was exceeded.
+    pub global: bool,
+    /// Scope of the exceeded rate limit: 'user', 'global', or 'shared'.
+    pub scope: Option<String>,
+}
+
+impl RateLimit {
+    /// Parses the rate limit headers of a response.
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let text = |name: &str| -> Option<String> {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        let seconds = |name: &str| -> Option<Duration> {
+            text(name)
+                .and_then(|value| value.parse::<f64>().ok())
+                .and_then(|value| Duration::try_from_secs_f64(value).ok())
+        };
+
+        Self {
+            bucket: text("x-ratelimit-bucket"),
+            limit: text("x-ratelimit-limit").and_then(|value| value.parse().ok()),
+            remaining: text("x-ratelimit-remaining").and_then(|value| value.parse().ok()),
+            reset_after: seconds("x-ratelimit-reset-after"),
+            retry_after: seconds("retry-after"),
+            global: text("x-ratelimit-global").is_some_and(|value| value == "true"),
+            scope: text("x-ratelimit-scope"),
+        }
+    }
+}
+
+/// Operations performed against the Discord API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Obtain the webhook.
+    GetWebhook,
+    /// Modify the webhook.
+    ModifyWebhook,
+    /// Delete the webhook.
+    DeleteWebhook,
+    /// Execute the webhook, creating a message.
+    CreateMessage,
+    /// Obtain a message.
+    GetMessage,
+    /// Edit a message.
+    EditMessage,
+    /// Delete a message.
+    DeleteMessage,
+}
+
+impl Operation {
+    /// HTTP method used for the operation.
+    pub(crate) fn method(self) -> Method {
+        match self {
+            Operation::GetWebhook | Operation::GetMessage => Method::GET,
+            Operation::ModifyWebhook | Operation::EditMessage => Method::PATCH,
+            Operation::DeleteWebhook | Operation::DeleteMessage => Method::DELETE,
+            Operation::CreateMessage => Method::POST,
+        }
+    }
+
+    /// Name of the operation, such as 'create_message'.
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::GetWebhook => "get_webhook",
+            Operation::ModifyWebhook => "modify_webhook",
+            Operation::DeleteWebhook => "delete_webhook",
+            Operation::CreateMessage => "create_message",
+            Operation::GetMessage => "get_message",
+            Operation::EditMessage => "edit_message",
+            Operation::DeleteMessage => "delete_message",
+        }
+    }
+
+    /// Route template of the operation, never containing the actual token.
+    pub fn template(self) -> &'static str {
+        match self {
+            Operation::GetWebhook
+            | Operation::ModifyWebhook
+            | Operation::DeleteWebhook
+            | Operation::CreateMessage => "/webhooks/{webhook.id}/{webhook.token}",
+            Operation::GetMessage | Operation::EditMessage | Operation::DeleteMessage => {
+                "/webhooks/{webhook.id}/{webhook.token}/messages/{message.id}"
+            }
+        }
+    }
+}
+
+/// Single limit that was exceeded while validating a message or embed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Path to the offending value, such as `embeds[2].fields[7].value`.
+    pub path: String,
+    /// Size of the offending value, in characters or items.
+    pub size: usize,
+    /// Maximum size allowed by the Discord API.
+    pub max: usize,
+}
+
+impl Violation {
+    /// Creates a new violation for the value at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the offending value.
+    /// * `size` - Size of the offending value.
+    /// * `max` - Maximum size allowed.
+    pub(crate) fn new(path: &str, size: usize, max: usize) -> Self {
+        Self {
+            path: path.to_string(),
+            size,
+            max,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} exceeded max, {} of {}",
+            self.path, self.size, self.max
+        )
+    }
+}
+
 /// Collection of Limits enforced by the Discord API.
 ///
 /// ## References / Documentation
@@ -78,6 +281,43 @@ impl Limit {
     pub const FOOTER_TEXT: usize = 2048;
     /// Maximum total characters for an embed.
     pub const EMBED_TOTAL: usize = 6000;
+
+    /// Maximum amount of action rows on a single message.
+    pub const COMPONENTS: usize = 5;
+    /// Maximum amount of components within a single action row.
+    pub const ROW_COMPONENTS: usize = 5;
+    /// Maximum length of the label on a button.
+    pub const BUTTON_LABEL: usize = 80;
+
+    /// Maximum amount of files uploaded with a single message.
+    pub const FILES: usize = 10;
+
+    /// Counts the characters within `text` the way Discord does, by Unicode code points rather
+    /// than UTF-8 bytes. All limits are compared against this count.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Text to obtain the length of.
+    pub fn length(text: &str) -> usize {
+        text.chars().count()
+    }
+
+    /// Checks if `size` exceeds `max`, recording a violation for `path` if it does.
+    pub(crate) fn check(path: &str, size: usize, max: usize, violations: &mut Vec<Violation>) {
+        if size > max {
+            violations.push(Violation::new(path, size, max));
+        }
+    }
+}
+
+/// Response received from the Discord API.
+struct Response {
+    /// HTTP status of the response.
+    status: StatusCode,
+    /// Rate limit information from the headers.
+    rate_limit: RateLimit,
+    /// Body of the response.
+    body: String,
 }
 
 /// Webhook is a client that is responsible for making requests to the Discord API.
@@ -91,14 +331,35 @@ impl Limit {
 /// Webhook ID: __111122223333__
 ///
 /// Webhook Token: **AAAABBBBCCCC**
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub(crate) struct Client {
     /// ID of the Webhook.
-    pub(crate) id: String,
-    /// Token for the Webhook.
-    pub(crate) token: String,
-    /// HTTP client used to send requests to the API.
-    client: HyperClient<HttpsConnector<HttpConnector>>,
+    pub(crate) id: WebhookId,
+    /// Token for the Webhook, redacted when displayed.
+    pub(crate) token: Token,
+    /// Base URL of the API, such as `https://discord.com/api/v10`.
+    pub(crate) base_url: String,
+    /// Maximum amount of times a rate limited (429) request is retried.
+    pub(crate) max_retries: u32,
+    /// Observers notified after every request.
+    pub(crate) observers: Vec<Arc<dyn Observer>>,
+    /// Middleware applied to every request and response, in order.
+    pub(crate) middleware: Vec<Arc<dyn Middleware>>,
+    /// Transport used to send requests to the API.
+    pub(crate) transport: Arc<dyn Transport>,
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("id", &self.id)
+            .field("token", &self.token)
+            .field("base_url", &self.base_url)
+            .field("max_retries", &self.max_retries)
+            .field("observers", &self.observers.len())
+            .field("middleware", &self.middleware.len())
+            .finish_non_exhaustive()
+    }
 }
 
 impl Client {
@@ -108,71 +369,446 @@ impl Client {
     ///
     /// * `webhook_id` - ID of the Webhook.
     /// * `webhook_token` - Token of the Webhook.
-    pub(crate) fn new(webhook_id: &str, webhook_token: &str) -> Self {
-        let connector = HttpsConnector::new();
+    pub(crate) fn new(webhook_id: WebhookId, webhook_token: &str) -> Self {
         Self {
-            id: webhook_id.to_string(),
-            token: webhook_token.to_string(),
-            client: HyperClient::builder().build::<_, Body>(connector),
+            id: webhook_id,
+            token: Token::new(webhook_token),
+            base_url: ROOT_URI.to_string(),
+            max_retries: 0,
+            observers: vec![],
+            middleware: vec![],
+            transport: Arc::new(HyperTransport::new()),
         }
     }
 
-    /// Used to create the base endpoint.
-    fn url(&self) -> String {
-        format!("{}/{}/{}", ROOT_URI, self.id, self.token)
+    /// Sends requests to the Discord API. Rate limited requests are retried up to `max_retries`
+    /// times, waiting as long as the API instructs between attempts.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - Operation being performed, determines the HTTP method.
+    /// * `endpoint` - Target endpoint to access.
+    /// * `body` - JSON body to send to the API (used for POST and PATCH.)
+    pub(crate) async fn send(
+        &self,
+        operation: Operation,
+        endpoint: &str,
+        body: String,
+    ) -> Result<String> {
+        self.send_files(operation, endpoint, body, &[], &[]).await
     }
 
-    /// Sends requests to the Discord API.
+    /// Sends requests to the Discord API along with files to upload, see `Client::send`. Values
+    /// cut from the message are reported to the observers and as `tracing` warnings.
     ///
     /// # Arguments
     ///
-    /// * `method` - Method to perform, valid options are: Method::GET, Method::POST,
-    /// Method::DELETE, and Method::PATCH.
+    /// * `operation` - Operation being performed, determines the HTTP method.
     /// * `endpoint` - Target endpoint to access.
-    /// * `body` - HTTP Body to send to the API (used for POST and PATCH.)
-    pub(crate) async fn send(&self, method: Method, endpoint: &str, body: Body) -> Result<String> {
-        let url = format!("{}{}", self.url(), endpoint);
-
-        // Build the request for the Method.
-        let req = Request::builder()
-            .method(method)
-            .uri(url)
-            .header("Content-Type", "application/json")
-            .body(body);
-
-        // Send the request, parse the response.
-        match self.client.request(req.unwrap()).await {
-            Ok(value) => match value.status() {
-                StatusCode::OK => {
-                    // Convert the HTTP body stream to a &[u8]
-                    let body = match hyper::body::to_bytes(value).await {
-                        Ok(data) => data,
-                        Err(_) => {
-                            return Err(WebhookError::Unknown(
-                                "unable to convert http body".to_string(),
-                            ))
-                        }
-                    };
-
-                    // Convert to JSON string to be parsed by calling function and return.
-                    match std::str::from_utf8(body.chunk()) {
-                        Ok(data) => Ok(data.to_owned()),
-                        Err(_) => Err(WebhookError::Unknown(
-                            "unable to convert to json".to_string(),
-                        )),
-                    }
-                }
+    /// * `body` - JSON body to send to the API (used for POST and PATCH.)
+    /// * `files` - Files to upload, the body is sent as `multipart/form-data` if there are any.
+    /// * `truncated` - Values that were cut from the message by its truncation policy.
+    pub(crate) async fn send_files(
+        &self,
+        operation: Operation,
+        endpoint: &str,
+        body: String,
+        files: &[File],
+        truncated: &[Truncated],
+    ) -> Result<String> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::field::Empty;
+            use tracing::Instrument;
+
+            let span = tracing::info_span!(
+                "yadwh.request",
+                http.method = %operation.method(),
+                route = operation.template(),
+                operation = operation.name(),
+                webhook.id = %self.id,
+                http.status = Empty,
+                discord.code = Empty,
+                ratelimit.bucket = Empty,
+                retries = Empty,
+                latency_ms = Empty,
+            );
+
+            self.attempt(operation, endpoint, body, files, truncated)
+                .instrument(span)
+                .await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.attempt(operation, endpoint, body, files, truncated)
+            .await
+    }
+
+    /// Performs the request, retrying if rate limited, and parses the final response.
+    async fn attempt(
+        &self,
+        operation: Operation,
+        endpoint: &str,
+        body: String,
+        files: &[File],
+        truncated: &[Truncated],
+    ) -> Result<String> {
+        let start = Instant::now();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(bytes = body.len(), "request payload");
+        truncate::report(truncated);
+
+        let mut retries: u32 = 0;
+        let mut last: Option<(StatusCode, RateLimit)> = None;
+        let result = loop {
+            let response = match self
+                .execute(operation, endpoint, &body, files, retries)
+                .await
+            {
+                Ok(value) => value,
+                Err(error) => break Err(error),
+            };
+
+            last = Some((response.status, response.rate_limit.clone()));
+            if response.status != StatusCode::TOO_MANY_REQUESTS || retries >= self.max_retries {
+                break Self::parse(response);
+            }
+
+            // Wait for the duration requested by the API before trying again.
+            retries += 1;
+            let wait = retry_after(&response).unwrap_or(Duration::from_secs(1));
+            tokio::time::sleep(wait).await;
+        };
 
-                // Bad status code received, print the code.
-                StatusCode::NO_CONTENT => Err(WebhookError::NoContent),
-                _ => {
-                    let code = format!("Status Code: {}", value.status().as_u16());
-                    Err(WebhookError::BadStatus(code.to_string()))
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            span.record("retries", retries);
+            span.record("latency_ms", start.elapsed().as_millis() as u64);
+            match &result {
+                Ok(value) => tracing::debug!(bytes = value.len(), "response payload"),
+                Err(error) => tracing::warn!(error = %error, "request failed"),
+            }
+        }
+
+        // Notify the observers of the completed request.
+        if !self.observers.is_empty() {
+            let (status, rate_limit) = match last {
+                Some((status, rate_limit)) => (Some(status.as_u16()), Some(rate_limit)),
+                None => (None, None),
+            };
+
+            let event = RequestEvent {
+                webhook_id: self.id,
+                operation,
+                outcome: outcome(&result),
+                status,
+                code: match &result {
+                    Err(WebhookError::Discord(error)) => Some(error.code),
+                    _ => None,
+                },
+                latency: start.elapsed(),
+                retries,
+                rate_limit,
+                truncated: truncated.to_vec(),
+            };
+
+            for observer in self.observers.iter() {
+                observer.observe(&event);
+            }
+        }
+
+        result
+    }
+
+    /// Sends a single request to the API through the middleware, returning the raw response.
+    async fn execute(
+        &self,
+        operation: Operation,
+        endpoint: &str,
+        body: &str,
+        files: &[File],
+        attempt: u32,
+    ) -> Result<Response> {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let mut request = middleware::Request {
+            webhook_id: self.id,
+            base_url: self.base_url.clone(),
+            token: self.token.clone(),
+            operation,
+            method: operation.method(),
+            endpoint: endpoint.to_string(),
+            headers,
+            body: body.to_string(),
+            files: files.to_vec(),
+            attempt,
+        };
+
+        // Middleware is applied in the order it was added.
+        for layer in self.middleware.iter() {
+            layer.before_send(&mut request)?;
+        }
+
+        let mut response = self.transport.send(&request).await?;
+
+        // Responses pass back through the middleware in reverse order.
+        for layer in self.middleware.iter().rev() {
+            layer.after_response(&request, &mut response)?;
+        }
+
+        let rate_limit = RateLimit::from_headers(&response.headers);
+
+        #[cfg(feature = "tracing")]
+        {
+            let span = tracing::Span::current();
+            span.record("http.status", response.status.as_u16());
+            if let Some(bucket) = &rate_limit.bucket {
+                span.record("ratelimit.bucket", bucket.as_str());
+            }
+        }
+
+        Ok(Response {
+            status: response.status,
+            rate_limit,
+            body: response.body,
+        })
+    }
+
+    /// Converts a response into either its body or the appropriate error.
+    fn parse(response: Response) -> Result<String> {
+        let error = match response.status.is_success() {
+            true => None,
+            false => serde_json::from_str::<DiscordError>(&response.body).ok(),
+        };
+
+        #[cfg(feature = "tracing")]
+        if let Some(error) = &error {
+            tracing::Span::current().record("discord.code", error.code);
+        }
+
+        match response.status {
+            StatusCode::OK => Ok(response.body),
+            StatusCode::NO_CONTENT => Err(WebhookError::NoContent),
+            StatusCode::TOO_MANY_REQUESTS => {
+                let mut rate_limit = response.rate_limit.clone();
+                rate_limit.retry_after = retry_after(&response);
+                Err(WebhookError::RateLimited(rate_limit))
+            }
+
+            // Bad status code received, provide the Discord error if there is one.
+            status => match error {
+                Some(mut error) => {
+                    error.status = status.as_u16();
+                    Err(WebhookError::Discord(error))
+                }
+                None => {
+                    let code = format!("Status Code: {}", status.as_u16());
+                    Err(WebhookError::BadStatus(code))
                 }
             },
+        }
+    }
+}
+
+/// Determines the outcome of a request from its result.
+fn outcome(result: &Result<String>) -> Outcome {
+    match result {
+        Ok(_) | Err(WebhookError::NoContent) => Outcome::Success,
+        Err(WebhookError::RateLimited(_)) => Outcome::RateLimited,
+        Err(WebhookError::Discord(_)) | Err(WebhookError::BadStatus(_)) => Outcome::Rejected,
+        Err(_) => Outcome::Failed,
+    }
+}
+
+/// Obtains how long to wait before retrying a rate limited response, preferring the more precise
+/// `retry_after` within the body over the headers.
+fn retry_after(response: &Response) -> Option<Duration> {
+    /// Body of a rate limited (429) response.
+    #[derive(Deserialize)]
+    struct Body {
+        retry_after: f64,
+    }
+
+    match serde_json::from_str::<Body>(&response.body) {
+        Ok(body) => Duration::try_from_secs_f64(body.retry_after).ok(),
+        Err(_) => response
+            .rate_limit
+            .retry_after
+            .or(response.rate_limit.reset_after),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::{Request, Response as Exchange};
+    use futures::future::BoxFuture;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Transport that replies with queued responses, counting the attempts made.
+    struct Queue {
+        responses: Mutex<VecDeque<Exchange>>,
+        attempts: Mutex<Vec<u32>>,
+    }
+
+    impl Queue {
+        fn new(responses: Vec<Exchange>) -> Arc<Self> {
+            Arc::new(Self {
+                responses: Mutex::new(responses.into()),
+                attempts: Mutex::new(vec![]),
+            })
+        }
+
+        fn attempts(&self) -> Vec<u32> {
+            self.attempts.lock().unwrap().clone()
+        }
+    }
+
+    impl Transport for Queue {
+        fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Exchange>> {
+            self.attempts.lock().unwrap().push(request.attempt);
+            let response = self.responses.lock().unwrap().pop_front();
+            Box::pin(async move { Ok(response.expect("no response queued")) })
+        }
+    }
+
+    fn response(status: u16, headers: &[(&'static str, &str)], body: &str) -> Exchange {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        Exchange {
+            status: StatusCode::from_u16(status).unwrap(),
+            headers: map,
+            body: body.to_string(),
+        }
+    }
+
+    fn limited(retry_after: f64) -> Exchange {
+        let body = format!(
+            r#"{{"message": "You are being rate limited.", "retry_after": {}, "global": false}}"#,
+            retry_after
+        );
+        response(429, &[("x-ratelimit-bucket", "abc")], &body)
+    }
+
+    fn client(transport: &Arc<Queue>, max_retries: u32) -> Client {
+        let mut client = Client::new(WebhookId::new(1), "token");
+        client.transport = transport.clone();
+        client.max_retries = max_retries;
+        client
+    }
+
+    fn parsed(response: Exchange) -> Response {
+        Response {
+            status: response.status,
+            rate_limit: RateLimit::from_headers(&response.headers),
+            body: response.body,
+        }
+    }
+
+    #[test]
+    fn prefers_the_body_for_retry_after() {
+        let value = parsed(response(
+            429,
+            &[("retry-after", "3")],
+            r#"{"retry_after": 0.25}"#,
+        ));
+        assert_eq!(retry_after(&value), Some(Duration::from_millis(250)));
+
+        let value = parsed(response(429, &[("retry-after", "3")], ""));
+        assert_eq!(retry_after(&value), Some(Duration::from_secs(3)));
+
+        let value = parsed(response(429, &[("x-ratelimit-reset-after", "1.5")], "{}"));
+        assert_eq!(retry_after(&value), Some(Duration::from_millis(1500)));
+
+        let value = parsed(response(429, &[("retry-after", "soon")], "{}"));
+        assert_eq!(retry_after(&value), None);
+
+        let value = parsed(response(429, &[], r#"{"retry_after": -1}"#));
+        assert_eq!(retry_after(&value), None);
+    }
+
+    #[test]
+    fn parses_rate_limit_headers() {
+        let value = response(
+            429,
+            &[
+                ("x-ratelimit-bucket", "abc"),
+                ("x-ratelimit-limit", "5"),
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset-after", "0.5"),
+                ("x-ratelimit-global", "true"),
+                ("x-ratelimit-scope", "shared"),
+            ],
+            "",
+        );
+
+        let limit = RateLimit::from_headers(&value.headers);
+        assert_eq!(limit.bucket.as_deref(), Some("abc"));
+        assert_eq!(limit.limit, Some(5));
+        assert_eq!(limit.remaining, Some(0));
+        assert_eq!(limit.reset_after, Some(Duration::from_millis(500)));
+        assert_eq!(limit.retry_after, None);
+        assert!(limit.global);
+        assert_eq!(limit.scope.as_deref(), Some("shared"));
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limited_requests() {
+        let transport = Queue::new(vec![limited(0.0), limited(0.01), response(200, &[], "{}")]);
+        let client = client(&transport, 2);
+
+        let body = client.send(Operation::GetWebhook, "", String::new()).await;
+        assert_eq!(body.unwrap(), "{}");
+        assert_eq!(transport.attempts(), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn stops_after_max_retries() {
+        let transport = Queue::new(vec![limited(0.0), limited(0.5)]);
+        let client = client(&transport, 1);
+
+        match client.send(Operation::GetWebhook, "", String::new()).await {
+            Err(WebhookError::RateLimited(limit)) => {
+                assert_eq!(limit.bucket.as_deref(), Some("abc"));
+                assert_eq!(limit.retry_after, Some(Duration::from_millis(500)));
+            }
+            other => panic!("expected a rate limit, got {:?}", other),
+        }
+        assert_eq!(transport.attempts(), vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_by_default() {
+        let transport = Queue::new(vec![limited(0.0)]);
+        let client = client(&transport, 0);
+
+        let result = client.send(Operation::GetWebhook, "", String::new()).await;
+        assert!(matches!(result, Err(WebhookError::RateLimited(_))));
+        assert_eq!(transport.attempts(), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_other_errors() {
+        let body = r#"{"code": 10008, "message": "Unknown Message"}"#;
+        let transport = Queue::new(vec![response(404, &[], body), response(503, &[], "")]);
+        let client = client(&transport, 3);
+
+        match client.send(Operation::GetMessage, "", String::new()).await {
+            Err(WebhookError::Discord(error)) => {
+                assert_eq!((error.status, error.code), (404, 10008));
+            }
+            other => panic!("expected a Discord error, got {:?}", other),
+        }
 
-            // Non-status code error while processing response.
-            Err(_) => Err(WebhookError::Unknown("request to API".to_string())),
+        match client.send(Operation::GetMessage, "", String::new()).await {
+            Err(WebhookError::BadStatus(status)) => assert_eq!(status, "Status Code: 503"),
+            other => panic!("expected a bad status, got {:?}", other),
         }
+        assert_eq!(transport.attempts(), vec![0, 0]);
     }
 }
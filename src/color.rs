@@ -0,0 +1,201 @@
+//! Colors used for the side bar of embeds.
+//!
+//! `color` contains the `Color` type that can be created from RGB, hex (with or without the '#'
+//! prefix), HSL, or one of the named palette constants. Unlike assigning a raw integer, parsing a
+//! color surfaces an error if it is invalid.
+
+use crate::client::{Result, WebhookError};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// RGB color, serialized as the integer expected by the Discord API. Deserializes from either the
+/// integer or a hex string such as "#5865F2".
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct Color(u32);
+
+impl Color {
+    /// Discord's blurple, #5865F2.
+    pub const BLURPLE: Color = Color(0x5865F2);
+    /// Discord's green, #57F287.
+    pub const GREEN: Color = Color(0x57F287);
+    /// Discord's yellow, #FEE75C.
+    pub const YELLOW: Color = Color(0xFEE75C);
+    /// Discord's fuchsia, #EB459E.
+    pub const FUCHSIA: Color = Color(0xEB459E);
+    /// Discord's red, #ED4245.
+    pub const RED: Color = Color(0xED4245);
+    /// Discord's greyple, #99AAB5.
+    pub const GREYPLE: Color = Color(0x99AAB5);
+    /// Discord's dark theme background, #2C2F33.
+    pub const DARK: Color = Color(0x2C2F33);
+    /// Blue, #3498DB.
+    pub const BLUE: Color = Color(0x3498DB);
+    /// Orange, #E67E22.
+    pub const ORANGE: Color = Color(0xE67E22);
+    /// Purple, #9B59B6.
+    pub const PURPLE: Color = Color(0x9B59B6);
+    /// Gold, #F1C40F.
+    pub const GOLD: Color = Color(0xF1C40F);
+    /// White, #FFFFFF.
+    pub const WHITE: Color = Color(0xFFFFFF);
+    /// Black, #000000. Discord renders this as the default color.
+    pub const BLACK: Color = Color(0x000000);
+
+    /// Creates a color from its integer representation, such as 0xCBA6F7. Bits beyond the 24
+    /// used for RGB are discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Integer representation of the color.
+    pub const fn new(value: u32) -> Self {
+        Self(value & 0xFFFFFF)
+    }
+
+    /// Creates a color from its red, green, and blue components.
+    ///
+    /// # Arguments
+    ///
+    /// * `red` - Red component.
+    /// * `green` - Green component.
+    /// * `blue` - Blue component.
+    pub const fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self(((red as u32) << 16) | ((green as u32) << 8) | blue as u32)
+    }
+
+    /// Parses a color from hex, such as AA11BB, #AA11BB, or the short form #A1B.
+    ///
+    /// # Arguments
+    ///
+    /// * `hex` - Hex representation of the color, with or without the '#' prefix.
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let error = || WebhookError::BadParse(format!("color '{}'", hex));
+
+        // Remove the '#' prefix if it exists.
+        let digits = hex.trim();
+        let digits = digits.strip_prefix('#').unwrap_or(digits);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        // Expand the short form, such as 'A1B' to 'AA11BB'.
+        let digits: String = match digits.len() {
+            3 => digits.chars().flat_map(|c| [c, c]).collect(),
+            6 => digits.to_string(),
+            _ => return Err(error()),
+        };
+
+        match u32::from_str_radix(&digits, 16) {
+            Ok(value) => Ok(Self(value)),
+            Err(_) => Err(error()),
+        }
+    }
+
+    /// Creates a color from hue, saturation, and lightness. Values outside of their ranges are
+    /// clamped.
+    ///
+    /// # Arguments
+    ///
+    /// * `hue` - Hue in degrees, wraps around 360.
+    /// * `saturation` - Saturation from 0.0 to 1.0.
+    /// * `lightness` - Lightness from 0.0 to 1.0.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let x = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        let m = lightness - chroma / 2.0;
+        let scale = |value: f32| ((value + m) * 255.0).round() as u8;
+        Self::from_rgb(scale(r), scale(g), scale(b))
+    }
+
+    /// Integer representation of the color.
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Red, green, and blue components of the color.
+    pub const fn rgb(self) -> (u8, u8, u8) {
+        ((self.0 >> 16) as u8, (self.0 >> 8) as u8, self.0 as u8)
+    }
+}
+
+impl From<u32> for Color {
+    fn from(value: u32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((red, green, blue): (u8, u8, u8)) -> Self {
+        Self::from_rgb(red, green, blue)
+    }
+}
+
+impl From<Color> for u32 {
+    fn from(value: Color) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for Color {
+    type Err = WebhookError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::from_hex(value)
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(ColorVisitor)
+    }
+}
+
+/// Visitor accepting a color as an integer or hex string.
+struct ColorVisitor;
+
+impl Visitor<'_> for ColorVisitor {
+    type Value = Color;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a color as an integer or hex string")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Self::Value, E> {
+        match u32::try_from(value) {
+            Ok(value) if value <= 0xFFFFFF => Ok(Color(value)),
+            _ => Err(E::custom("color exceeds 0xFFFFFF")),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Self::Value, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::custom("color cannot be negative")),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Self::Value, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:06X}", self.0)
+    }
+}
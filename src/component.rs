@@ -0,0 +1,114 @@
+//! Message components, such as rows of link buttons.
+//!
+//! `component` contains the `Component` struct sent with messages to the Discord API. Components
+//! are placed within action rows, up to 5 rows per message with up to 5 buttons each. Webhooks
+//! that are not owned by an application may only send link buttons.
+//!
+//! ## References / Documentation
+//!
+//! <https://discord.com/developers/docs/interactions/message-components>
+
+use serde::{Deserialize, Serialize};
+
+/// Interactive component of a message, either an action row or an element placed within one.
+///
+/// ## References / Documentation
+///
+/// <https://discord.com/developers/docs/interactions/message-components#component-object>
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Component {
+    /// Type of component, see `Component::ACTION_ROW` and `Component::BUTTON`.
+    pub r#type: u8,
+    /// Style of a button, see `Component::LINK`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<u8>,
+    /// Text displayed on a button.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// URL opened by a link button.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Developer defined identifier, required for non-link buttons.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_id: Option<String>,
+    /// Whether the component is disabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    /// Components within an action row.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Component>,
+}
+
+impl Component {
+    /// Container for other components.
+    pub const ACTION_ROW: u8 = 1;
+    /// Clickable button.
+    pub const BUTTON: u8 = 2;
+
+    /// Blurple button style.
+    pub const PRIMARY: u8 = 1;
+    /// Grey button style.
+    pub const SECONDARY: u8 = 2;
+    /// Green button style.
+    pub const SUCCESS: u8 = 3;
+    /// Red button style.
+    pub const DANGER: u8 = 4;
+    /// Grey button style that navigates to a URL.
+    pub const LINK: u8 = 5;
+
+    /// Creates an action row containing the components provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - Components within the row, up to `Limit::ROW_COMPONENTS`.
+    pub fn row(components: Vec<Component>) -> Self {
+        Self {
+            r#type: Self::ACTION_ROW,
+            components,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a button that opens a URL when clicked.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - Text displayed on the button, maximum length is `Limit::BUTTON_LABEL`.
+    /// * `url` - URL opened by the button.
+    pub fn link(label: &str, url: &str) -> Self {
+        Self {
+            r#type: Self::BUTTON,
+            style: Some(Self::LINK),
+            label: Some(label.to_string()),
+            url: Some(url.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a button that sends an interaction to the application owning the webhook.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - Style of the button, such as `Component::PRIMARY`.
+    /// * `label` - Text displayed on the button, maximum length is `Limit::BUTTON_LABEL`.
+    /// * `custom_id` - Identifier sent with the interaction.
+    pub fn button(style: u8, label: &str, custom_id: &str) -> Self {
+        Self {
+            r#type: Self::BUTTON,
+            style: Some(style),
+            label: Some(label.to_string()),
+            custom_id: Some(custom_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets whether the component is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `disabled` - `true` to disable the component.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = Some(disabled);
+        self
+    }
+}
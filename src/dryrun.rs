@@ -0,0 +1,274 @@
+//! Dry runs that exercise the whole pipeline without sending anything.
+//!
+//! `dryrun` contains the `DryRun` transport. Messages are still truncated, validated, serialized,
+//! and passed through middleware, but the request is answered locally with a synthetic response:
+//! created messages receive generated snowflake IDs and timestamps, and are kept in memory so they
+//! can be obtained, edited, and deleted afterwards. Editing an unknown message, such as one sent
+//! before the dry run, stores a synthetic one. Every would-be request is captured and, with the
+//! `tracing` feature enabled, logged.
+//!
+//! Enable it with `WebhookApi::dry_run`, or keep a clone of a `DryRun` to inspect the captured
+//! requests: `WebhookApi::new(id, token).transport(dry_run.clone())`
+
+use crate::client::{Operation, Result, WebhookError};
+use crate::middleware::{Request, Response};
+use crate::snowflake::{ChannelId, MessageId, Snowflake};
+use crate::timestamp::Timestamp;
+use crate::transport::Transport;
+use futures::future::BoxFuture;
+use hyper::{HeaderMap, StatusCode};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+/// Request that would have been sent to the Discord API.
+#[derive(Debug, Clone)]
+pub struct Captured {
+    /// Operation that would have been performed.
+    pub operation: Operation,
+    /// URL of the request with the token masked.
+    pub url: String,
+    /// JSON body of the request, empty if there is none.
+    pub body: String,
+}
+
+impl Captured {
+    /// Parses the JSON body of the request.
+    pub fn json(&self) -> Result<Value> {
+        serde_json::from_str(&self.body)
+            .map_err(|error| WebhookError::BadParse(format!("captured body, {}", error)))
+    }
+}
+
+/// State shared between clones of a dry run.
+#[derive(Debug)]
+struct State {
+    /// Channel that messages are sent to when no thread is provided.
+    channel_id: ChannelId,
+    /// Messages created during the dry run.
+    messages: BTreeMap<MessageId, Value>,
+    /// Every request that would have been sent, in order.
+    captured: Vec<Captured>,
+    /// Last snowflake generated.
+    last_id: u64,
+}
+
+impl State {
+    /// Generates a new snowflake for the current time, always greater than the last.
+    fn next_id(&mut self) -> Snowflake {
+        let now = Snowflake::from_time(SystemTime::now()).get();
+        self.last_id = now.max(self.last_id + 1);
+        Snowflake::new(self.last_id)
+    }
+}
+
+/// Transport that answers requests locally instead of sending them. Clones share the same
+/// captured requests and messages.
+#[derive(Debug, Clone)]
+pub struct DryRun {
+    /// State shared between clones.
+    state: Arc<Mutex<State>>,
+}
+
+impl DryRun {
+    /// Creates a new dry run, messages are placed in a generated channel.
+    pub fn new() -> Self {
+        let mut state = State {
+            channel_id: ChannelId::new(0),
+            messages: BTreeMap::new(),
+            captured: vec![],
+            last_id: 0,
+        };
+        state.channel_id = ChannelId::from(state.next_id());
+
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Assigns the channel that messages are placed in when no thread is provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel_id` - Channel of the webhook.
+    pub fn channel(self, channel_id: ChannelId) -> Self {
+        self.lock().channel_id = channel_id;
+        self
+    }
+
+    /// Every request that would have been sent, in order.
+    pub fn captured(&self) -> Vec<Captured> {
+        self.lock().captured.clone()
+    }
+
+    /// Removes every captured request.
+    pub fn clear(&self) {
+        self.lock().captured.clear();
+    }
+
+    /// Locks the shared state, ignoring poisoning.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Creates the synthetic response for a request.
+    fn answer(&self, request: &Request) -> Response {
+        let mut state = self.lock();
+        let captured = Captured {
+            operation: request.operation,
+            url: request.redacted_url(),
+            body: request.body.clone(),
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            operation = captured.operation.name(),
+            url = %captured.url,
+            body = %captured.body,
+            "dry run, request not sent"
+        );
+
+        state.captured.push(captured);
+
+        let body: Value = serde_json::from_str(&request.body).unwrap_or(Value::Null);
+        let (path, query) = match request.endpoint.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (request.endpoint.as_str(), ""),
+        };
+        let thread_id = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "thread_id")
+            .and_then(|(_, value)| value.parse::<ChannelId>().ok());
+        let message_id = path
+            .strip_prefix("/messages/")
+            .and_then(|id| id.parse::<MessageId>().ok());
+
+        match request.operation {
+            Operation::GetWebhook | Operation::ModifyWebhook => {
+                let mut webhook = json!({
+                    "id": request.webhook_id,
+                    "type": 1,
+                    "guild_id": null,
+                    "channel_id": state.channel_id,
+                    "name": "Dry Run",
+                    "avatar": null,
+                    "token": request.token.expose(),
+                    "url": "",
+                });
+                for key in ["name", "avatar"] {
+                    if let Some(value) = body.get(key) {
+                        webhook[key] = value.clone();
+                    }
+                }
+                ok(&webhook)
+            }
+            Operation::CreateMessage => {
+                let id = MessageId::from(state.next_id());
+                let mut message = json!({
+                    "id": id,
+                    "channel_id": thread_id.unwrap_or(state.channel_id),
+                    "content": "",
+                    "timestamp": Timestamp::now(),
+                    "edited_timestamp": null,
+                    "tts": false,
+                    "mention_everyone": false,
+                    "embeds": [],
+                    "components": [],
+                    "pinned": false,
+                    "webhook_id": request.webhook_id,
+                    "type": 0,
+                });
+                apply(&mut message, &body);
+                state.messages.insert(id, message.clone());
+                ok(&message)
+            }
+            Operation::GetMessage => match message_id.and_then(|id| state.messages.get(&id)) {
+                Some(message) => ok(message),
+                None => unknown_message(),
+            },
+            Operation::EditMessage => match message_id {
+                Some(id) => {
+                    // Unknown messages are synthesized so later requests see the edit.
+                    let channel_id = thread_id.unwrap_or(state.channel_id);
+                    let message = state.messages.entry(id).or_insert_with(|| {
+                        json!({
+                            "id": id,
+                            "channel_id": channel_id,
+                            "content": "",
+                            "timestamp": Timestamp::from(Snowflake::new(id.get()).timestamp()),
+                            "edited_timestamp": null,
+                            "tts": false,
+                            "mention_everyone": false,
+                            "embeds": [],
+                            "components": [],
+                            "pinned": false,
+                            "webhook_id": request.webhook_id,
+                            "type": 0,
+                        })
+                    });
+                    apply(message, &body);
+                    message["edited_timestamp"] = json!(Timestamp::now());
+                    ok(message)
+                }
+                None => unknown_message(),
+            },
+            Operation::DeleteWebhook | Operation::DeleteMessage => {
+                if let Some(id) = message_id {
+                    state.messages.remove(&id);
+                }
+                Response {
+                    status: StatusCode::NO_CONTENT,
+                    headers: HeaderMap::new(),
+                    body: String::new(),
+                }
+            }
+        }
+    }
+}
+
+impl Default for DryRun {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for DryRun {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+        let response = self.answer(request);
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+/// Copies the values of a message payload onto a message.
+fn apply(message: &mut Value, body: &Value) {
+    for key in ["content", "tts", "embeds", "components"] {
+        if let Some(value) = body.get(key) {
+            message[key] = value.clone();
+        }
+    }
+
+    let everyone = message["content"]
+        .as_str()
+        .is_some_and(|content| content.contains("@everyone"));
+    message["mention_everyone"] = json!(everyone);
+}
+
+/// Successful response with a JSON body.
+fn ok(value: &Value) -> Response {
+    Response {
+        status: StatusCode::OK,
+        headers: HeaderMap::new(),
+        body: value.to_string(),
+    }
+}
+
+/// Response for a message that does not exist.
+fn unknown_message() -> Response {
+    Response {
+        status: StatusCode::NOT_FOUND,
+        headers: HeaderMap::new(),
+        body: json!({ "code": 10008, "message": "Unknown Message" }).to_string(),
+    }
+}
@@ -3,9 +3,17 @@
 //! `embed` contains the Embed struct used to be sent with messages to the Discord API. Up to 10
 //! embeds can be sent per message.
 
-use crate::client::{Limit, WebhookError};
+use crate::client::{Limit, Violation, WebhookError};
+use crate::color::Color;
+use crate::timestamp::Timestamp;
+use crate::truncate::{self, Truncated, Truncation};
 use serde::{Deserialize, Serialize};
 
+/// Derives `IntoEmbed` for structs with named fields, requires the `derive` feature. See the
+/// `yadwh-derive` crate for the attributes that are supported.
+#[cfg(feature = "derive")]
+pub use yadwh_derive::IntoEmbed;
+
 /// Author information for the embed.
 ///
 /// ## References / Documentation
@@ -119,11 +127,12 @@ pub struct Embed {
     pub url: Option<String>,
     /// Timestamp of the embed content.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub timestamp: Option<String>,
+    pub timestamp: Option<Timestamp>,
     /// color code of the embed.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub color: Option<u32>,
+    pub color: Option<Color>,
     /// Fields information.
+    #[serde(default)]
     pub fields: Vec<EmbedField>,
     /// Footer information.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -140,6 +149,9 @@ pub struct Embed {
     /// Provider information.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider: Option<EmbedProvider>,
+    /// Truncation policy for the embed, inherits the policy of the message if not set.
+    #[serde(skip)]
+    pub truncation: Option<Truncation>,
 }
 
 impl Embed {
@@ -151,79 +163,241 @@ impl Embed {
         }
     }
 
-    /// Validates the Embed does not exceed the maxmium lengths. Returns to the total amount of
-    /// characters within the embed.
+    /// Validates the Embed does not exceed any of the limits within `Limit`, counting characters
+    /// the way Discord does (see `Limit::length`). Returns the total amount of characters within
+    /// the embed. On failure, every exceeded limit is returned within `WebhookError::Invalid`.
     pub fn validate(&self) -> Result<usize, WebhookError> {
-        let too_big = |name: &str, size: usize, max: usize| -> WebhookError {
-            WebhookError::TooBig(name.to_string(), size, max)
+        let mut violations: Vec<Violation> = vec![];
+        let total = self.violations("", &mut violations);
+
+        // Verify the total is less than embed max.
+        Limit::check("embed", total, Limit::EMBED_TOTAL, &mut violations);
+
+        match violations.is_empty() {
+            true => Ok(total),
+            false => Err(WebhookError::Invalid(violations)),
+        }
+    }
+
+    /// Records every limit exceeded by the embed, excluding `Limit::EMBED_TOTAL`. Paths are
+    /// prefixed with `prefix`, such as `embeds[2]`. Returns the total amount of characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Path of the embed, empty if the embed is not nested.
+    /// * `violations` - Collection that exceeded limits are added to.
+    pub(crate) fn violations(&self, prefix: &str, violations: &mut Vec<Violation>) -> usize {
+        let path = |name: &str| join(prefix, name);
+
+        let mut check = |name: &str, text: Option<&str>, max: usize| -> usize {
+            let size = text.map_or(0, Limit::length);
+            Limit::check(&path(name), size, max, violations);
+            size
         };
 
         let mut total: usize = 0;
+        total += check(
+            "author.name",
+            self.author.as_ref().map(|a| a.name.as_str()),
+            Limit::AUTHOR_NAME,
+        );
+        total += check("title", self.title.as_deref(), Limit::TITLE);
+        total += check(
+            "description",
+            self.description.as_deref(),
+            Limit::DESCRIPTION,
+        );
+        total += check(
+            "footer.text",
+            self.footer.as_ref().map(|f| f.text.as_str()),
+            Limit::FOOTER_TEXT,
+        );
 
-        // Check if the author is too large.
-        let author = match &self.author {
-            Some(value) => value.name.len(),
-            None => 0,
-        };
-        total += match author {
-            0..=Limit::AUTHOR_NAME => author,
-            _ => return Err(too_big("author", author, Limit::AUTHOR_NAME)),
-        };
+        // Check all of the fields.
+        for (i, field) in self.fields.iter().enumerate() {
+            let name = format!("fields[{}].name", i);
+            total += check(&name, Some(&field.name), Limit::FIELD_NAME);
 
-        // Check if the title is too large.
-        let title = match &self.title {
-            Some(value) => value.len(),
-            None => 0,
-        };
-        total += match title {
-            0..=Limit::TITLE => title,
-            _ => return Err(too_big("title", title, Limit::TITLE)),
-        };
+            let value = format!("fields[{}].value", i);
+            total += check(&value, Some(&field.value), Limit::FIELD_VALUE);
+        }
 
-        // Check if the description is too large.
-        let desc = match &self.description {
-            Some(value) => value.len(),
-            None => 0,
-        };
-        total += match desc {
-            0..=Limit::DESCRIPTION => desc,
-            _ => return Err(too_big("description", desc, Limit::DESCRIPTION)),
-        };
+        // Check the amount of fields.
+        Limit::check(
+            &path("fields"),
+            self.fields.len(),
+            Limit::FIELDS,
+            violations,
+        );
 
-        // Check if the footer is too large.
-        let footer = match &self.footer {
-            Some(value) => value.text.len(),
-            None => 0,
-        };
-        total += match footer {
-            0..=Limit::FOOTER_TEXT => footer,
-            _ => return Err(too_big("footer", footer, Limit::FOOTER_TEXT)),
-        };
+        total
+    }
 
-        // Check all of the fields.
-        for field in self.fields.iter() {
-            // Check if the name is too large.
-            let name = field.name.len();
-            total += match name {
-                0..=Limit::FIELD_NAME => name,
-                _ => return Err(too_big("field name", name, Limit::FIELD_NAME)),
-            };
+    /// Clips every value that exceeds its limit according to the truncation policy of the embed
+    /// (see `Embed::truncation`), then drops trailing fields to fit within `Limit::EMBED_TOTAL`.
+    /// Returns every value that was cut, nothing is cut if the policy is `Truncation::Error`.
+    pub fn truncate(&mut self) -> Vec<Truncated> {
+        let mut truncated: Vec<Truncated> = vec![];
+        let policy = self.truncation.unwrap_or_default();
+
+        self.truncate_with("", policy, &mut truncated);
+        self.fit("", Limit::EMBED_TOTAL, policy, &mut truncated);
+        truncated
+    }
 
-            // Check if the value is too large.
-            let value = field.value.len();
-            total += match value {
-                0..=Limit::FIELD_VALUE => value,
-                _ => return Err(too_big("field value", value, Limit::FIELD_VALUE)),
+    /// Clips every value that exceeds its limit and drops fields beyond `Limit::FIELDS`. Paths
+    /// are prefixed with `prefix`, such as `embeds[2]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Path of the embed, empty if the embed is not nested.
+    /// * `policy` - Truncation policy to apply.
+    /// * `truncated` - Collection that cut values are added to.
+    pub(crate) fn truncate_with(
+        &mut self,
+        prefix: &str,
+        policy: Truncation,
+        truncated: &mut Vec<Truncated>,
+    ) {
+        if policy == Truncation::Error {
+            return;
+        }
+
+        let path = |name: &str| join(prefix, name);
+
+        if let Some(author) = &mut self.author {
+            let name = &mut author.name;
+            truncate::clip(
+                &path("author.name"),
+                name,
+                Limit::AUTHOR_NAME,
+                policy,
+                truncated,
+            );
+        }
+
+        if let Some(title) = &mut self.title {
+            truncate::clip(&path("title"), title, Limit::TITLE, policy, truncated);
+        }
+
+        if let Some(desc) = &mut self.description {
+            truncate::clip(
+                &path("description"),
+                desc,
+                Limit::DESCRIPTION,
+                policy,
+                truncated,
+            );
+        }
+
+        if let Some(footer) = &mut self.footer {
+            let text = &mut footer.text;
+            truncate::clip(
+                &path("footer.text"),
+                text,
+                Limit::FOOTER_TEXT,
+                policy,
+                truncated,
+            );
+        }
+
+        for (i, field) in self.fields.iter_mut().enumerate() {
+            let name = path(&format!("fields[{}].name", i));
+            truncate::clip(&name, &mut field.name, Limit::FIELD_NAME, policy, truncated);
+
+            let value = path(&format!("fields[{}].value", i));
+            truncate::clip(
+                &value,
+                &mut field.value,
+                Limit::FIELD_VALUE,
+                policy,
+                truncated,
+            );
+        }
+
+        // Drop any fields beyond the limit.
+        if self.fields.len() > Limit::FIELDS {
+            for (i, field) in self.fields.split_off(Limit::FIELDS).iter().enumerate() {
+                truncated.push(Truncated {
+                    path: path(&format!("fields[{}]", Limit::FIELDS + i)),
+                    size: Limit::length(&field.name) + Limit::length(&field.value),
+                    kept: 0,
+                });
+            }
+        }
+    }
+
+    /// Drops trailing fields until the embed is at most `max` characters. If that is not enough,
+    /// the description is clipped as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Path of the embed, empty if the embed is not nested.
+    /// * `max` - Maximum total characters for the embed.
+    /// * `policy` - Truncation policy to apply.
+    /// * `truncated` - Collection that cut values are added to.
+    pub(crate) fn fit(
+        &mut self,
+        prefix: &str,
+        max: usize,
+        policy: Truncation,
+        truncated: &mut Vec<Truncated>,
+    ) {
+        if policy == Truncation::Error {
+            return;
+        }
+
+        // Drop trailing fields until it fits.
+        while self.length() > max {
+            let field = match self.fields.pop() {
+                Some(value) => value,
+                None => break,
             };
+
+            truncated.push(Truncated {
+                path: join(prefix, &format!("fields[{}]", self.fields.len())),
+                size: Limit::length(&field.name) + Limit::length(&field.value),
+                kept: 0,
+            });
         }
 
-        // Verify the total is less than embed max.
-        match total {
-            0..=Limit::EMBED_TOTAL => Ok(total),
-            _ => Err(too_big("embed", total, Limit::EMBED_TOTAL)),
+        // Clip the description with whatever is still in excess.
+        let excess = self.length().saturating_sub(max);
+        if let (true, Some(desc)) = (excess > 0, &mut self.description) {
+            let keep = Limit::length(desc).saturating_sub(excess);
+            truncate::clip(&join(prefix, "description"), desc, keep, policy, truncated);
         }
     }
 
+    /// Obtains the total amount of characters within the embed that count towards
+    /// `Limit::EMBED_TOTAL`. This does not validate the embed.
+    pub fn length(&self) -> usize {
+        let size = |text: Option<&str>| text.map_or(0, Limit::length);
+
+        let author = size(self.author.as_ref().map(|value| value.name.as_str()));
+        let title = size(self.title.as_deref());
+        let desc = size(self.description.as_deref());
+        let footer = size(self.footer.as_ref().map(|value| value.text.as_str()));
+        let fields: usize = self
+            .fields
+            .iter()
+            .map(|field| Limit::length(&field.name) + Limit::length(&field.value))
+            .sum();
+
+        author + title + desc + footer + fields
+    }
+
+    /// Sets the truncation policy for the Embed. When enabled, values exceeding their limits are
+    /// clipped by `Embed::truncate` instead of failing validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Truncation policy for the embed.
+    pub fn truncation(&mut self, policy: Truncation) -> &mut Self {
+        self.truncation = Some(policy);
+        self
+    }
+
     /// Sets the title for the Embed.
     ///
     /// # Arguments
@@ -254,38 +428,25 @@ impl Embed {
         self
     }
 
-    /// Sets the timestamp for the Embed.
+    /// Sets the timestamp for the Embed. Accepts a `Timestamp`, a `SystemTime`, or a `chrono` or
+    /// `time` type if their features are enabled. Use `Timestamp::parse` for ISO8601 strings.
     ///
     /// # Arguments
     ///
     /// * `timestamp` - Timestamp to assign to the embed.
-    pub fn timestamp(&mut self, timestamp: &str) -> &mut Self {
-        self.timestamp = Some(timestamp.to_string());
+    pub fn timestamp<T: Into<Timestamp>>(&mut self, timestamp: T) -> &mut Self {
+        self.timestamp = Some(timestamp.into());
         self
     }
 
-    /// Sets the color (in hex, such as AA11BB or #AA11BB) for the Embed.
+    /// Sets the color for the Embed. Accepts a `Color`, a `u32` such as 0xAA11BB, or an RGB
+    /// tuple. Use `Color::from_hex` to parse hex such as AA11BB or #AA11BB.
     ///
     /// # Arguments
     ///
     /// * `color` - Color to assign to the embed.
-    pub fn color(&mut self, color: &str) -> &mut Self {
-        // Remove the '#' prefix if it exists.
-        let color_hex = match color.is_empty() {
-            true => return self,
-            false => match color.strip_prefix('#') {
-                Some(value) => value,
-                None => color,
-            },
-        };
-
-        // Convert the HEX color to u32.
-        let color_u32: u32 = match u32::from_str_radix(color_hex, 16) {
-            Ok(value) => value,
-            Err(_) => return self,
-        };
-        self.color = Some(color_u32);
-
+    pub fn color<C: Into<Color>>(&mut self, color: C) -> &mut Self {
+        self.color = Some(color.into());
         self
     }
 
@@ -442,3 +603,27 @@ impl Embed {
         self
     }
 }
+
+/// Converts a value into an embed, such as an event that is reported through a webhook. Usually
+/// implemented with `#[derive(IntoEmbed)]` and the `derive` feature.
+///
+/// The conversion borrows the value, hence `to_embed` rather than `into_embed`, so the same value
+/// can be reported to several webhooks or kept after it is sent.
+pub trait IntoEmbed {
+    /// Creates an embed describing the value.
+    fn to_embed(&self) -> Embed;
+}
+
+impl IntoEmbed for Embed {
+    fn to_embed(&self) -> Embed {
+        self.clone()
+    }
+}
+
+/// Joins the path of a value to the path of the embed it belongs to.
+fn join(prefix: &str, name: &str) -> String {
+    match prefix.is_empty() {
+        true => name.to_string(),
+        false => format!("{}.{}", prefix, name),
+    }
+}
@@ -0,0 +1,338 @@
+//! Failover between a primary webhook and its backups.
+//!
+//! `failover` contains `Failover`, which sends messages to a primary `Target` and falls back to
+//! backup targets in order when the error is of a configured `ErrorClass`, such as the webhook
+//! being deleted or the channel being locked. Targets that fail are skipped for a cool-down so
+//! later messages go straight to a working backup, and every delivery reports which target sent it.
+//!
+//! ## References / Documentation
+//!
+//! <https://discord.com/developers/docs/topics/opcodes-and-status-codes#json>
+
+use crate::client::{Result, WebhookError};
+use crate::group::Target;
+use crate::message::{Message, MessageBuilder};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default time a target is skipped after failing.
+const COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// Kind of error that causes a `Failover` to try the next target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// The webhook was deleted, Discord error code 10015.
+    UnknownWebhook,
+    /// The channel or thread cannot be posted in: Unknown Channel (10003), Missing Access (50001),
+    /// Missing Permissions (50013), archived thread (50083), or locked thread (160005).
+    ChannelLocked,
+    /// Rate limited and no retries remained. The target is skipped until the rate limit resets.
+    RateLimited,
+    /// Discord responded with a server error, a 5xx status.
+    ServerError,
+    /// The request could not be sent or the response could not be received.
+    Network,
+}
+
+impl ErrorClass {
+    /// Every class, the default for `Failover` excludes `RateLimited`.
+    pub const ALL: [ErrorClass; 5] = [
+        Self::UnknownWebhook,
+        Self::ChannelLocked,
+        Self::RateLimited,
+        Self::ServerError,
+        Self::Network,
+    ];
+
+    /// Determines the class of an error, `None` for errors that backups would fail on as well,
+    /// such as an invalid message.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Error returned while sending to a target.
+    pub fn of(error: &WebhookError) -> Option<Self> {
+        match error {
+            WebhookError::Discord(error) => match error.code {
+                10015 => Some(Self::UnknownWebhook),
+                10003 | 50001 | 50013 | 50083 | 160005 => Some(Self::ChannelLocked),
+                _ if error.status >= 500 => Some(Self::ServerError),
+                _ => None,
+            },
+            WebhookError::RateLimited(_) => Some(Self::RateLimited),
+            // Responses without a Discord error only include the status, as "Status Code: 503".
+            WebhookError::BadStatus(status) => {
+                let code = status
+                    .strip_prefix("Status Code: ")
+                    .and_then(|code| code.parse::<u16>().ok());
+                match code {
+                    Some(code) if code >= 500 => Some(Self::ServerError),
+                    _ => None,
+                }
+            }
+            WebhookError::Transport(_) => Some(Self::Network),
+            _ => None,
+        }
+    }
+}
+
+/// Error from a target that was failed over from.
+#[derive(Debug)]
+pub struct Attempt {
+    /// Name of the target.
+    pub target: String,
+    /// Class of the error.
+    pub class: ErrorClass,
+    /// Error returned by the target.
+    pub error: WebhookError,
+}
+
+/// Message delivered by a `Failover`, along with which target delivered it.
+#[derive(Debug)]
+pub struct Routed {
+    /// Name of the target that delivered the message.
+    pub target: String,
+    /// Position of the target, 0 is the primary.
+    pub index: usize,
+    /// Message that was created.
+    pub message: Message,
+    /// Targets that failed before the message was delivered, in the order they were tried.
+    pub attempts: Vec<Attempt>,
+}
+
+impl Routed {
+    /// Whether the message was delivered by a backup rather than the primary.
+    pub fn is_backup(&self) -> bool {
+        self.index > 0
+    }
+}
+
+/// Sends messages to a primary webhook, falling back to backups on configured error classes.
+pub struct Failover {
+    /// Primary target followed by backups, in the order they are tried.
+    targets: Vec<Target>,
+    /// When each target may be tried again, `None` if it is healthy.
+    unhealthy: Mutex<Vec<Option<Instant>>>,
+    /// Classes of errors that cause the next target to be tried.
+    classes: HashSet<ErrorClass>,
+    /// Time a target is skipped after failing.
+    cooldown: Duration,
+}
+
+impl Failover {
+    /// Creates a failover for a primary target. By default every `ErrorClass` except
+    /// `RateLimited` fails over, and failed targets are skipped for 5 minutes.
+    ///
+    /// # Arguments
+    ///
+    /// * `primary` - Target that messages are sent to while it is healthy.
+    pub fn new(primary: Target) -> Self {
+        let classes = ErrorClass::ALL
+            .into_iter()
+            .filter(|class| *class != ErrorClass::RateLimited)
+            .collect();
+
+        Self {
+            targets: vec![primary],
+            unhealthy: Mutex::new(vec![None]),
+            classes,
+            cooldown: COOLDOWN,
+        }
+    }
+
+    /// Adds a backup target, tried in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `backup` - Target used when the ones before it fail.
+    pub fn backup(mut self, backup: Target) -> Self {
+        self.targets.push(backup);
+        self.unhealthy.get_mut().unwrap().push(None);
+        self
+    }
+
+    /// Sets the classes of errors that cause the next target to be tried, other errors are
+    /// returned immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `classes` - Classes of errors to fail over on.
+    pub fn fail_on(mut self, classes: &[ErrorClass]) -> Self {
+        self.classes = classes.iter().copied().collect();
+        self
+    }
+
+    /// Sets how long a target is skipped after failing. Rate limited targets are skipped until
+    /// the rate limit resets instead, if Discord provided when that is.
+    ///
+    /// # Arguments
+    ///
+    /// * `cooldown` - Time a target is skipped after failing.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Targets in the order they are tried, starting with the primary.
+    pub fn targets(&self) -> &[Target] {
+        &self.targets
+    }
+
+    /// Targets that are cooling down, along with the time remaining until they are tried again.
+    pub fn unhealthy(&self) -> Vec<(&str, Duration)> {
+        let now = Instant::now();
+        let unhealthy = self.unhealthy.lock().unwrap_or_else(|e| e.into_inner());
+        self.targets
+            .iter()
+            .zip(unhealthy.iter())
+            .filter_map(|(target, until)| match until {
+                Some(until) if *until > now => Some((target.name(), *until - now)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Marks every target as healthy, such as after a webhook has been replaced.
+    pub fn reset(&self) {
+        let mut unhealthy = self.unhealthy.lock().unwrap_or_else(|e| e.into_inner());
+        unhealthy.iter_mut().for_each(|until| *until = None);
+    }
+
+    /// Sends a message to the first healthy target, failing over to the next on the configured
+    /// error classes. Targets that are cooling down are skipped, unless every target is, in which
+    /// case all of them are tried in order rather than dropping the message.
+    ///
+    /// Errors outside of the configured classes are returned immediately. If every target fails,
+    /// the error of the last target tried is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message to send, with the overrides of the target that delivers it applied.
+    pub async fn send(&self, message: &MessageBuilder) -> Result<Routed> {
+        let order = self.order();
+        let mut attempts: Vec<Attempt> = vec![];
+
+        for index in order {
+            let target = &self.targets[index];
+            let result = match target.prepare(message) {
+                Ok(message) => target.create(&message).await,
+                Err(error) => Err(error),
+            };
+
+            let error = match result {
+                Ok(message) => {
+                    self.mark(index, None);
+                    return Ok(Routed {
+                        target: target.name().to_string(),
+                        index,
+                        message,
+                        attempts,
+                    });
+                }
+                Err(error) => error,
+            };
+
+            let class = match ErrorClass::of(&error) {
+                Some(class) if self.classes.contains(&class) => class,
+                _ => return Err(error),
+            };
+
+            let cooldown = match &error {
+                WebhookError::RateLimited(limit) => limit.retry_after.unwrap_or(self.cooldown),
+                _ => self.cooldown,
+            };
+            self.mark(index, Some(Instant::now() + cooldown));
+
+            attempts.push(Attempt {
+                target: target.name().to_string(),
+                class,
+                error,
+            });
+        }
+
+        // There is always a primary, so at least one target was tried.
+        let last = attempts.pop().expect("failover has a primary target");
+        Err(last.error)
+    }
+
+    /// Indexes of the healthy targets in the order they are tried, or every target if none are.
+    fn order(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let unhealthy = self.unhealthy.lock().unwrap_or_else(|e| e.into_inner());
+        let healthy: Vec<usize> = (0..self.targets.len())
+            .filter(|i| unhealthy[*i].is_none_or(|until| until <= now))
+            .collect();
+
+        match healthy.is_empty() {
+            true => (0..self.targets.len()).collect(),
+            false => healthy,
+        }
+    }
+
+    /// Sets when a target may be tried again, `None` for healthy.
+    fn mark(&self, index: usize, until: Option<Instant>) {
+        self.unhealthy.lock().unwrap_or_else(|e| e.into_inner())[index] = until;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{DiscordError, RateLimit};
+
+    fn discord(status: u16, code: u32) -> WebhookError {
+        WebhookError::Discord(DiscordError {
+            status,
+            code,
+            message: String::new(),
+        })
+    }
+
+    #[test]
+    fn classifies_errors() {
+        assert_eq!(
+            ErrorClass::of(&discord(404, 10015)),
+            Some(ErrorClass::UnknownWebhook)
+        );
+        for code in [10003, 50001, 50013, 50083, 160005] {
+            assert_eq!(
+                ErrorClass::of(&discord(403, code)),
+                Some(ErrorClass::ChannelLocked),
+                "code {}",
+                code
+            );
+        }
+        assert_eq!(
+            ErrorClass::of(&discord(502, 0)),
+            Some(ErrorClass::ServerError)
+        );
+        assert_eq!(
+            ErrorClass::of(&WebhookError::RateLimited(RateLimit::default())),
+            Some(ErrorClass::RateLimited)
+        );
+        assert_eq!(
+            ErrorClass::of(&WebhookError::BadStatus("Status Code: 503".to_string())),
+            Some(ErrorClass::ServerError)
+        );
+        assert_eq!(
+            ErrorClass::of(&WebhookError::Transport("connection refused".to_string())),
+            Some(ErrorClass::Network)
+        );
+    }
+
+    #[test]
+    fn leaves_errors_backups_would_repeat_unclassified() {
+        for error in [
+            discord(400, 50035),
+            discord(401, 50027),
+            WebhookError::BadStatus("Status Code: 404".to_string()),
+            WebhookError::BadStatus("unexpected".to_string()),
+            WebhookError::Unknown("building request".to_string()),
+            WebhookError::BadParse("create response".to_string()),
+            WebhookError::Invalid(vec![]),
+            WebhookError::NoContent,
+        ] {
+            assert_eq!(ErrorClass::of(&error), None, "{:?}", error);
+        }
+    }
+}
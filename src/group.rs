@@ -0,0 +1,340 @@
+//! Sending one message to many webhooks at once.
+//!
+//! `group` contains `WebhookGroup`, which holds several `WebhookApi`s as named `Target`s and sends
+//! a `MessageBuilder` to all of them concurrently, with a bound on how many requests are in flight.
+//! Targets can override the thread, username, and avatar of the message. Every target is reported
+//! in a `GroupReport`, and a `GroupPolicy` decides whether a failure undoes the other deliveries.
+
+use crate::client::{Result, WebhookError};
+use crate::message::{Message, MessageBuilder};
+use crate::snowflake::ChannelId;
+use crate::webhook::WebhookApi;
+use futures::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Webhook within a `WebhookGroup`, with optional overrides for messages sent through it.
+pub struct Target {
+    /// Name used to identify the target within reports.
+    name: String,
+    /// Client used to send messages.
+    api: WebhookApi,
+    /// Thread the message is sent in.
+    thread_id: Option<ChannelId>,
+    /// Overrides the username of the message.
+    username: Option<String>,
+    /// Overrides the avatar of the message.
+    avatar_url: Option<String>,
+}
+
+impl Target {
+    /// Creates a target without overrides.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name used to identify the target within reports, such as the server it posts to.
+    /// * `api` - Client used to send messages.
+    pub fn new(name: &str, api: WebhookApi) -> Self {
+        Self {
+            name: name.to_string(),
+            api,
+            thread_id: None,
+            username: None,
+            avatar_url: None,
+        }
+    }
+
+    /// Sends messages within a thread, such as a post within a Forum Channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id` - Thread to send messages in.
+    pub fn thread(mut self, thread_id: ChannelId) -> Self {
+        self.thread_id = Some(thread_id);
+        self
+    }
+
+    /// Overrides the username of messages sent to this target.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - Username displayed for the message.
+    pub fn username(mut self, username: &str) -> Self {
+        self.username = Some(username.to_string());
+        self
+    }
+
+    /// Overrides the avatar of messages sent to this target.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the avatar displayed for the message.
+    pub fn avatar_url(mut self, url: &str) -> Self {
+        self.avatar_url = Some(url.to_string());
+        self
+    }
+
+    /// Name used to identify the target within reports.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Applies the overrides of the target to a message, then truncates and validates it.
+    pub(crate) fn prepare(&self, message: &MessageBuilder) -> Result<MessageBuilder> {
+        let mut message = message.clone();
+        if let Some(username) = &self.username {
+            message = message.username(username)?;
+        }
+        if let Some(url) = &self.avatar_url {
+            message = message.avatar_url(url);
+        }
+
+        message.truncate();
+        message.validate()?;
+        Ok(message)
+    }
+
+    /// Creates a message that was prepared with `Target::prepare`.
+    pub(crate) async fn create(&self, message: &MessageBuilder) -> Result<Message> {
+        self.api.message.create(message, self.thread_id).await
+    }
+}
+
+/// Decides what happens to the other targets when one of them fails.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GroupPolicy {
+    /// Send to every target regardless of failures, this is the default.
+    #[default]
+    BestEffort,
+    /// Every target must succeed. The message is validated for every target before anything is
+    /// sent, no new sends are started after a failure, and messages that were already delivered
+    /// are deleted.
+    AllOrNothing,
+}
+
+/// Outcome of sending to a single target.
+#[derive(Debug)]
+pub enum Outcome {
+    /// The message was created.
+    Delivered(Message),
+    /// The message could not be created.
+    Failed(WebhookError),
+    /// Nothing was sent, as another target failed with `GroupPolicy::AllOrNothing`.
+    Skipped,
+    /// The message was created then deleted, as another target failed with
+    /// `GroupPolicy::AllOrNothing`.
+    RolledBack(Message),
+}
+
+/// Result of sending to a single target.
+#[derive(Debug)]
+pub struct Delivery {
+    /// Name of the target.
+    pub target: String,
+    /// What happened to the message.
+    pub outcome: Outcome,
+}
+
+/// Results of sending to every target within a `WebhookGroup`, in the order they were added.
+#[derive(Debug)]
+pub struct GroupReport {
+    /// Result for each target.
+    pub deliveries: Vec<Delivery>,
+}
+
+impl GroupReport {
+    /// Whether the message was delivered to every target.
+    pub fn is_success(&self) -> bool {
+        self.deliveries
+            .iter()
+            .all(|delivery| matches!(delivery.outcome, Outcome::Delivered(_)))
+    }
+
+    /// Messages that were delivered and still exist, along with the name of their target.
+    pub fn delivered(&self) -> impl Iterator<Item = (&str, &Message)> {
+        self.deliveries
+            .iter()
+            .filter_map(|delivery| match &delivery.outcome {
+                Outcome::Delivered(message) => Some((delivery.target.as_str(), message)),
+                _ => None,
+            })
+    }
+
+    /// Errors for targets that failed, along with the name of their target.
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &WebhookError)> {
+        self.deliveries
+            .iter()
+            .filter_map(|delivery| match &delivery.outcome {
+                Outcome::Failed(error) => Some((delivery.target.as_str(), error)),
+                _ => None,
+            })
+    }
+
+    /// Converts the report into the delivered messages, or the error of the first target that
+    /// failed.
+    pub fn into_result(self) -> Result<Vec<Message>> {
+        let mut messages: Vec<Message> = vec![];
+        for delivery in self.deliveries {
+            match delivery.outcome {
+                Outcome::Delivered(message) => messages.push(message),
+                Outcome::Failed(error) => return Err(error),
+                Outcome::Skipped | Outcome::RolledBack(_) => (),
+            }
+        }
+        Ok(messages)
+    }
+}
+
+/// Group of webhooks that messages are sent to concurrently, such as mirrors of an alert channel
+/// within several servers.
+pub struct WebhookGroup {
+    /// Webhooks messages are sent to.
+    targets: Vec<Target>,
+    /// Maximum amount of requests in flight at once.
+    concurrency: usize,
+    /// What happens to the other targets when one fails.
+    policy: GroupPolicy,
+}
+
+impl WebhookGroup {
+    /// Creates an empty group that sends to up to 4 targets at once, with
+    /// `GroupPolicy::BestEffort`.
+    pub fn new() -> Self {
+        Self {
+            targets: vec![],
+            concurrency: 4,
+            policy: GroupPolicy::BestEffort,
+        }
+    }
+
+    /// Adds a target, see `Target` for overrides.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - Webhook to send messages to.
+    pub fn target(mut self, target: Target) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    /// Adds a webhook without overrides.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name used to identify the webhook within reports.
+    /// * `api` - Client used to send messages.
+    pub fn webhook(self, name: &str, api: WebhookApi) -> Self {
+        self.target(Target::new(name, api))
+    }
+
+    /// Sets the maximum amount of requests in flight at once, at least 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - Maximum amount of concurrent requests.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Sets what happens to the other targets when one fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Policy for failures, see `GroupPolicy`.
+    pub fn policy(mut self, policy: GroupPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Targets within the group, in the order they were added.
+    pub fn targets(&self) -> &[Target] {
+        &self.targets
+    }
+
+    /// Sends a message to every target, applying the overrides of each. The report contains the
+    /// outcome for every target in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message to send to every target.
+    pub async fn send(&self, message: &MessageBuilder) -> GroupReport {
+        let all = self.policy == GroupPolicy::AllOrNothing;
+        let prepared: Vec<Result<MessageBuilder>> = self
+            .targets
+            .iter()
+            .map(|target| target.prepare(message))
+            .collect();
+
+        // Nothing is sent if the message is invalid for any target.
+        let failed = AtomicBool::new(all && prepared.iter().any(|message| message.is_err()));
+
+        let mut outcomes: Vec<(usize, Outcome)> = stream::iter(prepared.into_iter().enumerate())
+            .map(|(i, message)| {
+                let target = &self.targets[i];
+                let failed = &failed;
+                async move {
+                    let message = match message {
+                        Ok(_) if all && failed.load(Ordering::SeqCst) => {
+                            return (i, Outcome::Skipped)
+                        }
+                        Ok(message) => message,
+                        Err(error) => return (i, Outcome::Failed(error)),
+                    };
+
+                    match target.create(&message).await {
+                        Ok(message) => (i, Outcome::Delivered(message)),
+                        Err(error) => {
+                            failed.store(true, Ordering::SeqCst);
+                            (i, Outcome::Failed(error))
+                        }
+                    }
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        if all && failed.load(Ordering::SeqCst) {
+            outcomes = self.rollback(outcomes).await;
+        }
+
+        outcomes.sort_by_key(|(i, _)| *i);
+        GroupReport {
+            deliveries: outcomes
+                .into_iter()
+                .map(|(i, outcome)| Delivery {
+                    target: self.targets[i].name.clone(),
+                    outcome,
+                })
+                .collect(),
+        }
+    }
+
+    /// Deletes delivered messages. Those that could not be deleted remain delivered.
+    async fn rollback(&self, outcomes: Vec<(usize, Outcome)>) -> Vec<(usize, Outcome)> {
+        stream::iter(outcomes)
+            .map(|(i, outcome)| async move {
+                let message = match outcome {
+                    Outcome::Delivered(message) => message,
+                    outcome => return (i, outcome),
+                };
+
+                let target = &self.targets[i];
+                let thread_id = target.thread_id.or(target.api.message.thread_id);
+                let api = target.api.message.clone().in_thread(thread_id);
+                match api.delete(message.id).await {
+                    Ok(_) => (i, Outcome::RolledBack(message)),
+                    Err(_) => (i, Outcome::Delivered(message)),
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await
+    }
+}
+
+impl Default for WebhookGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -7,10 +7,36 @@
 #![cfg_attr(all(test, feature = "full"), deny(unreachable_pub))]
 #![cfg_attr(all(test, feature = "full"), deny(warnings))]
 
+pub mod attachment;
+pub mod cassette;
 mod client;
+pub mod color;
+pub mod component;
+pub mod dryrun;
 pub mod embed;
+pub mod failover;
+pub mod group;
+pub mod live;
+pub mod load;
+pub mod markdown;
+pub mod mention;
 pub mod message;
+pub mod middleware;
+pub mod observe;
+pub mod paginate;
+pub mod preview;
+pub mod secret;
+pub mod snowflake;
+pub mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod timestamp;
+pub mod transport;
+pub mod truncate;
+pub mod url;
 pub mod webhook;
+pub mod widget;
 
-pub use crate::client::{Limit, Result, WebhookError};
+pub use crate::client::{DiscordError, Limit, RateLimit, Result, Violation, WebhookError};
+pub use crate::url::WebhookUrl;
 pub use crate::webhook::WebhookApi;
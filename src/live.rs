@@ -0,0 +1,468 @@
+//! Live messages that are edited in place as work progresses.
+//!
+//! `live` contains `LiveMessage`, a handle created by `MessageApi::create_live` that remembers the
+//! ID and thread of the message it posted. Changes are made to its `MessageBuilder` with
+//! `LiveMessage::update` and pushed with `MessageApi::edit`. Updates made faster than the
+//! interval between edits are coalesced, only the latest state is sent once the interval passes,
+//! keeping a chatty deploy script under Discord's rate limits.
+//!
+//! Coalesced updates are pushed by a background task, so the final state is sent even if no
+//! further updates are made or the handle is dropped. `LiveMessage::flush` and
+//! `LiveMessage::finish` push it immediately instead, waiting for the rest of the interval.
+//!
+//! ## References / Documentation
+//!
+//! <https://discord.com/developers/docs/resources/webhook#edit-webhook-message>
+
+use crate::client::{Result, WebhookError};
+use crate::message::{Message, MessageApi, MessageBuilder};
+use crate::snowflake::{ChannelId, MessageId};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Default time between edits, Discord allows roughly five edits to a message every five seconds.
+const INTERVAL: Duration = Duration::from_secs(1);
+
+/// Handle to a message that is kept up to date by editing it, see the module documentation.
+pub struct LiveMessage {
+    /// Used to edit and delete the message, within its thread.
+    api: MessageApi,
+    /// ID of the message.
+    id: MessageId,
+    /// Minimum time between edits.
+    interval: Duration,
+    /// State shared with the task pushing coalesced updates.
+    state: Arc<Mutex<State>>,
+}
+
+/// State of a live message, shared with the task pushing coalesced updates.
+struct State {
+    /// Message as last returned by Discord.
+    message: Message,
+    /// Current state of the message, including changes that have not been pushed.
+    builder: MessageBuilder,
+    /// When the message was last created or edited.
+    pushed: Instant,
+    /// Incremented by every change to the builder.
+    version: u64,
+    /// Version of the builder that was last pushed.
+    pushed_version: u64,
+    /// Task that pushes the latest state once the interval passes, if one is scheduled.
+    timer: Option<JoinHandle<()>>,
+    /// Error from a push made by the task, returned by the next call.
+    error: Option<WebhookError>,
+}
+
+impl State {
+    /// Whether the builder has changes that have not been pushed.
+    fn pending(&self) -> bool {
+        self.version != self.pushed_version
+    }
+
+    /// Stores the result of pushing a version of the builder.
+    fn record(&mut self, result: Result<Message>, version: u64) -> Result<()> {
+        // The interval starts over even if the edit fails, so a failing edit is not retried in a
+        // tight loop by the next update.
+        self.pushed = Instant::now();
+        self.message = result?;
+        self.pushed_version = version;
+        self.builder.files.clear();
+        Ok(())
+    }
+}
+
+impl MessageApi {
+    /// Creates a message and returns a `LiveMessage` used to keep editing it. Files are uploaded
+    /// with the first message only, later edits keep the existing attachments.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Initial state of the message.
+    /// * `thread_id` - Thread to post and edit the message in, otherwise the default thread is used.
+    ///
+    /// ## References / Documentation
+    ///
+    /// <https://discord.com/developers/docs/resources/webhook#execute-webhook>
+    pub async fn create_live(
+        &self,
+        message: &MessageBuilder,
+        thread_id: Option<ChannelId>,
+    ) -> Result<LiveMessage> {
+        let api = self.clone().in_thread(thread_id.or(self.thread_id));
+        let created = api.create(message, None).await?;
+
+        let mut builder = message.clone();
+        builder.files.clear();
+        Ok(LiveMessage {
+            api,
+            id: created.id,
+            interval: INTERVAL,
+            state: Arc::new(Mutex::new(State {
+                message: created,
+                builder,
+                pushed: Instant::now(),
+                version: 0,
+                pushed_version: 0,
+                timer: None,
+                error: None,
+            })),
+        })
+    }
+}
+
+impl LiveMessage {
+    /// Sets the minimum time between edits, updates within it are coalesced. Defaults to 1 second.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Minimum time between edits, zero pushes every update.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// ID of the message.
+    pub fn id(&self) -> MessageId {
+        self.id
+    }
+
+    /// Thread the message was posted in, if any.
+    pub fn thread_id(&self) -> Option<ChannelId> {
+        self.api.thread_id
+    }
+
+    /// Message as last returned by Discord, this does not include pending changes.
+    pub fn message(&self) -> Message {
+        self.lock().message.clone()
+    }
+
+    /// Current state of the message, including pending changes.
+    pub fn builder(&self) -> MessageBuilder {
+        self.lock().builder.clone()
+    }
+
+    /// Whether there are changes that have not been pushed yet.
+    pub fn pending(&self) -> bool {
+        self.lock().pending()
+    }
+
+    /// Changes the message and pushes it if the interval since the last edit has passed.
+    /// Otherwise the change is pushed in the background once the interval passes, along with any
+    /// later changes. Returns whether the message was edited by this call.
+    ///
+    /// An error from a push made in the background is returned by the next call to `update`,
+    /// `flush`, or `finish`. The change is still kept and pushed later.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - Changes the message, such as `|message| message.embeds[0].description("...")`.
+    pub async fn update<Func>(&mut self, func: Func) -> Result<bool>
+    where
+        Func: FnOnce(&mut MessageBuilder),
+    {
+        {
+            let mut state = self.lock();
+            func(&mut state.builder);
+            state.version += 1;
+
+            if let Some(error) = state.error.take() {
+                return Err(error);
+            }
+
+            // A scheduled push sends the latest state, including this change.
+            if state.timer.is_some() {
+                return Ok(false);
+            }
+
+            let wait = self.interval.saturating_sub(state.pushed.elapsed());
+            if !wait.is_zero() {
+                state.timer = Some(self.schedule(wait));
+                return Ok(false);
+            }
+        }
+
+        self.push().await.map(|_| true)
+    }
+
+    /// Replaces the message and pushes it if the interval since the last edit has passed, see
+    /// `LiveMessage::update`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - New state of the message.
+    pub async fn replace(&mut self, message: MessageBuilder) -> Result<bool> {
+        self.update(|builder| *builder = message).await
+    }
+
+    /// Pushes pending changes now rather than in the background, waiting for the rest of the
+    /// interval if needed. Returns the message as last returned by Discord.
+    pub async fn flush(&mut self) -> Result<Message> {
+        self.cancel().await;
+
+        let wait = {
+            let mut state = self.lock();
+            if let Some(error) = state.error.take() {
+                return Err(error);
+            }
+            if !state.pending() {
+                return Ok(state.message.clone());
+            }
+            self.interval.saturating_sub(state.pushed.elapsed())
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        self.push().await?;
+        Ok(self.message())
+    }
+
+    /// Makes a final change and pushes it along with any pending changes, waiting for the rest
+    /// of the interval if needed. Returns the final message.
+    ///
+    /// # Arguments
+    ///
+    /// * `func` - Changes the message, such as marking the deploy as complete.
+    pub async fn finish<Func>(mut self, func: Func) -> Result<Message>
+    where
+        Func: FnOnce(&mut MessageBuilder),
+    {
+        {
+            let mut state = self.lock();
+            func(&mut state.builder);
+            state.version += 1;
+        }
+        self.flush().await
+    }
+
+    /// Deletes the message, discarding any pending changes.
+    pub async fn delete(self) -> Result<()> {
+        self.cancel().await;
+        self.api.delete(self.id).await
+    }
+
+    /// Edits the message with the current state.
+    async fn push(&self) -> Result<()> {
+        let (builder, version) = snapshot(&self.state);
+        let result = self.api.edit(self.id, &builder).await;
+        lock(&self.state).record(result, version)
+    }
+
+    /// Spawns a task that pushes the latest state after waiting, and again after each interval
+    /// while changes keep arriving during the edits.
+    ///
+    /// # Arguments
+    ///
+    /// * `wait` - Time until the first push.
+    fn schedule(&self, wait: Duration) -> JoinHandle<()> {
+        let api = self.api.clone();
+        let id = self.id;
+        let interval = self.interval;
+        let state = Arc::clone(&self.state);
+
+        tokio::spawn(async move {
+            let mut wait = wait;
+            loop {
+                tokio::time::sleep(wait).await;
+                let (builder, version) = snapshot(&state);
+                let result = api.edit(id, &builder).await;
+
+                let mut state = lock(&state);
+                let result = state.record(result, version);
+                if let Err(error) = result {
+                    state.error = Some(error);
+                }
+                if state.error.is_some() || !state.pending() {
+                    state.timer = None;
+                    return;
+                }
+                wait = interval;
+            }
+        })
+    }
+
+    /// Stops the scheduled push, if any, waiting for it to stop.
+    async fn cancel(&self) {
+        let timer = self.lock().timer.take();
+        if let Some(timer) = timer {
+            timer.abort();
+            let _ = timer.await;
+        }
+    }
+
+    /// Locks the shared state, ignoring poisoning.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        lock(&self.state)
+    }
+}
+
+/// Locks the shared state, ignoring poisoning.
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Copies the current state of the builder along with its version.
+fn snapshot(state: &Mutex<State>) -> (MessageBuilder, u64) {
+    let state = lock(state);
+    (state.builder.clone(), state.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dryrun::DryRun;
+    use crate::middleware::{Middleware, Request};
+    use crate::observe::Operation;
+    use crate::snowflake::WebhookId;
+    use crate::webhook::WebhookApi;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    const SHORT: Duration = Duration::from_millis(100);
+
+    /// Rejects edits while enabled.
+    struct FailEdits(Arc<AtomicBool>);
+
+    impl Middleware for FailEdits {
+        fn before_send(&self, request: &mut Request) -> Result<()> {
+            match request.operation == Operation::EditMessage && self.0.load(Ordering::SeqCst) {
+                true => Err(WebhookError::Unknown("edit rejected".to_string())),
+                false => Ok(()),
+            }
+        }
+    }
+
+    /// Contents of every edit that was sent.
+    fn edits(dry_run: &DryRun) -> Vec<String> {
+        dry_run
+            .captured()
+            .iter()
+            .filter(|request| request.operation == Operation::EditMessage)
+            .map(|request| request.json().unwrap()["content"].to_string())
+            .collect()
+    }
+
+    async fn live(dry_run: &DryRun) -> LiveMessage {
+        let webhook = WebhookApi::new(WebhookId::new(1), "token").transport(dry_run.clone());
+        let message = MessageBuilder::new().content("0").unwrap();
+        let live = webhook.message.create_live(&message, None).await.unwrap();
+        live.interval(SHORT)
+    }
+
+    fn content(value: &str) -> impl FnOnce(&mut MessageBuilder) + '_ {
+        move |message| message.content = Some(value.to_string())
+    }
+
+    #[tokio::test]
+    async fn coalesces_updates_and_pushes_the_latest_state() {
+        let dry_run = DryRun::new();
+        let mut live = live(&dry_run).await;
+
+        for value in ["1", "2", "3"] {
+            assert!(!live.update(content(value)).await.unwrap());
+        }
+        assert!(live.pending());
+        assert!(edits(&dry_run).is_empty());
+
+        // The latest state is pushed once the interval passes, without another update.
+        tokio::time::sleep(SHORT * 3).await;
+        assert_eq!(edits(&dry_run), ["\"3\""]);
+        assert!(!live.pending());
+        assert_eq!(live.message().content, "3");
+
+        // Updates after the interval are pushed immediately.
+        assert!(live.update(content("4")).await.unwrap());
+        assert_eq!(edits(&dry_run), ["\"3\"", "\"4\""]);
+    }
+
+    #[tokio::test]
+    async fn pushes_after_the_handle_is_dropped() {
+        let dry_run = DryRun::new();
+        let mut live = live(&dry_run).await;
+        live.update(content("1")).await.unwrap();
+        drop(live);
+
+        tokio::time::sleep(SHORT * 3).await;
+        assert_eq!(edits(&dry_run), ["\"1\""]);
+    }
+
+    #[tokio::test]
+    async fn flush_waits_for_the_interval() {
+        let dry_run = DryRun::new();
+        let mut live = live(&dry_run).await;
+        let start = Instant::now();
+
+        live.update(content("1")).await.unwrap();
+        let message = live.flush().await.unwrap();
+        assert!(start.elapsed() >= SHORT / 2);
+        assert_eq!(message.content, "1");
+        assert!(!live.pending());
+
+        // The scheduled push was replaced by the flush, and nothing is left to flush.
+        tokio::time::sleep(SHORT * 2).await;
+        assert_eq!(edits(&dry_run), ["\"1\""]);
+        live.flush().await.unwrap();
+        assert_eq!(edits(&dry_run).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn finish_pushes_the_final_state() {
+        let dry_run = DryRun::new();
+        let mut live = live(&dry_run).await;
+
+        live.update(content("1")).await.unwrap();
+        let message = live.finish(content("done")).await.unwrap();
+        assert_eq!(message.content, "done");
+
+        tokio::time::sleep(SHORT * 2).await;
+        assert_eq!(edits(&dry_run), ["\"done\""]);
+    }
+
+    #[tokio::test]
+    async fn delete_discards_pending_changes() {
+        let dry_run = DryRun::new();
+        let mut live = live(&dry_run).await;
+        let id = live.id();
+
+        live.update(content("1")).await.unwrap();
+        live.delete().await.unwrap();
+
+        tokio::time::sleep(SHORT * 2).await;
+        assert!(edits(&dry_run).is_empty());
+        let deleted = dry_run
+            .captured()
+            .iter()
+            .any(|request| request.url.ends_with(&format!("/messages/{}", id)));
+        assert!(deleted);
+    }
+
+    #[tokio::test]
+    async fn returns_errors_from_background_pushes() {
+        let dry_run = DryRun::new();
+        let failing = Arc::new(AtomicBool::new(true));
+        let webhook = WebhookApi::new(WebhookId::new(1), "token")
+            .transport(dry_run.clone())
+            .middleware(FailEdits(failing.clone()));
+        let message = MessageBuilder::new().content("0").unwrap();
+        let mut live = webhook
+            .message
+            .create_live(&message, None)
+            .await
+            .unwrap()
+            .interval(SHORT);
+
+        live.update(content("1")).await.unwrap();
+        tokio::time::sleep(SHORT * 3).await;
+        assert!(live.pending());
+
+        // The error is returned once, the change is kept and pushed by the flush.
+        assert!(matches!(
+            live.update(content("2")).await,
+            Err(WebhookError::Unknown(_))
+        ));
+        failing.store(false, Ordering::SeqCst);
+        let message = live.flush().await.unwrap();
+        assert_eq!(message.content, "2");
+        assert!(!live.pending());
+    }
+}
@@ -0,0 +1,336 @@
+//! Loading messages from JSON, TOML, and YAML documents.
+//!
+//! `load` parses declarative message files into a `MessageBuilder` and validates them, so layouts
+//! can be kept under version control. Documents use the same shape as the JSON body of Discord's
+//! execute webhook request, keys not used by this crate (such as `allowed_mentions`) are ignored
+//! so payloads from other tools can be pasted in. Colors may be integers or hex strings.
+//!
+//! JSON is always available, TOML requires the `toml` feature and YAML requires the `yaml`
+//! feature. Errors include the path to the offending value, such as `embeds[0].color`.
+//!
+//! ## References / Documentation
+//!
+//! <https://discord.com/developers/docs/resources/webhook#execute-webhook-jsonform-params>
+
+use crate::client::{Result, WebhookError};
+use crate::message::MessageBuilder;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::path::Path;
+
+/// Format of a message document. Variants depend on the features enabled, so matching on it
+/// requires a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// JSON, such as a webhook payload copied from another tool.
+    Json,
+    /// TOML, requires the `toml` feature.
+    #[cfg(feature = "toml")]
+    Toml,
+    /// YAML, requires the `yaml` feature.
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+impl Format {
+    /// Determines the format from the extension of a path, `.json`, `.toml`, `.yaml`, or `.yml`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the document.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("json") => Ok(Self::Json),
+            #[cfg(feature = "toml")]
+            Some("toml") => Ok(Self::Toml),
+            #[cfg(feature = "yaml")]
+            Some("yaml") | Some("yml") => Ok(Self::Yaml),
+            _ => Err(WebhookError::BadParse(format!(
+                "format of {}, supported extensions are {}",
+                path.display(),
+                Self::extensions()
+            ))),
+        }
+    }
+
+    /// Extensions of the formats enabled by features.
+    fn extensions() -> &'static str {
+        match (cfg!(feature = "toml"), cfg!(feature = "yaml")) {
+            (true, true) => ".json, .toml, .yaml, and .yml",
+            (true, false) => ".json and .toml",
+            (false, true) => ".json, .yaml, and .yml",
+            (false, false) => ".json",
+        }
+    }
+
+    /// Parses a document into any deserializable value, errors include the path to the value.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Contents of the document.
+    pub fn parse<T: DeserializeOwned>(self, text: &str) -> Result<T> {
+        match self {
+            Self::Json => {
+                let mut deserializer = serde_json::Deserializer::from_str(text);
+                let value = serde_path_to_error::deserialize(&mut deserializer)
+                    .map_err(|error| self.error(error))?;
+                deserializer.end().map_err(|error| self.error(error))?;
+                Ok(value)
+            }
+            #[cfg(feature = "toml")]
+            Self::Toml => {
+                let deserializer = toml::Deserializer::new(text);
+                serde_path_to_error::deserialize(deserializer).map_err(|error| self.error(error))
+            }
+            // Errors from YAML already include the path to the value.
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::from_str(text).map_err(|error| self.error(error)),
+        }
+    }
+
+    /// Converts an error from the format into a `WebhookError`.
+    fn error<E: fmt::Display>(self, error: E) -> WebhookError {
+        WebhookError::BadParse(format!("{} document, {}", self, error))
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Json => f.write_str("JSON"),
+            #[cfg(feature = "toml")]
+            Self::Toml => f.write_str("TOML"),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => f.write_str("YAML"),
+        }
+    }
+}
+
+impl MessageBuilder {
+    /// Parses and validates a message from a document.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Contents of the document.
+    /// * `format` - Format of the document.
+    pub fn parse(text: &str, format: Format) -> Result<Self> {
+        let message: Self = format.parse(text)?;
+        message.validate()?;
+        Ok(message)
+    }
+
+    /// Parses and validates a message from a JSON document, such as a webhook payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Contents of the document.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Self::parse(json, Format::Json)
+    }
+
+    /// Parses and validates a message from a TOML document.
+    ///
+    /// # Arguments
+    ///
+    /// * `toml` - Contents of the document.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        Self::parse(toml, Format::Toml)
+    }
+
+    /// Parses and validates a message from a YAML document.
+    ///
+    /// # Arguments
+    ///
+    /// * `yaml` - Contents of the document.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        Self::parse(yaml, Format::Yaml)
+    }
+
+    /// Reads, parses, and validates a message from a file, the format is determined by its
+    /// extension (see `Format::from_path`).
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the document.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let format = Format::from_path(path)?;
+        let text = std::fs::read_to_string(path).map_err(|error| {
+            WebhookError::Unknown(format!("reading {}, {}", path.display(), error))
+        })?;
+
+        Self::parse(&text, format).map_err(|error| match error {
+            WebhookError::BadParse(value) => {
+                WebhookError::BadParse(format!("{}, {}", path.display(), value))
+            }
+            error => error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+
+    /// Writes a document to a temporary file, returning its path.
+    fn write(name: &str, text: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("yadwh-load-{}-{}", std::process::id(), name));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn bad_parse(result: Result<MessageBuilder>) -> String {
+        match result {
+            Err(WebhookError::BadParse(value)) => value,
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_json_payloads() {
+        let message = MessageBuilder::from_json(
+            r##"{
+                "content": "Hello.",
+                "allowed_mentions": { "parse": [] },
+                "embeds": [{ "title": "Title", "color": "#5865F2", "fields": [
+                    { "name": "Name", "value": "Value", "inline": true }
+                ]}]
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(message.content.as_deref(), Some("Hello."));
+        assert_eq!(message.embeds[0].title.as_deref(), Some("Title"));
+        assert_eq!(message.embeds[0].color, Some(Color::BLURPLE));
+        assert_eq!(message.embeds[0].fields[0].inline, Some(true));
+    }
+
+    #[test]
+    fn reports_the_path_of_invalid_values() {
+        let error = bad_parse(MessageBuilder::from_json(
+            r#"{ "embeds": [{ "color": "blue" }] }"#,
+        ));
+        assert!(
+            error.starts_with("JSON document, embeds[0].color"),
+            "{}",
+            error
+        );
+
+        let error = bad_parse(MessageBuilder::from_json(r#"{ "content": "x" } trailing"#));
+        assert!(error.starts_with("JSON document"), "{}", error);
+    }
+
+    #[test]
+    fn validates_parsed_messages() {
+        let text = format!(r#"{{ "content": "{}" }}"#, "x".repeat(2001));
+        assert!(matches!(
+            MessageBuilder::from_json(&text),
+            Err(WebhookError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn determines_the_format_from_the_extension() {
+        assert_eq!(Format::from_path("message.json").unwrap(), Format::Json);
+        assert_eq!(Format::from_path("MESSAGE.JSON").unwrap(), Format::Json);
+
+        #[cfg(feature = "toml")]
+        assert_eq!(Format::from_path("message.toml").unwrap(), Format::Toml);
+        #[cfg(feature = "yaml")]
+        assert_eq!(Format::from_path("message.yml").unwrap(), Format::Yaml);
+
+        for path in ["message.txt", "message", "message.json.bak"] {
+            let error = match Format::from_path(path) {
+                Err(WebhookError::BadParse(value)) => value,
+                other => panic!("expected a parse error for '{}', got {:?}", path, other),
+            };
+            assert!(error.contains(Format::extensions()), "{}", error);
+        }
+    }
+
+    #[test]
+    fn loads_files() {
+        let path = write("message.json", r#"{ "content": "Loaded." }"#);
+        let message = MessageBuilder::load(&path).unwrap();
+        assert_eq!(message.content.as_deref(), Some("Loaded."));
+
+        // Parse errors include the path of the file.
+        std::fs::write(&path, r#"{ "tts": "yes" }"#).unwrap();
+        let error = bad_parse(MessageBuilder::load(&path));
+        assert!(error.starts_with(&path.display().to_string()), "{}", error);
+        assert!(error.contains("tts"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            MessageBuilder::load(&path),
+            Err(WebhookError::Unknown(_))
+        ));
+        assert!(matches!(
+            MessageBuilder::load("message.txt"),
+            Err(WebhookError::BadParse(_))
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn parses_toml() {
+        let message = MessageBuilder::from_toml(
+            r##"
+            content = "Hello."
+
+            [[embeds]]
+            title = "Title"
+            color = "#57F287"
+
+            [[embeds.fields]]
+            name = "Name"
+            value = "Value"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(message.content.as_deref(), Some("Hello."));
+        assert_eq!(message.embeds[0].color, Some(Color::GREEN));
+        assert_eq!(message.embeds[0].fields[0].name, "Name");
+
+        let error = bad_parse(MessageBuilder::from_toml("[[embeds]]\ncolor = \"blue\""));
+        assert!(
+            error.starts_with("TOML document, embeds[0].color"),
+            "{}",
+            error
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn parses_yaml() {
+        let message = MessageBuilder::from_yaml(
+            r##"
+            content: Hello.
+            embeds:
+              - title: Title
+                color: 15548997
+            "##,
+        )
+        .unwrap();
+        assert_eq!(message.content.as_deref(), Some("Hello."));
+        assert_eq!(message.embeds[0].title.as_deref(), Some("Title"));
+        assert_eq!(message.embeds[0].color, Some(Color::RED));
+
+        let error = bad_parse(MessageBuilder::from_yaml("embeds:\n  - color: blue\n"));
+        assert!(
+            error.starts_with("YAML document, embeds[0].color"),
+            "{}",
+            error
+        );
+    }
+}
@@ -0,0 +1,376 @@
+//! Discord markdown formatting and escaping.
+//!
+//! `markdown` contains `escape` for untrusted text, functions that wrap text in Discord's
+//! formatting (bold, italic, underline, strikethrough, spoilers, headers, subtext, lists, quotes,
+//! masked links, and code), and `Markdown`, a builder that tracks the length of the text against
+//! a limit such as `Limit::CONTENT`.
+//!
+//! The formatting functions do not escape their input so they can be nested, such as
+//! `bold(&italic("text"))`. Text from users should be passed through `escape` first, `Markdown`
+//! escapes text added with `Markdown::text` for you.
+//!
+//! ## References / Documentation
+//!
+//! <https://support.discord.com/hc/en-us/articles/210298617-Markdown-Text-101-Chat-Formatting-Bold-Italic-Underline>
+
+use crate::client::{Limit, Result, WebhookError};
+
+/// Zero width space, placed between characters that would otherwise form markdown.
+const ZERO_WIDTH: char = '\u{200b}';
+
+/// Escapes characters that Discord interprets as markdown, so the text is displayed as written.
+/// Line-leading markers for quotes, headers, subtext, and lists are escaped as well.
+///
+/// # Arguments
+///
+/// * `text` - Untrusted text to escape.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            escaped.push('\n');
+        }
+
+        // Markers only have meaning at the start of a line, after any indentation.
+        let trimmed = line.trim_start();
+        escaped.push_str(&line[..line.len() - trimmed.len()]);
+        let mut chars = trimmed.char_indices().peekable();
+        if let Some(&(_, first)) = chars.peek() {
+            if matches!(first, '>' | '#' | '-') {
+                escaped.push('\\');
+            } else if first.is_ascii_digit() {
+                // Ordered lists such as "1. item".
+                let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+                if trimmed[digits..].starts_with(". ") {
+                    escaped.push_str(&trimmed[..digits]);
+                    escaped.push('\\');
+                    chars.nth(digits - 1);
+                }
+            }
+        }
+
+        for (_, c) in chars {
+            if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '[' | ']') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Wraps text in bold, `**text**`.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to format.
+pub fn bold(text: &str) -> String {
+    format!("**{}**", text)
+}
+
+/// Wraps text in italics, `*text*`.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to format.
+pub fn italic(text: &str) -> String {
+    format!("*{}*", text)
+}
+
+/// Wraps text in an underline, `__text__`.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to format.
+pub fn underline(text: &str) -> String {
+    format!("__{}__", text)
+}
+
+/// Wraps text in a strikethrough, `~~text~~`.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to format.
+pub fn strike(text: &str) -> String {
+    format!("~~{}~~", text)
+}
+
+/// Hides text behind a spoiler, `||text||`.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to format.
+pub fn spoiler(text: &str) -> String {
+    format!("||{}||", text)
+}
+
+/// Creates a header, the level is clamped between 1 (largest) and 3 (smallest).
+///
+/// # Arguments
+///
+/// * `level` - Size of the header, 1 through 3.
+/// * `text` - Markdown displayed as the header, on a single line.
+pub fn header(level: u8, text: &str) -> String {
+    let level = level.clamp(1, 3) as usize;
+    format!("{} {}", "#".repeat(level), single_line(text))
+}
+
+/// Creates subtext, smaller grey text displayed on its own line, `-# text`.
+///
+/// # Arguments
+///
+/// * `text` - Markdown displayed as subtext, on a single line.
+pub fn subtext(text: &str) -> String {
+    format!("-# {}", single_line(text))
+}
+
+/// Creates a bulleted list, one item per line. Lines within an item are indented beneath it.
+///
+/// # Arguments
+///
+/// * `items` - Markdown for each item.
+pub fn list<S: AsRef<str>>(items: &[S]) -> String {
+    items
+        .iter()
+        .map(|item| format!("- {}", item.as_ref().replace('\n', "\n  ")))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Creates a numbered list starting at 1, one item per line.
+///
+/// # Arguments
+///
+/// * `items` - Markdown for each item.
+pub fn ordered_list<S: AsRef<str>>(items: &[S]) -> String {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = format!("{}. ", i + 1);
+            let indent = format!("\n{}", " ".repeat(marker.len()));
+            format!("{}{}", marker, item.as_ref().replace('\n', &indent))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Quotes text, prefixing every line with `> `.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to quote.
+pub fn quote(text: &str) -> String {
+    text.split('\n')
+        .map(|line| format!("> {}", line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Quotes text, including everything that follows it within the message, `>>> text`.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to quote.
+pub fn block_quote(text: &str) -> String {
+    format!(">>> {}", text)
+}
+
+/// Creates a masked link, `[label](<url>)`. The angle brackets prevent Discord from embedding a
+/// preview of the URL.
+///
+/// # Arguments
+///
+/// * `label` - Markdown displayed in place of the URL.
+/// * `url` - URL opened by the link.
+pub fn link(label: &str, url: &str) -> String {
+    let url = url
+        .replace(' ', "%20")
+        .replace('<', "%3C")
+        .replace('>', "%3E");
+    format!("[{}](<{}>)", single_line(label), url)
+}
+
+/// Displays text as inline code, the text is not escaped as markdown has no effect within it.
+/// Backticks within the text are handled by using a longer delimiter and breaking up runs.
+///
+/// # Arguments
+///
+/// * `text` - Text to display as code.
+pub fn code(text: &str) -> String {
+    let text = single_line(text);
+    match text.contains('`') {
+        // Padding prevents backticks at either end from joining the delimiter.
+        true => format!("`` {} ``", break_backticks(&text)),
+        false => format!("`{}`", text),
+    }
+}
+
+/// Displays text as a fenced code block with optional syntax highlighting.
+///
+/// # Arguments
+///
+/// * `language` - Language used for highlighting, such as `rust`.
+/// * `code` - Code to display, backticks within it are broken up so they do not end the block.
+pub fn code_block(language: Option<&str>, code: &str) -> String {
+    let language: String = language
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '#' | '_'))
+        .collect();
+    let code = break_backticks(code);
+    format!("```{}\n{}\n```", language, code.trim_end_matches('\n'))
+}
+
+/// Places a zero width space between consecutive backticks, so they cannot end code.
+fn break_backticks(text: &str) -> String {
+    let mut broken = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        if c == '`' && previous == Some('`') {
+            broken.push(ZERO_WIDTH);
+        }
+        broken.push(c);
+        previous = Some(c);
+    }
+    broken
+}
+
+/// Joins lines into one, as headers, subtext, and labels cannot span lines.
+fn single_line(text: &str) -> String {
+    text.lines().collect::<Vec<&str>>().join(" ")
+}
+
+/// Builder for markdown that tracks its length against a limit, `Limit::CONTENT` by default.
+///
+/// Text added with `Markdown::text` is escaped, markdown from the formatting functions is added
+/// with `Markdown::push` or `Markdown::line`. Use `Markdown::fits` to check whether more can be
+/// added, and `Markdown::build` to obtain the text once it is verified to be within the limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Markdown {
+    /// Markdown created so far.
+    text: String,
+    /// Maximum length of the markdown.
+    limit: usize,
+}
+
+impl Markdown {
+    /// Creates empty markdown limited to `Limit::CONTENT`.
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            limit: Limit::CONTENT,
+        }
+    }
+
+    /// Sets the maximum length, such as `Limit::DESCRIPTION` for the description of an embed.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum length in characters.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Adds untrusted text, escaping any markdown within it.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Text to add as written.
+    pub fn text(mut self, text: &str) -> Self {
+        self.text.push_str(&escape(text));
+        self
+    }
+
+    /// Adds markdown as is, such as the output of `bold` or `code_block`.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - Markdown to add.
+    pub fn push(mut self, markdown: &str) -> Self {
+        self.text.push_str(markdown);
+        self
+    }
+
+    /// Adds markdown on a new line, block elements such as headers and lists must start a line.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - Markdown to add.
+    pub fn line(mut self, markdown: &str) -> Self {
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+        self.text.push_str(markdown);
+        self
+    }
+
+    /// Length of the markdown, counted the way Discord does (see `Limit::length`).
+    pub fn length(&self) -> usize {
+        Limit::length(&self.text)
+    }
+
+    /// Characters remaining before the limit is reached.
+    pub fn remaining(&self) -> usize {
+        self.limit.saturating_sub(self.length())
+    }
+
+    /// Whether adding the markdown on a new line would stay within the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `markdown` - Markdown that would be added with `Markdown::line`.
+    pub fn fits(&self, markdown: &str) -> bool {
+        let newline = usize::from(!self.text.is_empty() && !self.text.ends_with('\n'));
+        Limit::length(markdown) + newline <= self.remaining()
+    }
+
+    /// Obtains the markdown, erroring with `WebhookError::TooBig` if it exceeds the limit.
+    pub fn build(self) -> Result<String> {
+        let size = self.length();
+        match size > self.limit {
+            true => Err(WebhookError::TooBig(
+                "markdown".to_string(),
+                size,
+                self.limit,
+            )),
+            false => Ok(self.text),
+        }
+    }
+}
+
+impl Default for Markdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_inline_markdown() {
+        assert_eq!(escape("plain text"), "plain text");
+        assert_eq!(escape("*bold* _it_"), "\\*bold\\* \\_it\\_");
+        assert_eq!(
+            escape("~~s~~ ||x|| `c`"),
+            "\\~\\~s\\~\\~ \\|\\|x\\|\\| \\`c\\`"
+        );
+        assert_eq!(escape("[a](b) \\"), "\\[a\\](b) \\\\");
+    }
+
+    #[test]
+    fn escapes_line_leading_markers() {
+        assert_eq!(escape("# title\n> quote"), "\\# title\n\\> quote");
+        assert_eq!(escape("  - item"), "  \\- item");
+        assert_eq!(escape("12. item\n1.5 items"), "12\\. item\n1.5 items");
+        assert_eq!(escape("a # b - c > d"), "a # b - c > d");
+    }
+
+    #[test]
+    fn escaped_text_survives_formatting() {
+        assert_eq!(bold(&escape("**")), "**\\*\\***");
+    }
+}
@@ -0,0 +1,415 @@
+//! Mentions, timestamps, and custom emoji within message content.
+//!
+//! `mention` contains functions that produce Discord's special syntaxes: user, role, and channel
+//! mentions, slash command mentions, custom emoji, and timestamps that are displayed in the local
+//! time of each reader. `parse` extracts them from content, such as with `MessageBuilder::mentions`,
+//! so who a message will ping can be audited before it is sent.
+//!
+//! ## References / Documentation
+//!
+//! <https://discord.com/developers/docs/reference#message-formatting>
+
+use crate::message::{Message, MessageBuilder};
+use crate::snowflake::{ChannelId, CommandId, EmojiId, RoleId, UserId};
+use crate::timestamp::Timestamp;
+use std::fmt;
+
+/// Mentions a user, `<@id>`.
+///
+/// # Arguments
+///
+/// * `id` - User to mention.
+pub fn user(id: UserId) -> String {
+    Mention::User(id).to_string()
+}
+
+/// Mentions a role, `<@&id>`.
+///
+/// # Arguments
+///
+/// * `id` - Role to mention.
+pub fn role(id: RoleId) -> String {
+    Mention::Role(id).to_string()
+}
+
+/// Mentions a channel or thread, `<#id>`.
+///
+/// # Arguments
+///
+/// * `id` - Channel to mention.
+pub fn channel(id: ChannelId) -> String {
+    Mention::Channel(id).to_string()
+}
+
+/// Mentions a slash command, `</name:id>`, which can be clicked to use it.
+///
+/// # Arguments
+///
+/// * `name` - Name of the command, including any subcommand such as `config set`.
+/// * `id` - ID of the command.
+pub fn command(name: &str, id: CommandId) -> String {
+    Mention::Command {
+        name: name.to_string(),
+        id,
+    }
+    .to_string()
+}
+
+/// Displays a custom emoji, `<:name:id>` or `<a:name:id>` if it is animated.
+///
+/// # Arguments
+///
+/// * `name` - Name of the emoji.
+/// * `id` - ID of the emoji.
+/// * `animated` - Whether the emoji is animated.
+pub fn emoji(name: &str, id: EmojiId, animated: bool) -> String {
+    Mention::Emoji {
+        name: name.to_string(),
+        id,
+        animated,
+    }
+    .to_string()
+}
+
+/// Displays a time in the local time zone of each reader, `<t:unix:style>`.
+///
+/// # Arguments
+///
+/// * `time` - Time to display, such as a `SystemTime` or `Timestamp`.
+/// * `style` - How the time is displayed, such as `TimestampStyle::Relative`.
+pub fn timestamp<T: Into<Timestamp>>(time: T, style: TimestampStyle) -> String {
+    Mention::Timestamp {
+        time: time.into(),
+        style: Some(style),
+    }
+    .to_string()
+}
+
+/// Mentions everyone that can see the channel, `@everyone`.
+pub fn everyone() -> String {
+    Mention::Everyone.to_string()
+}
+
+/// Mentions everyone online that can see the channel, `@here`.
+pub fn here() -> String {
+    Mention::Here.to_string()
+}
+
+/// How a timestamp is displayed, the examples are for June 1st 2023 at 12:34:56.
+///
+/// ## References / Documentation
+///
+/// <https://discord.com/developers/docs/reference#message-formatting-timestamp-styles>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimestampStyle {
+    /// `t`, such as "12:34 PM".
+    ShortTime,
+    /// `T`, such as "12:34:56 PM".
+    LongTime,
+    /// `d`, such as "06/01/2023".
+    ShortDate,
+    /// `D`, such as "June 1, 2023".
+    LongDate,
+    /// `f`, such as "June 1, 2023 12:34 PM". Used by Discord when no style is provided.
+    #[default]
+    ShortDateTime,
+    /// `F`, such as "Thursday, June 1, 2023 12:34 PM".
+    LongDateTime,
+    /// `R`, such as "2 hours ago" or "in 5 minutes".
+    Relative,
+}
+
+impl TimestampStyle {
+    /// Every style, in the order they are listed by Discord.
+    pub const ALL: [TimestampStyle; 7] = [
+        Self::ShortTime,
+        Self::LongTime,
+        Self::ShortDate,
+        Self::LongDate,
+        Self::ShortDateTime,
+        Self::LongDateTime,
+        Self::Relative,
+    ];
+
+    /// Character identifying the style within the timestamp syntax.
+    pub fn as_char(self) -> char {
+        match self {
+            Self::ShortTime => 't',
+            Self::LongTime => 'T',
+            Self::ShortDate => 'd',
+            Self::LongDate => 'D',
+            Self::ShortDateTime => 'f',
+            Self::LongDateTime => 'F',
+            Self::Relative => 'R',
+        }
+    }
+
+    /// Obtains the style identified by a character.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Character of the style, such as `R`.
+    pub fn from_char(value: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|style| style.as_char() == value)
+    }
+}
+
+/// Mention, timestamp, or custom emoji found within content.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Mention {
+    /// User mention, `<@id>`.
+    User(UserId),
+    /// Role mention, `<@&id>`.
+    Role(RoleId),
+    /// Channel mention, `<#id>`.
+    Channel(ChannelId),
+    /// `@everyone`.
+    Everyone,
+    /// `@here`.
+    Here,
+    /// Slash command mention, `</name:id>`.
+    Command {
+        /// Name of the command, including any subcommand.
+        name: String,
+        /// ID of the command.
+        id: CommandId,
+    },
+    /// Custom emoji, `<:name:id>` or `<a:name:id>`.
+    Emoji {
+        /// Name of the emoji.
+        name: String,
+        /// ID of the emoji.
+        id: EmojiId,
+        /// Whether the emoji is animated.
+        animated: bool,
+    },
+    /// Timestamp, `<t:unix>` or `<t:unix:style>`.
+    Timestamp {
+        /// Time displayed, to the second.
+        time: Timestamp,
+        /// How the time is displayed, `None` uses `TimestampStyle::ShortDateTime`.
+        style: Option<TimestampStyle>,
+    },
+}
+
+impl Mention {
+    /// Whether the mention notifies someone: users, roles, `@everyone`, and `@here`. Whether they
+    /// are actually notified also depends on the allowed mentions of the message.
+    pub fn pings(&self) -> bool {
+        matches!(
+            self,
+            Self::User(_) | Self::Role(_) | Self::Everyone | Self::Here
+        )
+    }
+}
+
+impl fmt::Display for Mention {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::User(id) => write!(f, "<@{}>", id),
+            Self::Role(id) => write!(f, "<@&{}>", id),
+            Self::Channel(id) => write!(f, "<#{}>", id),
+            Self::Everyone => f.write_str("@everyone"),
+            Self::Here => f.write_str("@here"),
+            Self::Command { name, id } => write!(f, "</{}:{}>", name, id),
+            Self::Emoji { name, id, animated } => match animated {
+                true => write!(f, "<a:{}:{}>", name, id),
+                false => write!(f, "<:{}:{}>", name, id),
+            },
+            Self::Timestamp { time, style } => {
+                let seconds = time.unix_millis().div_euclid(1000);
+                match style {
+                    Some(style) => write!(f, "<t:{}:{}>", seconds, style.as_char()),
+                    None => write!(f, "<t:{}>", seconds),
+                }
+            }
+        }
+    }
+}
+
+/// Extracts every mention, timestamp, and custom emoji from content in the order they appear.
+/// Those within code or escaped with a backslash are ignored, as Discord does not display them.
+///
+/// # Arguments
+///
+/// * `content` - Content of a message.
+pub fn parse(content: &str) -> Vec<Mention> {
+    let mut mentions: Vec<Mention> = vec![];
+    let mut rest = content;
+
+    while let Some(c) = rest.chars().next() {
+        match c {
+            // Escaped characters are displayed as is.
+            '\\' => {
+                let next = rest[1..].chars().next().map_or(0, char::len_utf8);
+                rest = &rest[1 + next..];
+                continue;
+            }
+            // Code is closed by the same amount of backticks that opened it.
+            '`' => {
+                let fence = &rest[..rest.len() - rest.trim_start_matches('`').len()];
+                if let Some(end) = rest[fence.len()..].find(fence) {
+                    rest = &rest[fence.len() * 2 + end..];
+                    continue;
+                }
+            }
+            '<' => {
+                let tag = rest
+                    .find('>')
+                    .and_then(|end| Some((tag(&rest[1..end])?, end)));
+                if let Some((mention, end)) = tag {
+                    mentions.push(mention);
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+            '@' => {
+                let found = [("@everyone", Mention::Everyone), ("@here", Mention::Here)]
+                    .into_iter()
+                    .find(|(text, _)| rest.starts_with(text));
+                if let Some((text, mention)) = found {
+                    mentions.push(mention);
+                    rest = &rest[text.len()..];
+                    continue;
+                }
+            }
+            _ => (),
+        }
+
+        rest = &rest[c.len_utf8()..];
+    }
+
+    mentions
+}
+
+/// Parses the inside of `<...>`.
+fn tag(inner: &str) -> Option<Mention> {
+    if let Some(id) = inner.strip_prefix("@&") {
+        return id.parse().ok().map(Mention::Role);
+    }
+    if let Some(id) = inner.strip_prefix('@') {
+        // Older clients prefix nickname mentions with an exclamation mark.
+        let id = id.strip_prefix('!').unwrap_or(id);
+        return id.parse().ok().map(Mention::User);
+    }
+    if let Some(id) = inner.strip_prefix('#') {
+        return id.parse().ok().map(Mention::Channel);
+    }
+    if let Some(command) = inner.strip_prefix('/') {
+        let (name, id) = command.rsplit_once(':')?;
+        return Some(Mention::Command {
+            name: name.to_string(),
+            id: id.parse().ok()?,
+        });
+    }
+    if let Some(time) = inner.strip_prefix("t:") {
+        let (seconds, style) = match time.split_once(':') {
+            Some((seconds, style)) => {
+                let mut chars = style.chars();
+                let style = match (chars.next(), chars.next()) {
+                    (Some(style), None) => TimestampStyle::from_char(style)?,
+                    _ => return None,
+                };
+                (seconds, Some(style))
+            }
+            None => (time, None),
+        };
+        let seconds: i64 = seconds.parse().ok()?;
+        return Some(Mention::Timestamp {
+            time: Timestamp::from_unix_millis(seconds.checked_mul(1000)?),
+            style,
+        });
+    }
+
+    let (animated, emoji) = match inner.strip_prefix("a:") {
+        Some(emoji) => (true, emoji),
+        None => (false, inner.strip_prefix(':')?),
+    };
+    let (name, id) = emoji.split_once(':')?;
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    match !name.is_empty() && name.chars().all(valid) {
+        true => Some(Mention::Emoji {
+            name: name.to_string(),
+            id: id.parse().ok()?,
+            animated,
+        }),
+        false => None,
+    }
+}
+
+impl MessageBuilder {
+    /// Extracts the mentions, timestamps, and custom emoji within the content of the message, see
+    /// `mention::parse`. Mentions within embeds never notify anyone and are not included.
+    pub fn mentions(&self) -> Vec<Mention> {
+        parse(self.content.as_deref().unwrap_or_default())
+    }
+}
+
+impl Message {
+    /// Extracts the mentions, timestamps, and custom emoji within the content of the message, see
+    /// `mention::parse`.
+    pub fn mentions(&self) -> Vec<Mention> {
+        parse(&self.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_kind_of_mention() {
+        let content = "<@1> <@!2> <@&3> <#4> @everyone @here </deploy app:5> <:ok:6> <a:party:7> \
+                       <t:1700000000> <t:1700000000:R>";
+        let time = Timestamp::from_unix_millis(1_700_000_000_000);
+        assert_eq!(
+            parse(content),
+            vec![
+                Mention::User(UserId::new(1)),
+                Mention::User(UserId::new(2)),
+                Mention::Role(RoleId::new(3)),
+                Mention::Channel(ChannelId::new(4)),
+                Mention::Everyone,
+                Mention::Here,
+                Mention::Command {
+                    name: "deploy app".to_string(),
+                    id: CommandId::new(5),
+                },
+                Mention::Emoji {
+                    name: "ok".to_string(),
+                    id: EmojiId::new(6),
+                    animated: false,
+                },
+                Mention::Emoji {
+                    name: "party".to_string(),
+                    id: EmojiId::new(7),
+                    animated: true,
+                },
+                Mention::Timestamp { time, style: None },
+                Mention::Timestamp {
+                    time,
+                    style: Some(TimestampStyle::Relative),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_code_and_escapes() {
+        let content = "`<@1>` ``@everyone`` ```\n@here\n``` \\<@2> \\@here <@3>";
+        assert_eq!(parse(content), vec![Mention::User(UserId::new(3))]);
+    }
+
+    #[test]
+    fn ignores_invalid_tags() {
+        let content = "<@0> <@x> <#> <t:1:Q> <:bad name:1> <> a < b > c";
+        assert!(parse(content).is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        let content = "<@&3> </deploy:5> <a:party:7> <t:1700000000:R>";
+        let rendered: Vec<String> = parse(content).iter().map(|m| m.to_string()).collect();
+        assert_eq!(rendered.join(" "), content);
+    }
+}
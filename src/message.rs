@@ -2,9 +2,13 @@
 //!
 //! This is used by proxy in `WebhookApi` to manage messages.
 
-use crate::client::{Client, Limit, Result, WebhookError};
+use crate::attachment::{Attachment, File};
+use crate::client::{Client, Limit, Operation, Result, Violation, WebhookError};
+use crate::component::Component;
 use crate::embed::Embed;
-use hyper::{Body, Method};
+use crate::snowflake::{ChannelId, MessageId, WebhookId};
+use crate::timestamp::Timestamp;
+use crate::truncate::{self, Truncated, Truncation};
 use serde::{Deserialize, Serialize};
 
 /// Message received from the Discord API after message creation, edit, and obtaining.
@@ -12,28 +16,34 @@ use serde::{Deserialize, Serialize};
 /// ## References / Documentation
 ///
 /// <https://discord.com/developers/docs/resources/channel#message-object>
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Message {
     /// ID of the message.
-    pub id: String,
+    pub id: MessageId,
     /// ID of the channel the message was sent in.
-    pub channel_id: String,
+    pub channel_id: ChannelId,
     /// Contents of the message.
     pub content: String,
     /// When this message was sent.
-    pub timestamp: String,
+    pub timestamp: Timestamp,
     /// When this message was last edited.
-    pub edited_timestamp: Option<String>,
+    pub edited_timestamp: Option<Timestamp>,
     /// Whether this was a TTS (Text-to-Speech) message.
     pub tts: bool,
     /// Whether this message mentions everyone.
     pub mention_everyone: bool,
     /// Any embedded content.
     pub embeds: Vec<Embed>,
+    /// Components such as buttons, within action rows.
+    #[serde(default)]
+    pub components: Vec<Component>,
+    /// Files attached to the message.
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
     /// Whether this message is pinned.
     pub pinned: bool,
     /// This is the webhook's ID.
-    pub webhook_id: String,
+    pub webhook_id: WebhookId,
     /// Type of message.
     pub r#type: u8,
 }
@@ -44,19 +54,38 @@ pub struct Message {
 /// ## References / Documentation
 ///
 /// <https://discord.com/developers/docs/resources/webhook#execute-webhook-jsonform-params>
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
 pub struct MessageBuilder {
     /// Overrides the default username of the webhook.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub username: Option<String>,
+    /// Overrides the default avatar of the webhook.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
     /// The message contents (up to 2000 characters)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
     /// True if this is a TTS (Text-to-Speech) message.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tts: Option<bool>,
-    /// Embedded `rich` content, an array of up to 10 embeds.
+    /// Embedded `rich` content, an array of up to 10 embeds. Omitted when empty, so edits keep
+    /// the existing embeds.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub embeds: Vec<Embed>,
+    /// Action rows containing components such as buttons, up to 5 rows.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Component>,
+    /// Files uploaded with the message, up to 10.
+    #[serde(skip)]
+    pub files: Vec<File>,
+    /// Truncation policy for the message, also used by embeds without their own policy.
+    #[serde(skip)]
+    pub truncation: Truncation,
+    /// Values cut by the truncation policy so far, reported to observers (see `RequestEvent`)
+    /// and as `tracing` warnings when the message is sent.
+    #[serde(skip)]
+    pub truncated: Vec<Truncated>,
 }
 
 impl MessageBuilder {
@@ -77,66 +106,171 @@ impl MessageBuilder {
     pub fn from(message: &Message) -> Result<Self> {
         let builder = Self::new()
             .content(&message.content)?
-            .embeds(&message.embeds);
+            .embeds(&message.embeds)
+            .components(&message.components);
         Ok(builder)
     }
 
-    /// Validates the entire embed does not exceed the maxmium lengths. Maximium length is availabe
-    /// in `Limit::EMBED_TOTAL`. Returns the total size for all embeds within the message.
+    /// Validates the message does not exceed any of the limits within `Limit`, counting
+    /// characters the way Discord does (see `Limit::length`). Returns the total size for all
+    /// embeds within the message. On failure, every exceeded limit is returned within
+    /// `WebhookError::Invalid` with a path to the offending value, such as
+    /// `embeds[2].fields[7].value`.
     pub fn validate(&self) -> Result<usize> {
-        let too_big = |name: &str, size: usize, max: usize| -> WebhookError {
-            WebhookError::TooBig(name.to_string(), size, max)
-        };
-
-        // Check if the username is too large.
-        match &self.username {
-            Some(value) => match value.len() {
-                0..=Limit::USERNAME => (),
-                _ => return Err(too_big("username", value.len(), Limit::USERNAME)),
-            },
-            None => (),
-        };
-
-        // Check if the content is too large.
-        match &self.content {
-            Some(value) => match value.len() {
-                0..=Limit::CONTENT => (),
-                _ => return Err(too_big("content", value.len(), Limit::CONTENT)),
-            },
-            None => (),
-        };
+        let mut violations: Vec<Violation> = vec![];
+        let size = |text: &Option<String>| text.as_deref().map_or(0, Limit::length);
+
+        // Check if the username and content are too large.
+        Limit::check(
+            "username",
+            size(&self.username),
+            Limit::USERNAME,
+            &mut violations,
+        );
+        Limit::check(
+            "content",
+            size(&self.content),
+            Limit::CONTENT,
+            &mut violations,
+        );
+
+        // Check the amount of embeds attached.
+        Limit::check("embeds", self.embeds.len(), Limit::EMBEDS, &mut violations);
 
         // Check the total size of all embeds attached.
         let mut total: usize = 0;
-        for embed in self.embeds.iter() {
-            total += match embed.validate() {
-                Ok(value) => value,
-                Err(error) => return Err(error),
-            }
+        for (i, embed) in self.embeds.iter().enumerate() {
+            total += embed.violations(&format!("embeds[{}]", i), &mut violations);
         }
 
         // Verify the total is less than embed max.
-        match total {
-            0..=Limit::EMBED_TOTAL => Ok(total),
-            _ => Err(too_big("embed", total, Limit::EMBED_TOTAL)),
+        Limit::check("embeds.total", total, Limit::EMBED_TOTAL, &mut violations);
+
+        // Check the amount of files uploaded.
+        Limit::check("files", self.files.len(), Limit::FILES, &mut violations);
+
+        // Check the amount of action rows, their components, and button labels.
+        Limit::check(
+            "components",
+            self.components.len(),
+            Limit::COMPONENTS,
+            &mut violations,
+        );
+        for (i, row) in self.components.iter().enumerate() {
+            Limit::check(
+                &format!("components[{}].components", i),
+                row.components.len(),
+                Limit::ROW_COMPONENTS,
+                &mut violations,
+            );
+            for (j, component) in row.components.iter().enumerate() {
+                let label = component.label.as_deref().map_or(0, Limit::length);
+                Limit::check(
+                    &format!("components[{}].components[{}].label", i, j),
+                    label,
+                    Limit::BUTTON_LABEL,
+                    &mut violations,
+                );
+            }
+        }
+
+        match violations.is_empty() {
+            true => Ok(total),
+            false => Err(WebhookError::Invalid(violations)),
+        }
+    }
+
+    /// Clips every value that exceeds its limit according to the truncation policy of the
+    /// message and its embeds (see `MessageBuilder::truncation` and `Embed::truncation`). Trailing
+    /// fields are dropped to fit within `Limit::EMBED_TOTAL`. Returns every value that was cut,
+    /// nothing is cut if the policy is `Truncation::Error`. They are also added to
+    /// `MessageBuilder::truncated` so they are reported when the message is sent.
+    pub fn truncate(&mut self) -> Vec<Truncated> {
+        let mut truncated: Vec<Truncated> = vec![];
+        let policy = self.truncation;
+
+        if let Some(username) = &mut self.username {
+            truncate::clip(
+                "username",
+                username,
+                Limit::USERNAME,
+                policy,
+                &mut truncated,
+            );
+        }
+
+        if let Some(content) = &mut self.content {
+            truncate::clip("content", content, Limit::CONTENT, policy, &mut truncated);
+        }
+
+        // Truncate each embed individually.
+        for (i, embed) in self.embeds.iter_mut().enumerate() {
+            let policy = embed.truncation.unwrap_or(policy);
+            let prefix = format!("embeds[{}]", i);
+            embed.truncate_with(&prefix, policy, &mut truncated);
+            embed.fit(&prefix, Limit::EMBED_TOTAL, policy, &mut truncated);
+        }
+
+        // Remove the excess across all embeds, starting with the last embed.
+        let mut excess = self
+            .embeds
+            .iter()
+            .map(Embed::length)
+            .sum::<usize>()
+            .saturating_sub(Limit::EMBED_TOTAL);
+        for (i, embed) in self.embeds.iter_mut().enumerate().rev() {
+            if excess == 0 {
+                break;
+            }
+
+            let policy = embed.truncation.unwrap_or(policy);
+            let length = embed.length();
+            let max = length.saturating_sub(excess);
+            embed.fit(&format!("embeds[{}]", i), max, policy, &mut truncated);
+            excess -= length - embed.length();
         }
+
+        self.truncated.extend(truncated.iter().cloned());
+        truncated
+    }
+
+    /// Sets the truncation policy for the message. When enabled, `username`, `content`, and
+    /// embeds without their own policy are clipped instead of failing validation. Messages are
+    /// truncated automatically when created or edited with `MessageApi`.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Truncation policy for the message.
+    pub fn truncation(mut self, policy: Truncation) -> Self {
+        self.truncation = policy;
+        self
     }
 
     /// Overrides the username for the message. This will throw a `WebhookError::TooBig` if the
-    /// username exceeds the maximum length (currently 80 characters, see: `Limit::USERNAME`).
+    /// username exceeds the maximum length (currently 80 characters, see: `Limit::USERNAME`)
+    /// unless a truncation policy is set, in which case it is clipped.
     ///
     /// # Arguments
     ///
     /// * `username` - Username to be display for the message, maximum length is `Limit::USERNAME`
     pub fn username(mut self, username: &str) -> Result<Self> {
         // Assign, but will not send if it is an error.
-        self.username = Some(username.to_string());
+        let mut username = username.to_string();
+        truncate::clip(
+            "username",
+            &mut username,
+            Limit::USERNAME,
+            self.truncation,
+            &mut self.truncated,
+        );
+        let size = Limit::length(&username);
+        self.username = Some(username);
 
         // Throw an error if it is too long.
-        if username.len() > Limit::USERNAME {
+        if size > Limit::USERNAME {
             return Err(WebhookError::TooBig(
                 "username".to_string(),
-                username.len(),
+                size,
                 Limit::USERNAME,
             ));
         };
@@ -144,21 +278,41 @@ impl MessageBuilder {
         Ok(self)
     }
 
+    /// Overrides the default avatar of the webhook for this message.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the avatar image.
+    pub fn avatar_url(mut self, url: &str) -> Self {
+        self.avatar_url = Some(url.to_string());
+        self
+    }
+
     /// Adds content to the message. This will throw a `WebhookError::TooBig` if the content
-    /// exceeds the maximum length (currently 2000 characters).
+    /// exceeds the maximum length (currently 2000 characters) unless a truncation policy is set,
+    /// in which case it is clipped.
     ///
     /// # Arguments
     ///
     /// * `content` - String of content to be sent, maximum length is `Limit::CONTENT`
     pub fn content(mut self, content: &str) -> Result<Self> {
         // Assign, but will not send if it is an error.
-        self.content = Some(content.to_string());
+        let mut content = content.to_string();
+        truncate::clip(
+            "content",
+            &mut content,
+            Limit::CONTENT,
+            self.truncation,
+            &mut self.truncated,
+        );
+        let size = Limit::length(&content);
+        self.content = Some(content);
 
         // Throw an error if it is too long.
-        if content.len() > Limit::CONTENT {
+        if size > Limit::CONTENT {
             return Err(WebhookError::TooBig(
                 "content".to_string(),
-                content.len(),
+                size,
                 Limit::CONTENT,
             ));
         };
@@ -176,31 +330,73 @@ impl MessageBuilder {
         self
     }
 
-    /// Creates a new embed to be added to the list of embeds to be sent. If you attempt to add
-    /// more then 10 embeds, it will fail and only keep the first 10.
+    /// Creates a new embed to be added to the list of embeds to be sent. Adding more than
+    /// `Limit::EMBEDS` embeds is reported by `validate`.
     pub fn embed<Func>(mut self, func: Func) -> Self
     where
         Func: Fn(&mut Embed) -> &mut Embed,
     {
-        if self.embeds.len() < Limit::EMBEDS {
-            let mut embed = Embed::new();
-            func(&mut embed);
-            self.embeds.push(embed);
-        }
+        let mut embed = Embed::new();
+        func(&mut embed);
+        self.embeds.push(embed);
 
         self
     }
 
-    /// Adds additional embeds to the message. If you attempt to add
-    /// more then 10 embeds, it will fail and only keep the first 10.
+    /// Adds additional embeds to the message. Adding more than `Limit::EMBEDS` embeds is reported
+    /// by `validate`.
     pub fn embeds(mut self, embeds: &[Embed]) -> Self {
-        // Calculate the maximum number of new embeds that can be added.
-        let remaining_capacity = Limit::EMBEDS.saturating_sub(self.embeds.len());
+        self.embeds.extend(embeds.iter().cloned());
+        self
+    }
+
+    /// Adds a file to be uploaded with the message, such as one read with `File::open`. Adding
+    /// more than `Limit::FILES` files is reported by `validate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - File to upload.
+    pub fn file(mut self, file: File) -> Self {
+        self.files.push(file);
+        self
+    }
 
-        // Extend the embeds vector with the allowed number of new embeds.
-        self.embeds
-            .extend(embeds.iter().take(remaining_capacity).cloned());
+    /// Creates the JSON body of the message, describing any files to upload as attachments.
+    pub(crate) fn body(&self) -> String {
+        let mut body = serde_json::to_value(self).unwrap();
+        if !self.files.is_empty() {
+            let attachments: Vec<serde_json::Value> = self
+                .files
+                .iter()
+                .enumerate()
+                .map(|(id, file)| {
+                    serde_json::json!({
+                        "id": id,
+                        "filename": file.filename,
+                        "description": file.description,
+                    })
+                })
+                .collect();
+            body["attachments"] = serde_json::Value::from(attachments);
+        }
+        body.to_string()
+    }
+
+    /// Adds an action row containing the components provided, such as link buttons created with
+    /// `Component::link`. Adding more than `Limit::COMPONENTS` rows is reported by `validate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - Components within the row, up to `Limit::ROW_COMPONENTS`.
+    pub fn row(mut self, components: Vec<Component>) -> Self {
+        self.components.push(Component::row(components));
+        self
+    }
 
+    /// Adds additional action rows to the message. Adding more than `Limit::COMPONENTS` rows is
+    /// reported by `validate`.
+    pub fn components(mut self, rows: &[Component]) -> Self {
+        self.components.extend(rows.iter().cloned());
         self
     }
 }
@@ -208,9 +404,12 @@ impl MessageBuilder {
 /// `MessageAPI` is used to negotiate `Message` related functions with the Discord API. This allows
 /// the user to **Create**, **Get**, **Edit**, and **Delete** messages sent by the webhook. This is
 /// accessed by proxy in `WebhookApi`.
+#[derive(Clone)]
 pub struct MessageApi {
     /// HTTP client used to send requests to the API.
-    client: Client,
+    pub(crate) client: Client,
+    /// Thread used for messages when one is not provided.
+    pub(crate) thread_id: Option<ChannelId>,
 }
 
 impl MessageApi {
@@ -223,11 +422,32 @@ impl MessageApi {
     pub(crate) fn new(client: &Client) -> Self {
         Self {
             client: client.clone(),
+            thread_id: None,
+        }
+    }
+
+    /// Assigns the thread used when a `thread_id` is not provided to `MessageApi::create`. Get,
+    /// edit, and delete requests are also performed within this thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id` - Thread within a Forum Channel or text channel, `None` to remove it.
+    pub fn in_thread(mut self, thread_id: Option<ChannelId>) -> Self {
+        self.thread_id = thread_id;
+        self
+    }
+
+    /// Creates the path to a message, including the default thread if there is one.
+    fn message_url(&self, id: MessageId) -> String {
+        match self.thread_id {
+            Some(thread) => format!("/messages/{}?thread_id={}", id, thread),
+            None => format!("/messages/{}", id),
         }
     }
 
     /// Creates a new message via the webhook with the supplied message. The `thread_id` is
-    /// required if message is to be created inside of a Forum Channel Thread.
+    /// required if message is to be created inside of a Forum Channel Thread, unless a default
+    /// thread was assigned with `MessageApi::in_thread`.
     ///
     /// # Arguments
     ///
@@ -237,28 +457,49 @@ impl MessageApi {
     /// ## References / Documentation
     ///
     /// <https://discord.com/developers/docs/resources/webhook#execute-webhook>
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "yadwh.message.create",
+            skip_all,
+            fields(webhook.id = %self.client.id, thread.id = ?thread_id.or(self.thread_id))
+        )
+    )]
     pub async fn create(
         &self,
         message: &MessageBuilder,
-        thread_id: Option<&str>,
+        thread_id: Option<ChannelId>,
     ) -> Result<Message> {
-        // Validate the message.
-        match message.validate() {
-            Ok(_) => (),
-            Err(error) => return Err(error),
-        };
+        // Truncate the message if a policy allows it, then validate it.
+        let mut message = message.clone();
+        message.truncate();
+        message.validate()?;
 
         // '?wait=true' tells the API to return the message with the newly created ID.
         let mut url = "?wait=true".to_string();
-        url = match thread_id {
+        if !message.components.is_empty() {
+            // Required for webhooks that are not owned by an application to send link buttons.
+            url.push_str("&with_components=true");
+        }
+        url = match thread_id.or(self.thread_id) {
             Some(value) => format!("{}&thread_id={}", url, value),
             None => url,
         };
 
-        let body = Body::from(serde_json::to_string(message).unwrap());
+        let body = message.body();
 
         // Send a POST request to create the new webhook message.
-        match self.client.send(Method::POST, &url, body).await {
+        match self
+            .client
+            .send_files(
+                Operation::CreateMessage,
+                &url,
+                body,
+                &message.files,
+                &message.truncated,
+            )
+            .await
+        {
             Ok(value) => match serde_json::from_str(&value) {
                 Ok(resp) => Ok(resp),
                 Err(_) => Err(WebhookError::BadParse("create response".to_string())),
@@ -276,13 +517,17 @@ impl MessageApi {
     /// ## References / Documentation
     ///
     /// <https://discord.com/developers/docs/resources/webhook#get-webhook-message>
-    pub async fn get(&self, id: &str) -> Result<Message> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "yadwh.message.get", skip_all, fields(webhook.id = %self.client.id, message.id = %id))
+    )]
+    pub async fn get(&self, id: MessageId) -> Result<Message> {
         // Path to the actual message being accessed.
-        let url = format!("/messages/{}", id);
-        let body = Body::from("");
+        let url = self.message_url(id);
+        let body = String::new();
 
         // Send a GET request to obtain an existing webhook message.
-        match self.client.send(Method::GET, &url, body).await {
+        match self.client.send(Operation::GetMessage, &url, body).await {
             Ok(value) => match serde_json::from_str(&value) {
                 Ok(resp) => Ok(resp),
                 Err(_) => Err(WebhookError::BadParse("get response".to_string())),
@@ -292,28 +537,42 @@ impl MessageApi {
     }
 
     /// Edits an existing message sent by the webhook. This will error if it no longer exists.
+    /// Only the values provided are replaced, omitted content, embeds, and components are kept.
     ///
     /// # Arguments
     ///
     /// * `id` - ID of the message to edit.
-    /// * `message` - Message used to replace the already existing message.
+    /// * `message` - Values used to replace those of the already existing message.
     ///
     /// ## References / Documentation
     ///
     /// <https://discord.com/developers/docs/resources/webhook#edit-webhook-message>
-    pub async fn edit(&self, id: &str, message: &MessageBuilder) -> Result<Message> {
-        // Validate the message.
-        match message.validate() {
-            Ok(_) => (),
-            Err(error) => return Err(error),
-        }
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "yadwh.message.edit", skip_all, fields(webhook.id = %self.client.id, message.id = %id))
+    )]
+    pub async fn edit(&self, id: MessageId, message: &MessageBuilder) -> Result<Message> {
+        // Truncate the message if a policy allows it, then validate it.
+        let mut message = message.clone();
+        message.truncate();
+        message.validate()?;
 
         // Path to the actual message being modified.
-        let url = format!("/messages/{}", id);
-        let body = Body::from(serde_json::to_string(message).unwrap());
+        let url = self.message_url(id);
+        let body = message.body();
 
         // Send a PATCH request to change an existing webhook message.
-        match self.client.send(Method::PATCH, &url, body).await {
+        match self
+            .client
+            .send_files(
+                Operation::EditMessage,
+                &url,
+                body,
+                &message.files,
+                &message.truncated,
+            )
+            .await
+        {
             Ok(value) => match serde_json::from_str(&value) {
                 Ok(resp) => Ok(resp),
                 Err(_) => Err(WebhookError::BadParse("edit response".to_string())),
@@ -331,13 +590,17 @@ impl MessageApi {
     /// ## References / Documentation
     ///
     /// <https://discord.com/developers/docs/resources/webhook#delete-webhook-message>
-    pub async fn delete(&self, id: &str) -> Result<()> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "yadwh.message.delete", skip_all, fields(webhook.id = %self.client.id, message.id = %id))
+    )]
+    pub async fn delete(&self, id: MessageId) -> Result<()> {
         // Path to the actual message being modified.
-        let url = format!("/messages/{}", id);
-        let body = Body::from("");
+        let url = self.message_url(id);
+        let body = String::new();
 
         // Send a DELETE request to remove an existing webhook message.
-        match self.client.send(Method::DELETE, &url, body).await {
+        match self.client.send(Operation::DeleteMessage, &url, body).await {
             Ok(_) => Ok(()),
             Err(error) => match error {
                 WebhookError::NoContent => Ok(()),
@@ -346,3 +609,114 @@ impl MessageApi {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Paths of every violation within the result.
+    fn paths(result: Result<usize>) -> Vec<String> {
+        match result {
+            Err(WebhookError::Invalid(violations)) => {
+                violations.into_iter().map(|v| v.path).collect()
+            }
+            other => panic!("expected violations, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let mut message = MessageBuilder::new();
+        message.content = Some("x".repeat(Limit::CONTENT + 1));
+
+        let mut embed = Embed::new();
+        embed.title(&"t".repeat(Limit::TITLE + 1));
+        for i in 0..4 {
+            embed.field(&format!("Field {}", i), "value", None);
+        }
+        embed.fields[3].value = "v".repeat(Limit::FIELD_VALUE + 1);
+        message.embeds = vec![Embed::new(), embed];
+
+        assert_eq!(
+            paths(message.validate()),
+            vec!["content", "embeds[1].title", "embeds[1].fields[3].value"]
+        );
+    }
+
+    #[test]
+    fn reports_sizes_and_limits() {
+        let mut message = MessageBuilder::new();
+        message.username = Some("é".repeat(Limit::USERNAME + 5));
+        match message.validate() {
+            Err(WebhookError::Invalid(violations)) => assert_eq!(
+                violations,
+                vec![Violation::new(
+                    "username",
+                    Limit::USERNAME + 5,
+                    Limit::USERNAME
+                )]
+            ),
+            other => panic!("expected violations, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn counts_characters_rather_than_bytes() {
+        let message = MessageBuilder::new().content(&"é".repeat(Limit::CONTENT));
+        assert!(message.unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn records_values_cut_by_setters_and_truncate() {
+        let mut message = MessageBuilder::new()
+            .truncation(Truncation::Ellipsis)
+            .content(&"x".repeat(Limit::CONTENT + 10))
+            .unwrap();
+        message.username = Some("u".repeat(Limit::USERNAME + 1));
+
+        let cut = message.truncate();
+        assert_eq!(cut.len(), 1);
+        let paths: Vec<&str> = message.truncated.iter().map(|t| t.path.as_str()).collect();
+        assert_eq!(paths, vec!["content", "username"]);
+    }
+
+    #[tokio::test]
+    async fn reports_truncated_values_to_observers() {
+        use crate::observe::RequestEvent;
+        use crate::webhook::WebhookApi;
+        use std::sync::{Arc, Mutex};
+
+        let events: Arc<Mutex<Vec<RequestEvent>>> = Arc::default();
+        let recorded = Arc::clone(&events);
+        let webhook = WebhookApi::new(WebhookId::new(1), "token")
+            .dry_run()
+            .observer(move |event: &RequestEvent| recorded.lock().unwrap().push(event.clone()));
+
+        let mut message = MessageBuilder::new().truncation(Truncation::Silent);
+        message.content = Some("x".repeat(Limit::CONTENT + 1));
+        let created = webhook.message.create(&message, None).await.unwrap();
+        webhook.message.get(created.id).await.unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].truncated,
+            vec![Truncated {
+                path: "content".to_string(),
+                size: Limit::CONTENT + 1,
+                kept: Limit::CONTENT,
+            }]
+        );
+        assert!(events[1].truncated.is_empty());
+    }
+
+    #[test]
+    fn reports_embed_violations_without_a_prefix() {
+        let mut embed = Embed::new();
+        for i in 0..Limit::FIELDS + 1 {
+            embed.field(&format!("Field {}", i), "value", None);
+        }
+        embed.fields[0].name = "n".repeat(Limit::FIELD_NAME + 1);
+        assert_eq!(paths(embed.validate()), vec!["fields[0].name", "fields"]);
+    }
+}
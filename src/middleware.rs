@@ -0,0 +1,258 @@
+//! Interception of requests and responses.
+//!
+//! `middleware` contains the `Middleware` trait that is called before each request is sent and
+//! after each response is received, including every retry. Middleware is added with
+//! `WebhookApi::middleware` and can be used for audit logging, injecting headers, signing
+//! requests, altering payloads, or injecting faults while testing.
+//!
+//! Middleware runs in a defined order: `before_send` is called in the order the middleware was
+//! added, and `after_response` in the reverse order, so the first middleware added is the
+//! outermost layer. Returning an error from either aborts the request with that error.
+
+use crate::attachment::File;
+use crate::client::{Operation, Result, WebhookError};
+use crate::secret::{Token, REDACTED};
+use crate::snowflake::WebhookId;
+use hyper::{HeaderMap, Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Middleware that intercepts every request sent to the Discord API.
+///
+/// Both methods default to doing nothing, only the required ones need to be implemented.
+pub trait Middleware: Send + Sync {
+    /// Called before the request is sent. The request may be modified, such as adding headers or
+    /// altering the body.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Request about to be sent.
+    fn before_send(&self, request: &mut Request) -> Result<()> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Called after a response is received, before it is parsed. The response may be modified,
+    /// such as replacing the status to inject a fault.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Request that was sent.
+    /// * `response` - Response received from the API.
+    fn after_response(&self, request: &Request, response: &mut Response) -> Result<()> {
+        let _ = (request, response);
+        Ok(())
+    }
+}
+
+/// Request about to be sent to the Discord API.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// Webhook the request is made for.
+    pub webhook_id: WebhookId,
+    /// Base URL of the API, such as `https://discord.com/api/v10`.
+    pub base_url: String,
+    /// Token of the webhook, accessible through `Request::url`.
+    pub(crate) token: Token,
+    /// Operation being performed.
+    pub operation: Operation,
+    /// HTTP method of the request.
+    pub method: Method,
+    /// Endpoint following the webhook token, such as `/messages/444455556666` or `?wait=true`.
+    pub endpoint: String,
+    /// Headers of the request, `Content-Type` is set to JSON by default.
+    pub headers: HeaderMap,
+    /// JSON body of the request, empty if there is none.
+    pub body: String,
+    /// Files uploaded with the request, sent alongside the body as `multipart/form-data`.
+    pub files: Vec<File>,
+    /// Attempt number of the request, starting at 0 and increasing with each retry.
+    pub attempt: u32,
+}
+
+impl Request {
+    /// Full URL of the request. This exposes the token and must never be displayed, use
+    /// `Request::redacted_url` instead.
+    pub fn url(&self) -> String {
+        format!(
+            "{}/webhooks/{}/{}{}",
+            self.base_url,
+            self.webhook_id,
+            self.token.expose(),
+            self.endpoint
+        )
+    }
+
+    /// URL of the request with the token masked, safe to be used within logs.
+    pub fn redacted_url(&self) -> String {
+        format!(
+            "{}/webhooks/{}/{}{}",
+            self.base_url, self.webhook_id, REDACTED, self.endpoint
+        )
+    }
+
+    /// Parses the JSON body of the request.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.body)
+            .map_err(|error| WebhookError::BadParse(format!("request body, {}", error)))
+    }
+
+    /// Replaces the body of the request with a value serialized as JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - New body of the request.
+    pub fn set_json<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        self.body = serde_json::to_string(value)
+            .map_err(|error| WebhookError::BadParse(format!("request body, {}", error)))?;
+        Ok(())
+    }
+}
+
+/// Response received from the Discord API.
+#[derive(Debug, Clone)]
+pub struct Response {
+    /// HTTP status of the response.
+    pub status: StatusCode,
+    /// Headers of the response, including rate limits.
+    pub headers: HeaderMap,
+    /// Body of the response.
+    pub body: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::transport::Transport;
+    use futures::future::BoxFuture;
+    use hyper::header::HeaderValue;
+    use std::sync::{Arc, Mutex};
+
+    type Log = Arc<Mutex<Vec<String>>>;
+
+    /// Middleware recording when it is called, optionally failing at one of the stages.
+    struct Layer {
+        name: &'static str,
+        log: Log,
+        fail_before: bool,
+        fail_after: bool,
+    }
+
+    impl Layer {
+        fn new(name: &'static str, log: &Log) -> Self {
+            Self {
+                name,
+                log: log.clone(),
+                fail_before: false,
+                fail_after: false,
+            }
+        }
+    }
+
+    impl Middleware for Layer {
+        fn before_send(&self, request: &mut Request) -> Result<()> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} before", self.name));
+            request
+                .headers
+                .append("x-layers", HeaderValue::from_static(self.name));
+            match self.fail_before {
+                true => Err(WebhookError::Unknown(format!("{} rejected", self.name))),
+                false => Ok(()),
+            }
+        }
+
+        fn after_response(&self, _: &Request, response: &mut Response) -> Result<()> {
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("{} after", self.name));
+            response.body.push_str(self.name);
+            match self.fail_after {
+                true => Err(WebhookError::Unknown(format!("{} rejected", self.name))),
+                false => Ok(()),
+            }
+        }
+    }
+
+    /// Transport replying with the layers that modified the request as the body.
+    struct Echo(Log);
+
+    impl Transport for Echo {
+        fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+            self.0.lock().unwrap().push("send".to_string());
+            let layers: Vec<&str> = request
+                .headers
+                .get_all("x-layers")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .collect();
+            let body = format!("{}|", layers.join(","));
+            Box::pin(async move {
+                Ok(Response {
+                    status: StatusCode::OK,
+                    headers: HeaderMap::new(),
+                    body,
+                })
+            })
+        }
+    }
+
+    fn client(log: &Log, layers: Vec<Layer>) -> Client {
+        let mut client = Client::new(WebhookId::new(1), "token");
+        client.transport = Arc::new(Echo(log.clone()));
+        for layer in layers {
+            client.middleware.push(Arc::new(layer));
+        }
+        client
+    }
+
+    fn entries(log: &Log) -> Vec<String> {
+        log.lock().unwrap().clone()
+    }
+
+    #[tokio::test]
+    async fn runs_in_order_and_unwinds_in_reverse() {
+        let log = Log::default();
+        let client = client(&log, vec![Layer::new("a", &log), Layer::new("b", &log)]);
+
+        let body = client.send(Operation::GetWebhook, "", String::new()).await;
+        assert_eq!(body.unwrap(), "a,b|ba");
+        assert_eq!(
+            entries(&log),
+            ["a before", "b before", "send", "b after", "a after"]
+        );
+    }
+
+    #[tokio::test]
+    async fn short_circuits_before_sending() {
+        let log = Log::default();
+        let mut failing = Layer::new("b", &log);
+        failing.fail_before = true;
+        let layers = vec![Layer::new("a", &log), failing, Layer::new("c", &log)];
+        let client = client(&log, layers);
+
+        match client.send(Operation::GetWebhook, "", String::new()).await {
+            Err(WebhookError::Unknown(message)) => assert_eq!(message, "b rejected"),
+            other => panic!("expected the middleware error, got {:?}", other),
+        }
+        assert_eq!(entries(&log), ["a before", "b before"]);
+    }
+
+    #[tokio::test]
+    async fn short_circuits_after_the_response() {
+        let log = Log::default();
+        let mut failing = Layer::new("b", &log);
+        failing.fail_after = true;
+        let client = client(&log, vec![Layer::new("a", &log), failing]);
+
+        match client.send(Operation::GetWebhook, "", String::new()).await {
+            Err(WebhookError::Unknown(message)) => assert_eq!(message, "b rejected"),
+            other => panic!("expected the middleware error, got {:?}", other),
+        }
+        assert_eq!(entries(&log), ["a before", "b before", "send", "b after"]);
+    }
+}
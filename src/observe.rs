@@ -0,0 +1,181 @@
+//! Hooks for observing every request made to the Discord API.
+//!
+//! `observe` contains the `Observer` trait that is called once per request, after any retries,
+//! with a `RequestEvent` describing the operation, outcome, latency, and rate limits. Observers are
+//! added with `WebhookApi::observer`. With the `metrics` feature enabled, `MetricsObserver`
+//! records counters and histograms using the `metrics` crate.
+
+pub use crate::client::Operation;
+use crate::client::RateLimit;
+use crate::snowflake::WebhookId;
+use crate::truncate::Truncated;
+use std::time::Duration;
+
+/// Observer that is notified after every request made to the Discord API.
+///
+/// Closures taking a `&RequestEvent` implement this trait.
+pub trait Observer: Send + Sync {
+    /// Called once per request after it has completed, including any retries.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - Details of the completed request.
+    fn observe(&self, event: &RequestEvent);
+}
+
+impl<F> Observer for F
+where
+    F: Fn(&RequestEvent) + Send + Sync,
+{
+    fn observe(&self, event: &RequestEvent) {
+        self(event)
+    }
+}
+
+/// Result of a request, used to group events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// Request succeeded.
+    Success,
+    /// Request was rate limited (429) and no retries remained.
+    RateLimited,
+    /// Discord rejected the request with an error status.
+    Rejected,
+    /// Request failed before a response was received, or the response could not be read.
+    Failed,
+}
+
+impl Outcome {
+    /// Name of the outcome, such as 'success' or 'rate_limited'.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::RateLimited => "rate_limited",
+            Outcome::Rejected => "rejected",
+            Outcome::Failed => "failed",
+        }
+    }
+}
+
+/// Details of a completed request.
+#[derive(Debug, Clone)]
+pub struct RequestEvent {
+    /// Webhook the request was made for.
+    pub webhook_id: WebhookId,
+    /// Operation that was performed.
+    pub operation: Operation,
+    /// Result of the request.
+    pub outcome: Outcome,
+    /// HTTP status of the final response, if one was received.
+    pub status: Option<u16>,
+    /// Discord JSON error code, if one was returned.
+    pub code: Option<u32>,
+    /// Total time taken, including any retries.
+    pub latency: Duration,
+    /// Amount of times the request was retried due to rate limits.
+    pub retries: u32,
+    /// Rate limit information of the final response, if one was received.
+    pub rate_limit: Option<RateLimit>,
+    /// Values cut from the message by its truncation policy before it was sent, empty for
+    /// requests without a message.
+    pub truncated: Vec<Truncated>,
+}
+
+/// Observer that records metrics with the `metrics` crate, labeled by webhook, operation, and
+/// outcome. Any exporter, such as Prometheus, can be installed to collect them.
+///
+/// Metrics recorded, using the default `yadwh` prefix:
+/// * `yadwh_requests_total` - Counter of completed requests.
+/// * `yadwh_request_duration_seconds` - Histogram of request latency.
+/// * `yadwh_retries_total` - Counter of retries due to rate limits.
+/// * `yadwh_rate_limited_total` - Counter of requests that exhausted their retries.
+/// * `yadwh_ratelimit_remaining` - Gauge of requests remaining in the rate limit bucket.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone)]
+pub struct MetricsObserver {
+    /// Prefix for the name of every metric.
+    prefix: String,
+}
+
+#[cfg(feature = "metrics")]
+impl MetricsObserver {
+    /// Creates a new observer using the `yadwh` prefix.
+    pub fn new() -> Self {
+        Self::with_prefix("yadwh")
+    }
+
+    /// Creates a new observer using a custom prefix for the name of every metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Prefix for metric names, such as 'alerts' for `alerts_requests_total`.
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+        }
+    }
+
+    /// Creates the full name of a metric.
+    fn name(&self, metric: &str) -> String {
+        format!("{}_{}", self.prefix, metric)
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Default for MetricsObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Observer for MetricsObserver {
+    fn observe(&self, event: &RequestEvent) {
+        let webhook = event.webhook_id.to_string();
+        let operation = event.operation.name();
+
+        metrics::counter!(
+            self.name("requests_total"),
+            "webhook" => webhook.clone(),
+            "operation" => operation,
+            "outcome" => event.outcome.as_str()
+        )
+        .increment(1);
+
+        metrics::histogram!(
+            self.name("request_duration_seconds"),
+            "webhook" => webhook.clone(),
+            "operation" => operation,
+            "outcome" => event.outcome.as_str()
+        )
+        .record(event.latency.as_secs_f64());
+
+        if event.retries > 0 {
+            metrics::counter!(
+                self.name("retries_total"),
+                "webhook" => webhook.clone(),
+                "operation" => operation
+            )
+            .increment(u64::from(event.retries));
+        }
+
+        if event.outcome == Outcome::RateLimited {
+            metrics::counter!(
+                self.name("rate_limited_total"),
+                "webhook" => webhook.clone(),
+                "operation" => operation
+            )
+            .increment(1);
+        }
+
+        let remaining = event.rate_limit.as_ref().and_then(|limit| limit.remaining);
+        if let Some(remaining) = remaining {
+            metrics::gauge!(
+                self.name("ratelimit_remaining"),
+                "webhook" => webhook,
+                "operation" => operation
+            )
+            .set(f64::from(remaining));
+        }
+    }
+}
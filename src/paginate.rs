@@ -0,0 +1,280 @@
+//! Splits oversized embeds into several valid embeds and messages.
+//!
+//! `paginate` contains the `Paginator` used to break a single logical `Embed` that exceeds
+//! `Limit::FIELDS`, `Limit::DESCRIPTION`, or `Limit::EMBED_TOTAL` into multiple pages. Each page
+//! carries over the title, color, and author of the original and is numbered in the footer, such
+//! as "(2/5)". Pages can then be grouped into as many messages as required.
+
+use crate::client::Limit;
+use crate::embed::{Embed, EmbedFooter};
+use crate::message::MessageBuilder;
+use crate::truncate::{self, Truncation};
+
+/// Space reserved in the footer for the page counter, such as " (999/999)".
+const COUNTER_RESERVE: usize = 10;
+
+/// Paginator is responsible for splitting a single embed into several embeds that each respect
+/// the limits enforced by the Discord API.
+///
+/// Fields that individually exceed `Limit::FIELD_NAME` or `Limit::FIELD_VALUE` are not modified
+/// and will still fail validation.
+#[derive(Debug, Clone)]
+pub struct Paginator {
+    /// Embed being split into pages.
+    embed: Embed,
+}
+
+impl Paginator {
+    /// Creates a new paginator for the embed provided.
+    ///
+    /// # Arguments
+    ///
+    /// * `embed` - Embed that will be split into pages.
+    pub fn new(embed: &Embed) -> Self {
+        Self {
+            embed: embed.clone(),
+        }
+    }
+
+    /// Splits the embed into pages. If the embed already fits within the limits, a single page
+    /// containing the original embed is returned.
+    pub fn embeds(&self) -> Vec<Embed> {
+        let embed = &self.embed;
+
+        // Size of the values carried over to every page.
+        let header =
+            size(embed.title.as_deref()) + size(embed.author.as_ref().map(|a| a.name.as_str()));
+        let footer = size(embed.footer.as_ref().map(|f| f.text.as_str())) + COUNTER_RESERVE;
+        let budget = Limit::EMBED_TOTAL.saturating_sub(header + footer);
+
+        // Break the description into chunks that fit on a single page.
+        let descriptions: Vec<String> = match &embed.description {
+            Some(value) => split_text(value, budget.min(Limit::DESCRIPTION)),
+            None => vec![],
+        };
+
+        // Each page is composed of a description (optional) and fields.
+        let mut pages: Vec<(Option<String>, Vec<usize>)> = descriptions
+            .into_iter()
+            .map(|desc| (Some(desc), vec![]))
+            .collect();
+        if pages.is_empty() {
+            pages.push((None, vec![]));
+        }
+
+        // Fill the pages with fields, starting on the page with the last description chunk.
+        let mut total = header + footer + size(pages.last().and_then(|p| p.0.as_deref()));
+        for (index, field) in embed.fields.iter().enumerate() {
+            let length = Limit::length(&field.name) + Limit::length(&field.value);
+            let current = pages.last_mut().unwrap();
+
+            let fits = current.1.len() < Limit::FIELDS && total + length <= Limit::EMBED_TOTAL;
+            if fits || (current.0.is_none() && current.1.is_empty()) {
+                current.1.push(index);
+            } else {
+                pages.push((None, vec![index]));
+                total = header + footer;
+            }
+
+            total += length;
+        }
+
+        // Nothing to split, return the original.
+        if pages.len() == 1 {
+            return vec![embed.clone()];
+        }
+
+        let count = pages.len();
+        pages
+            .into_iter()
+            .enumerate()
+            .map(|(page, (description, fields))| {
+                let mut split = Embed::new();
+                split.title = embed.title.clone();
+                split.url = embed.url.clone();
+                split.color = embed.color;
+                split.author = embed.author.clone();
+                split.description = description;
+                split.fields = fields.iter().map(|&i| embed.fields[i].clone()).collect();
+
+                // Media and timestamps are only kept on the first page.
+                if page == 0 {
+                    split.timestamp = embed.timestamp;
+                    split.image = embed.image.clone();
+                    split.thumbnail = embed.thumbnail.clone();
+                    split.video = embed.video.clone();
+                    split.provider = embed.provider.clone();
+                }
+
+                // Number the page within the footer.
+                let counter = format!("({}/{})", page + 1, count);
+                split.footer = Some(match &embed.footer {
+                    Some(value) => {
+                        // Clip the original text so the counter still fits within the limit.
+                        let mut text = value.text.clone();
+                        let max = Limit::FOOTER_TEXT - Limit::length(&counter) - 1;
+                        truncate::clip(
+                            "footer.text",
+                            &mut text,
+                            max,
+                            Truncation::Ellipsis,
+                            &mut vec![],
+                        );
+                        EmbedFooter {
+                            text: format!("{} {}", text, counter),
+                            icon_url: value.icon_url.clone(),
+                            proxy_icon_url: value.proxy_icon_url.clone(),
+                        }
+                    }
+                    None => EmbedFooter {
+                        text: counter,
+                        icon_url: None,
+                        proxy_icon_url: None,
+                    },
+                });
+
+                split
+            })
+            .collect()
+    }
+
+    /// Splits the embed into pages and groups them into as many messages as necessary. Each
+    /// message respects `Limit::EMBEDS` and `Limit::EMBED_TOTAL`.
+    pub fn messages(&self) -> Vec<MessageBuilder> {
+        group(&self.embeds())
+    }
+}
+
+/// Groups embeds into as many messages as necessary, preserving their order. Each message
+/// respects `Limit::EMBEDS` and `Limit::EMBED_TOTAL`.
+///
+/// # Arguments
+///
+/// * `embeds` - Embeds to be distributed across messages.
+pub fn group(embeds: &[Embed]) -> Vec<MessageBuilder> {
+    let mut messages: Vec<MessageBuilder> = vec![];
+    let mut total: usize = 0;
+
+    for embed in embeds.iter() {
+        let length = embed.length();
+        let full = match messages.last() {
+            Some(message) => {
+                message.embeds.len() >= Limit::EMBEDS || total + length > Limit::EMBED_TOTAL
+            }
+            None => true,
+        };
+
+        if full {
+            messages.push(MessageBuilder::new());
+            total = 0;
+        }
+
+        messages.last_mut().unwrap().embeds.push(embed.clone());
+        total += length;
+    }
+
+    messages
+}
+
+/// Obtains the size of an optional string.
+fn size(text: Option<&str>) -> usize {
+    text.map_or(0, Limit::length)
+}
+
+/// Splits text into chunks no larger than `max`, preferring to break on new lines, then
+/// whitespace, before breaking in the middle of a word.
+fn split_text(text: &str, max: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = vec![];
+    let mut remaining = text;
+
+    while Limit::length(remaining) > max {
+        // Find the largest slice that contains at most `max` characters, always making progress.
+        let end = match remaining.char_indices().nth(max.max(1)) {
+            Some((index, _)) => index,
+            None => remaining.len(),
+        };
+
+        // Prefer breaking on a new line, then whitespace.
+        let slice = &remaining[..end];
+        let cut = match slice
+            .rfind('\n')
+            .or_else(|| slice.rfind(char::is_whitespace))
+        {
+            Some(0) | None => end,
+            Some(value) => value,
+        };
+
+        chunks.push(remaining[..cut].trim_end().to_string());
+        remaining = remaining[cut..].trim_start();
+    }
+
+    if !remaining.is_empty() || chunks.is_empty() {
+        chunks.push(remaining.to_string());
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Embed with a title, footer, and the amount of fields provided.
+    fn embed(fields: usize, footer: &str) -> Embed {
+        let mut embed = Embed::new();
+        embed.title("Report").footer(footer, None, None);
+        for i in 0..fields {
+            embed.field(&format!("Field {}", i), "value", None);
+        }
+        embed
+    }
+
+    #[test]
+    fn keeps_embeds_that_fit() {
+        let original = embed(3, "Footer");
+        let pages = Paginator::new(&original).embeds();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].footer.as_ref().unwrap().text, "Footer");
+    }
+
+    #[test]
+    fn splits_fields_across_pages() {
+        let pages = Paginator::new(&embed(60, "Footer")).embeds();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages.iter().map(|p| p.fields.len()).sum::<usize>(), 60);
+        for (i, page) in pages.iter().enumerate() {
+            assert!(page.validate().is_ok());
+            assert_eq!(page.title.as_deref(), Some("Report"));
+            let footer = &page.footer.as_ref().unwrap().text;
+            assert_eq!(footer, &format!("Footer ({}/3)", i + 1));
+        }
+    }
+
+    #[test]
+    fn splits_long_descriptions() {
+        let mut original = Embed::new();
+        original.description(&"word ".repeat(2000));
+        let pages = Paginator::new(&original).embeds();
+        assert_eq!(pages.len(), 3);
+        assert!(pages.iter().all(|page| page.validate().is_ok()));
+    }
+
+    #[test]
+    fn clips_footers_to_fit_the_counter() {
+        let pages = Paginator::new(&embed(30, &"x".repeat(Limit::FOOTER_TEXT))).embeds();
+        assert_eq!(pages.len(), 2);
+        for page in pages.iter() {
+            let footer = &page.footer.as_ref().unwrap().text;
+            assert_eq!(Limit::length(footer), Limit::FOOTER_TEXT);
+            assert!(page.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn groups_pages_into_messages() {
+        let messages = Paginator::new(&embed(300, "Footer")).messages();
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|message| message.validate().is_ok()));
+        assert_eq!(messages.iter().map(|m| m.embeds.len()).sum::<usize>(), 12);
+    }
+}
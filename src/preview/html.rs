@@ -0,0 +1,375 @@
+//! HTML preview of messages that mimics the look of Discord.
+//!
+//! `html` contains the `Html` renderer. The output is a self-contained snippet: styles are
+//! embedded and scoped to the `yadwh-message` class, and nothing is loaded besides the images
+//! referenced by the message. Embeds are laid out with their colored border, author, title,
+//! description, fields (inline fields share a row of up to three), thumbnail, image, and footer.
+//! Action rows are drawn as buttons.
+
+use super::{blocks, display_time, inline, Block, Preview, Segment, DEFAULT_USERNAME};
+use crate::component::Component;
+use crate::embed::{Embed, EmbedField, EmbedMedia};
+
+/// Color of the border for embeds without a color.
+const DEFAULT_BORDER: &str = "#1e1f22";
+
+/// Styles embedded in every snippet, scoped to the message.
+const STYLES: &str = "\
+.yadwh-message{font-family:'gg sans','Noto Sans','Helvetica Neue',Helvetica,Arial,sans-serif;\
+background:#313338;color:#dbdee1;font-size:16px;line-height:1.375;padding:8px 16px;}\
+.yadwh-message a{color:#00a8fc;text-decoration:none;}\
+.yadwh-message .header{display:flex;align-items:center;gap:4px;}\
+.yadwh-message .username{color:#f2f3f5;font-weight:500;}\
+.yadwh-message .tag{background:#5865f2;color:#fff;font-size:10px;font-weight:500;\
+border-radius:3px;padding:0 4px;line-height:15px;}\
+.yadwh-message .time,.yadwh-message .edited{color:#949ba4;font-size:12px;}\
+.yadwh-message .content{white-space:pre-wrap;overflow-wrap:anywhere;}\
+.yadwh-message code{background:#2b2d31;border-radius:3px;padding:0 2px;font-size:85%;\
+font-family:Consolas,'Andale Mono WT','Andale Mono',monospace;}\
+.yadwh-message pre{background:#2b2d31;border:1px solid #1e1f22;border-radius:4px;\
+padding:8px;margin:4px 0;white-space:pre-wrap;}\
+.yadwh-message pre code{background:none;padding:0;}\
+.yadwh-message .embed{display:grid;grid-template-columns:auto min-content;max-width:432px;\
+background:#2b2d31;border-left:4px solid;border-radius:4px;padding:8px 16px 16px 12px;\
+margin-top:8px;gap:8px 16px;}\
+.yadwh-message .embed-body{display:grid;gap:8px;min-width:0;}\
+.yadwh-message .author{display:flex;align-items:center;gap:8px;font-size:14px;\
+font-weight:600;color:#f2f3f5;}\
+.yadwh-message .author img,.yadwh-message .footer img{width:24px;height:24px;\
+border-radius:50%;}\
+.yadwh-message .title{font-weight:600;color:#f2f3f5;}\
+.yadwh-message .description,.yadwh-message .field-value{font-size:14px;\
+white-space:pre-wrap;overflow-wrap:anywhere;}\
+.yadwh-message .fields{display:grid;grid-template-columns:repeat(12,1fr);gap:8px;}\
+.yadwh-message .field{grid-column:span 12;min-width:0;}\
+.yadwh-message .field.inline{grid-column:span 4;}\
+.yadwh-message .field-name{font-size:14px;font-weight:600;color:#f2f3f5;}\
+.yadwh-message .thumbnail{max-width:80px;max-height:80px;border-radius:4px;}\
+.yadwh-message .image{max-width:100%;border-radius:4px;}\
+.yadwh-message .footer{display:flex;align-items:center;gap:8px;font-size:12px;\
+color:#dbdee1;}\
+.yadwh-message .components{display:flex;flex-direction:column;gap:8px;margin-top:8px;}\
+.yadwh-message .row{display:flex;flex-wrap:wrap;gap:8px;}\
+.yadwh-message .button{display:inline-block;border-radius:3px;padding:2px 16px;\
+font-size:14px;font-weight:500;line-height:28px;color:#fff;background:#4e5058;}\
+.yadwh-message .button.primary{background:#5865f2;}\
+.yadwh-message .button.success{background:#248046;}\
+.yadwh-message .button.danger{background:#da373c;}\
+.yadwh-message .button.disabled{opacity:0.5;}";
+
+/// Renders messages as HTML.
+#[derive(Debug, Clone, Copy)]
+pub struct Html {
+    /// Whether the styles are embedded within the snippet.
+    styles: bool,
+}
+
+impl Html {
+    /// Creates a new renderer that embeds its styles within every snippet.
+    pub fn new() -> Self {
+        Self { styles: true }
+    }
+
+    /// Sets whether the styles are embedded within the snippet. Disable this when rendering
+    /// several messages into one page, and include `Html::styles` once instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `styles` - `false` to omit the `<style>` element.
+    pub fn embed_styles(mut self, styles: bool) -> Self {
+        self.styles = styles;
+        self
+    }
+
+    /// Styles used by the snippets, scoped to the `yadwh-message` class.
+    pub fn styles() -> &'static str {
+        STYLES
+    }
+
+    /// Renders the preview of a message as a self-contained HTML snippet.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `MessageBuilder` or `Message` to preview.
+    pub fn render<'a, P: Into<Preview<'a>>>(&self, message: P) -> String {
+        let message: Preview = message.into();
+        let mut html = String::new();
+
+        if self.styles {
+            html.push_str(&format!("<style>{}</style>\n", STYLES));
+        }
+        html.push_str("<div class=\"yadwh-message\">\n");
+
+        // Header: username, app tag, and time sent.
+        html.push_str("<div class=\"header\">");
+        html.push_str(&format!(
+            "<span class=\"username\">{}</span><span class=\"tag\">APP</span>",
+            escape(message.username.unwrap_or(DEFAULT_USERNAME))
+        ));
+        if let Some(timestamp) = message.timestamp {
+            html.push_str(&format!(
+                "<time class=\"time\" datetime=\"{}\">{}</time>",
+                timestamp,
+                display_time(timestamp)
+            ));
+        }
+        if message.edited {
+            html.push_str("<span class=\"edited\">(edited)</span>");
+        }
+        html.push_str("</div>\n");
+
+        if !message.content.is_empty() {
+            html.push_str(&format!(
+                "<div class=\"content\">{}</div>\n",
+                markdown(message.content)
+            ));
+        }
+
+        for embed in message.embeds {
+            html.push_str(&self.embed(embed));
+        }
+
+        if !message.components.is_empty() {
+            html.push_str("<div class=\"components\">");
+            for row in message.components {
+                html.push_str("<div class=\"row\">");
+                for component in row.components.iter() {
+                    html.push_str(&button(component));
+                }
+                html.push_str("</div>");
+            }
+            html.push_str("</div>\n");
+        }
+
+        html.push_str("</div>\n");
+        html
+    }
+
+    /// Renders a full HTML document containing the preview, such as for a CI artifact.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `MessageBuilder` or `Message` to preview.
+    pub fn document<'a, P: Into<Preview<'a>>>(&self, message: P) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Message Preview</title>\n</head>\n\
+             <body style=\"margin:0;background:#313338;\">\n{}</body>\n</html>\n",
+            self.render(message)
+        )
+    }
+
+    /// Renders a single embed.
+    fn embed(&self, embed: &Embed) -> String {
+        let border = match embed.color {
+            Some(color) => color.to_string(),
+            None => DEFAULT_BORDER.to_string(),
+        };
+
+        let mut html = format!(
+            "<div class=\"embed\" style=\"border-left-color:{}\">\n<div class=\"embed-body\">\n",
+            border
+        );
+
+        if let Some(author) = &embed.author {
+            html.push_str("<div class=\"author\">");
+            if let Some(icon) = author.icon_url.as_deref().and_then(safe_url) {
+                html.push_str(&format!("<img src=\"{}\" alt=\"\">", icon));
+            }
+            html.push_str(&link(&escape(&author.name), author.url.as_deref()));
+            html.push_str("</div>\n");
+        }
+
+        if let Some(title) = &embed.title {
+            html.push_str(&format!(
+                "<div class=\"title\">{}</div>\n",
+                link(&line(&inline(title)), embed.url.as_deref())
+            ));
+        }
+
+        if let Some(description) = &embed.description {
+            html.push_str(&format!(
+                "<div class=\"description\">{}</div>\n",
+                markdown(description)
+            ));
+        }
+
+        if !embed.fields.is_empty() {
+            html.push_str("<div class=\"fields\">\n");
+            for field in embed.fields.iter() {
+                html.push_str(&self.field(field));
+            }
+            html.push_str("</div>\n");
+        }
+
+        if let Some(url) = media(&embed.image) {
+            html.push_str(&format!("<img class=\"image\" src=\"{}\" alt=\"\">\n", url));
+        }
+
+        // Footer text and timestamp share a single line.
+        let text: Vec<String> = [
+            embed.footer.as_ref().map(|footer| escape(&footer.text)),
+            embed.timestamp.map(display_time),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !text.is_empty() {
+            html.push_str("<div class=\"footer\">");
+            let icon = embed
+                .footer
+                .as_ref()
+                .and_then(|footer| footer.icon_url.as_deref())
+                .and_then(safe_url);
+            if let Some(icon) = icon {
+                html.push_str(&format!("<img src=\"{}\" alt=\"\">", icon));
+            }
+            html.push_str(&format!("<span>{}</span></div>\n", text.join(" &bull; ")));
+        }
+
+        html.push_str("</div>\n");
+        if let Some(url) = media(&embed.thumbnail) {
+            html.push_str(&format!(
+                "<img class=\"thumbnail\" src=\"{}\" alt=\"\">\n",
+                url
+            ));
+        }
+        html.push_str("</div>\n");
+        html
+    }
+
+    /// Renders a single field, inline fields take a third of the row.
+    fn field(&self, field: &EmbedField) -> String {
+        let class = match field.inline {
+            Some(true) => "field inline",
+            _ => "field",
+        };
+
+        format!(
+            "<div class=\"{}\"><div class=\"field-name\">{}</div>\
+             <div class=\"field-value\">{}</div></div>\n",
+            class,
+            line(&inline(&field.name)),
+            markdown(&field.value)
+        )
+    }
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Renders a button, link buttons navigate to their URL.
+fn button(button: &Component) -> String {
+    let style = match button.style {
+        Some(Component::PRIMARY) => "primary",
+        Some(Component::SUCCESS) => "success",
+        Some(Component::DANGER) => "danger",
+        _ => "secondary",
+    };
+    let disabled = match button.disabled {
+        Some(true) => " disabled",
+        _ => "",
+    };
+    let label = escape(button.label.as_deref().unwrap_or_default());
+
+    match button.url.as_deref().and_then(safe_url) {
+        Some(url) => format!(
+            "<a class=\"button {}{}\" href=\"{}\">{} &#8599;</a>",
+            style, disabled, url, label
+        ),
+        None => format!(
+            "<span class=\"button {}{}\">{}</span>",
+            style, disabled, label
+        ),
+    }
+}
+
+/// Renders markdown, code blocks become `<pre>` elements.
+fn markdown(text: &str) -> String {
+    blocks(text)
+        .iter()
+        .map(|block| match block {
+            Block::Text(lines) => lines.iter().map(|l| line(l)).collect::<Vec<_>>().join("\n"),
+            Block::Code(language, code) => {
+                let class = match language {
+                    Some(language) => format!(" class=\"language-{}\"", escape(language)),
+                    None => String::new(),
+                };
+                format!("<pre><code{}>{}</code></pre>", class, escape(code))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders a single line of styled text.
+fn line(segments: &[Segment]) -> String {
+    let mut html = String::new();
+    for segment in segments {
+        let style = segment.style;
+        let tags: Vec<&str> = [
+            (style.bold, "strong"),
+            (style.italic, "em"),
+            (style.underline, "u"),
+            (style.strike, "s"),
+            (style.code, "code"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, tag)| tag)
+        .collect();
+
+        for tag in tags.iter() {
+            html.push_str(&format!("<{}>", tag));
+        }
+        html.push_str(&escape(&segment.text));
+        for tag in tags.iter().rev() {
+            html.push_str(&format!("</{}>", tag));
+        }
+    }
+    html
+}
+
+/// Wraps HTML within a link if a safe URL is provided.
+fn link(html: &str, url: Option<&str>) -> String {
+    match url.and_then(safe_url) {
+        Some(url) => format!("<a href=\"{}\">{}</a>", url, html),
+        None => html.to_string(),
+    }
+}
+
+/// Source URL of an image, thumbnail, or other media.
+fn media(media: &Option<EmbedMedia>) -> Option<String> {
+    media
+        .as_ref()
+        .and_then(|media| media.url.as_deref())
+        .and_then(safe_url)
+}
+
+/// Escapes a URL for use within an attribute, only http(s) URLs are allowed.
+fn safe_url(url: &str) -> Option<String> {
+    let lower = url.trim().to_ascii_lowercase();
+    match lower.starts_with("https://") || lower.starts_with("http://") {
+        true => Some(escape(url.trim())),
+        false => None,
+    }
+}
+
+/// Escapes text for use within HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,230 @@
+//! Local previews of messages, approximating how Discord displays them.
+//!
+//! `preview` renders a `MessageBuilder` or `Message` without sending it: `terminal` prints it with
+//! ANSI colors and `html` exports a self-contained HTML snippet that mimics the look of Discord,
+//! including embeds and components such as buttons. Both accept anything that converts into a
+//! `Preview`. Only basic markdown is understood: bold, italics, underline, strikethrough, inline
+//! code, and code blocks.
+
+pub mod html;
+pub mod terminal;
+
+use crate::component::Component;
+use crate::embed::Embed;
+use crate::message::{Message, MessageBuilder};
+use crate::timestamp::Timestamp;
+
+pub use html::Html;
+pub use terminal::Terminal;
+
+/// Name displayed when a message does not override the username of the webhook.
+pub(crate) const DEFAULT_USERNAME: &str = "Webhook";
+
+/// Message to be previewed, borrowed from a `MessageBuilder` or `Message`.
+#[derive(Debug, Clone, Copy)]
+pub struct Preview<'a> {
+    /// Username displayed for the message.
+    pub username: Option<&'a str>,
+    /// Contents of the message.
+    pub content: &'a str,
+    /// Embeds attached to the message.
+    pub embeds: &'a [Embed],
+    /// Action rows attached to the message.
+    pub components: &'a [Component],
+    /// When the message was sent, if it has been.
+    pub timestamp: Option<Timestamp>,
+    /// Whether the message has been edited.
+    pub edited: bool,
+}
+
+impl<'a> From<&'a MessageBuilder> for Preview<'a> {
+    fn from(message: &'a MessageBuilder) -> Self {
+        Self {
+            username: message.username.as_deref(),
+            content: message.content.as_deref().unwrap_or_default(),
+            embeds: &message.embeds,
+            components: &message.components,
+            timestamp: None,
+            edited: false,
+        }
+    }
+}
+
+impl<'a> From<&'a Message> for Preview<'a> {
+    fn from(message: &'a Message) -> Self {
+        Self {
+            username: None,
+            content: &message.content,
+            embeds: &message.embeds,
+            components: &message.components,
+            timestamp: Some(message.timestamp),
+            edited: message.edited_timestamp.is_some(),
+        }
+    }
+}
+
+/// Styles applied to a run of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Style {
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) underline: bool,
+    pub(crate) strike: bool,
+    pub(crate) code: bool,
+}
+
+/// Run of text sharing the same style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Segment {
+    pub(crate) text: String,
+    pub(crate) style: Style,
+}
+
+/// Block of markdown, either regular text or a fenced code block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Block {
+    /// Lines of text with inline styles.
+    Text(Vec<Vec<Segment>>),
+    /// Code block with an optional language.
+    Code(Option<String>, String),
+}
+
+/// Splits markdown into text and code blocks.
+///
+/// # Arguments
+///
+/// * `text` - Markdown to parse.
+pub(crate) fn blocks(text: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = vec![];
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let start = rest.find("```");
+        let end = start.and_then(|start| rest[start + 3..].find("```").map(|end| start + 3 + end));
+
+        let (before, code, after) = match (start, end) {
+            (Some(start), Some(end)) => (
+                &rest[..start],
+                Some(&rest[start + 3..end]),
+                &rest[end + 3..],
+            ),
+            _ => (rest, None, ""),
+        };
+
+        let before = before.strip_suffix('\n').unwrap_or(before);
+        if !before.is_empty() {
+            blocks.push(Block::Text(before.lines().map(inline).collect()));
+        }
+
+        if let Some(code) = code {
+            // The first line names the language if it contains no spaces.
+            let (language, code) = match code.split_once('\n') {
+                Some((first, code)) if !first.contains(' ') => {
+                    let language = (!first.is_empty()).then(|| first.to_string());
+                    (language, code)
+                }
+                _ => (None, code),
+            };
+            blocks.push(Block::Code(
+                language,
+                code.trim_end_matches('\n').to_string(),
+            ));
+        }
+
+        rest = after.strip_prefix('\n').unwrap_or(after);
+    }
+
+    blocks
+}
+
+/// Parses the inline styles of a single line.
+///
+/// # Arguments
+///
+/// * `line` - Line of markdown to parse.
+pub(crate) fn inline(line: &str) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = vec![];
+    let mut style = Style::default();
+    let mut text = String::new();
+    let mut rest = line;
+
+    // Adds the pending text as a segment before the style changes.
+    let flush = |text: &mut String, style: Style, segments: &mut Vec<Segment>| {
+        if !text.is_empty() {
+            segments.push(Segment {
+                text: std::mem::take(text),
+                style,
+            });
+        }
+    };
+
+    while let Some(c) = rest.chars().next() {
+        // Escaped characters are kept as is.
+        if c == '\\' {
+            if let Some(next) = rest[1..]
+                .chars()
+                .next()
+                .filter(|n| n.is_ascii_punctuation())
+            {
+                text.push(next);
+                rest = &rest[1 + next.len_utf8()..];
+                continue;
+            }
+        }
+
+        // Inline code, no other styles apply within it.
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                flush(&mut text, style, &mut segments);
+                let code = Style {
+                    code: true,
+                    ..style
+                };
+                segments.push(Segment {
+                    text: rest[1..1 + end].to_string(),
+                    style: code,
+                });
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+
+        let marker = ["**", "__", "~~", "*", "_"]
+            .into_iter()
+            .find(|marker| rest.starts_with(marker));
+
+        if let Some(marker) = marker {
+            let active = match marker {
+                "**" => style.bold,
+                "__" => style.underline,
+                "~~" => style.strike,
+                _ => style.italic,
+            };
+
+            // Only open a style if it is closed later on the line.
+            if active || rest[marker.len()..].contains(marker) {
+                flush(&mut text, style, &mut segments);
+                match marker {
+                    "**" => style.bold = !style.bold,
+                    "__" => style.underline = !style.underline,
+                    "~~" => style.strike = !style.strike,
+                    _ => style.italic = !style.italic,
+                }
+                rest = &rest[marker.len()..];
+                continue;
+            }
+        }
+
+        text.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    flush(&mut text, style, &mut segments);
+    segments
+}
+
+/// Formats a timestamp for display, such as `2023-06-01 12:34 UTC`.
+pub(crate) fn display_time(timestamp: Timestamp) -> String {
+    let iso = timestamp.to_iso8601();
+    format!("{} {} UTC", &iso[..10], &iso[11..16])
+}
@@ -0,0 +1,426 @@
+//! Terminal preview of messages using ANSI escape codes.
+//!
+//! `terminal` contains the `Terminal` renderer. Each embed is drawn with a side bar in the color of
+//! the embed, followed by its author, title, description, fields, images, and footer. Inline fields
+//! are laid out in rows of up to three columns, and buttons are drawn beneath the embeds.
+
+use super::{blocks, display_time, inline, Block, Preview, Segment, Style, DEFAULT_USERNAME};
+use crate::color::Color;
+use crate::component::Component;
+use crate::embed::{Embed, EmbedField};
+
+/// Color of the side bar for embeds without a color.
+const DEFAULT_BAR: Color = Color::new(0x4F545C);
+
+/// Character used to draw the side bar of an embed.
+const BAR: &str = "\u{258C}";
+
+/// Maximum columns of inline fields per row.
+const COLUMNS: usize = 3;
+
+/// Space between columns of inline fields.
+const GUTTER: usize = 2;
+
+/// ANSI escape code that resets every style.
+const RESET: &str = "\x1b[0m";
+
+/// Renders messages for display within a terminal.
+#[derive(Debug, Clone, Copy)]
+pub struct Terminal {
+    /// Maximum width of the output, in characters.
+    width: usize,
+    /// Whether ANSI escape codes are used.
+    ansi: bool,
+}
+
+impl Terminal {
+    /// Creates a new renderer, 80 characters wide with ANSI escape codes.
+    pub fn new() -> Self {
+        Self {
+            width: 80,
+            ansi: true,
+        }
+    }
+
+    /// Sets the maximum width of the output.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Width in characters, at least 20 is used.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width.max(20);
+        self
+    }
+
+    /// Sets whether ANSI escape codes are used for colors and styles.
+    ///
+    /// # Arguments
+    ///
+    /// * `ansi` - False to produce plain text, such as when output is not a terminal.
+    pub fn ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    /// Prints the preview of a message to standard output.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `MessageBuilder` or `Message` to preview.
+    pub fn print<'a, P: Into<Preview<'a>>>(&self, message: P) {
+        println!("{}", self.render(message));
+    }
+
+    /// Renders the preview of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `MessageBuilder` or `Message` to preview.
+    pub fn render<'a, P: Into<Preview<'a>>>(&self, message: P) -> String {
+        let message: Preview = message.into();
+        let mut lines: Vec<String> = vec![];
+
+        // Header: username, app tag, and time sent.
+        let mut header = self.paint(
+            message.username.unwrap_or(DEFAULT_USERNAME),
+            Style {
+                bold: true,
+                ..Default::default()
+            },
+        );
+        header.push(' ');
+        header.push_str(&self.color(" APP ", Color::BLURPLE, true));
+        if let Some(timestamp) = message.timestamp {
+            header.push(' ');
+            header.push_str(&self.dim(&display_time(timestamp)));
+        }
+        if message.edited {
+            header.push(' ');
+            header.push_str(&self.dim("(edited)"));
+        }
+        lines.push(header);
+
+        lines.extend(self.markdown(message.content, self.width));
+
+        for embed in message.embeds {
+            lines.push(String::new());
+            lines.extend(self.embed(embed));
+        }
+
+        // Each action row is drawn on its own line.
+        for row in message.components {
+            let buttons: Vec<String> = row
+                .components
+                .iter()
+                .map(|component| self.button(component))
+                .collect();
+            if !buttons.is_empty() {
+                lines.push(buttons.join(" "));
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// Renders a single embed with its side bar.
+    fn embed(&self, embed: &Embed) -> Vec<String> {
+        let width = self.width - 2;
+        let mut body: Vec<String> = vec![];
+
+        if let Some(author) = &embed.author {
+            let style = Style {
+                bold: true,
+                ..Default::default()
+            };
+            body.extend(self.wrap(&[plain(&author.name, style)], width));
+        }
+
+        if let Some(title) = &embed.title {
+            let mut segments = inline(title);
+            for segment in segments.iter_mut() {
+                segment.style.bold = true;
+                segment.style.underline |= embed.url.is_some();
+            }
+            body.extend(self.wrap(&segments, width));
+        }
+
+        if let Some(description) = &embed.description {
+            body.extend(self.markdown(description, width));
+        }
+
+        // Inline fields are grouped into rows, other fields take a full row.
+        let mut row: Vec<&EmbedField> = vec![];
+        for field in embed.fields.iter() {
+            if field.inline != Some(true) || row.len() == COLUMNS {
+                body.extend(self.fields(&row, width));
+                row.clear();
+            }
+
+            match field.inline {
+                Some(true) => row.push(field),
+                _ => body.extend(self.fields(&[field], width)),
+            }
+        }
+        body.extend(self.fields(&row, width));
+
+        for (name, media) in [("image", &embed.image), ("thumbnail", &embed.thumbnail)] {
+            if let Some(url) = media.as_ref().and_then(|media| media.url.as_deref()) {
+                body.push(self.dim(&format!("[{}: {}]", name, url)));
+            }
+        }
+
+        // Footer text and timestamp share a single line.
+        let footer: Vec<String> = [
+            embed.footer.as_ref().map(|footer| footer.text.clone()),
+            embed.timestamp.map(display_time),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !footer.is_empty() {
+            let text = footer.join(" \u{2022} ");
+            for line in self.wrap(&[plain(&text, Style::default())], width) {
+                body.push(self.dim(&line));
+            }
+        }
+
+        let bar = self.color(BAR, embed.color.unwrap_or(DEFAULT_BAR), false);
+        body.iter()
+            .map(|line| format!("{} {}", bar, line))
+            .collect()
+    }
+
+    /// Renders a button in the color of its style, link buttons are marked with an arrow.
+    fn button(&self, button: &Component) -> String {
+        let label = button.label.as_deref().unwrap_or_default();
+        let (label, color) = match button.style {
+            Some(Component::PRIMARY) => (format!(" {} ", label), Color::BLURPLE),
+            Some(Component::SUCCESS) => (format!(" {} ", label), Color::new(0x248046)),
+            Some(Component::DANGER) => (format!(" {} ", label), Color::new(0xDA373C)),
+            Some(Component::LINK) => (format!(" {} \u{2197} ", label), DEFAULT_BAR),
+            _ => (format!(" {} ", label), DEFAULT_BAR),
+        };
+
+        match self.ansi {
+            true => self.color(&label, color, true),
+            false => format!("[{}]", label),
+        }
+    }
+
+    /// Renders a row of fields side by side.
+    fn fields(&self, row: &[&EmbedField], width: usize) -> Vec<String> {
+        if row.is_empty() {
+            return vec![];
+        }
+
+        let column = match row.len() {
+            1 => width,
+            _ => (width - GUTTER * (COLUMNS - 1)) / COLUMNS,
+        };
+
+        let cells: Vec<Vec<(String, usize)>> = row
+            .iter()
+            .map(|field| {
+                let mut name = inline(&field.name);
+                for segment in name.iter_mut() {
+                    segment.style.bold = true;
+                }
+
+                let mut lines = self.lines(&name, column);
+                for block in blocks(&field.value) {
+                    lines.extend(self.block(&block, column));
+                }
+                lines
+            })
+            .collect();
+
+        let height = cells
+            .iter()
+            .map(|cell| cell.len())
+            .max()
+            .unwrap_or_default();
+        (0..height)
+            .map(|index| {
+                let mut line = String::new();
+                for (i, cell) in cells.iter().enumerate() {
+                    let (text, size) = cell.get(index).cloned().unwrap_or_default();
+                    line.push_str(&text);
+                    if i + 1 < cells.len() {
+                        line.push_str(&" ".repeat(column.saturating_sub(size) + GUTTER));
+                    }
+                }
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Renders markdown, wrapping it to the width provided.
+    fn markdown(&self, text: &str, width: usize) -> Vec<String> {
+        blocks(text)
+            .iter()
+            .flat_map(|block| self.block(block, width))
+            .map(|(line, _)| line)
+            .collect()
+    }
+
+    /// Renders a block of markdown, returning each line with its visible width.
+    fn block(&self, block: &Block, width: usize) -> Vec<(String, usize)> {
+        match block {
+            Block::Text(lines) => lines
+                .iter()
+                .flat_map(|line| self.lines(line, width))
+                .collect(),
+            Block::Code(_, code) => {
+                let style = Style {
+                    code: true,
+                    ..Default::default()
+                };
+                code.lines()
+                    .flat_map(|line| {
+                        let line = format!(" {} ", line);
+                        self.lines(&[plain(&line, style)], width)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Wraps styled text to the width provided, returning each line with its visible width.
+    fn lines(&self, segments: &[Segment], width: usize) -> Vec<(String, usize)> {
+        wrap(segments, width)
+            .iter()
+            .map(|line| {
+                let size = line.iter().map(|s| s.text.chars().count()).sum();
+                let text = line.iter().map(|s| self.paint(&s.text, s.style)).collect();
+                (text, size)
+            })
+            .collect()
+    }
+
+    /// Wraps styled text to the width provided.
+    fn wrap(&self, segments: &[Segment], width: usize) -> Vec<String> {
+        self.lines(segments, width)
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect()
+    }
+
+    /// Applies a style to text.
+    fn paint(&self, text: &str, style: Style) -> String {
+        if !self.ansi || style == Style::default() {
+            return text.to_string();
+        }
+
+        let mut codes: Vec<&str> = vec![];
+        if style.bold {
+            codes.push("1");
+        }
+        if style.italic {
+            codes.push("3");
+        }
+        if style.underline {
+            codes.push("4");
+        }
+        if style.strike {
+            codes.push("9");
+        }
+        if style.code {
+            codes.push("48;5;236");
+        }
+
+        format!("\x1b[{}m{}{}", codes.join(";"), text, RESET)
+    }
+
+    /// Colors text, either the foreground or the background.
+    fn color(&self, text: &str, color: Color, background: bool) -> String {
+        if !self.ansi {
+            return text.to_string();
+        }
+
+        let (red, green, blue) = color.rgb();
+        let layer = if background { 48 } else { 38 };
+        format!(
+            "\x1b[{};2;{};{};{}m{}{}",
+            layer, red, green, blue, text, RESET
+        )
+    }
+
+    /// Dims text.
+    fn dim(&self, text: &str) -> String {
+        match self.ansi {
+            true => format!("\x1b[2m{}{}", text, RESET),
+            false => text.to_string(),
+        }
+    }
+}
+
+impl Default for Terminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates a segment of text with a single style.
+fn plain(text: &str, style: Style) -> Segment {
+    Segment {
+        text: text.to_string(),
+        style,
+    }
+}
+
+/// Wraps styled text at spaces so no line exceeds the width, words longer than the width are
+/// broken.
+fn wrap(segments: &[Segment], width: usize) -> Vec<Vec<Segment>> {
+    let width = width.max(1);
+    let mut lines: Vec<Vec<Segment>> = vec![vec![]];
+    let mut size = 0;
+
+    for segment in segments {
+        // Split into words, keeping the spaces so they are preserved within the line.
+        for word in segment.text.split_inclusive(' ') {
+            let mut word = word.to_string();
+            let mut length = word.trim_end().chars().count();
+
+            if size > 0 && size + length > width {
+                lines.push(vec![]);
+                size = 0;
+            }
+
+            // Break words longer than the line.
+            while length > width {
+                let head: String = word.chars().take(width).collect();
+                word = word.chars().skip(width).collect();
+                length = word.trim_end().chars().count();
+                push(lines.last_mut(), head, segment.style);
+                lines.push(vec![]);
+                size = 0;
+            }
+
+            size += word.chars().count();
+            push(lines.last_mut(), word, segment.style);
+        }
+    }
+
+    // Trailing spaces are not displayed.
+    for line in lines.iter_mut() {
+        if let Some(last) = line.last_mut() {
+            last.text.truncate(last.text.trim_end().len());
+        }
+        line.retain(|segment| !segment.text.is_empty());
+    }
+
+    lines
+}
+
+/// Appends text to a line, merging it with the last segment if the styles match.
+fn push(line: Option<&mut Vec<Segment>>, text: String, style: Style) {
+    let line = match line {
+        Some(line) => line,
+        None => return,
+    };
+
+    match line.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(&text),
+        _ => line.push(Segment { text, style }),
+    }
+}
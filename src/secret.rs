@@ -0,0 +1,140 @@
+//! Protection for webhook tokens.
+//!
+//! `secret` contains the `Token` type that wraps the token of a webhook so it cannot leak through
+//! `Debug` or `Display`, such as in panic messages and logs. URLs containing a token can be masked
+//! with `redact_url`. With the `zeroize` feature enabled, tokens are zeroed from memory on drop.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Text displayed in place of a token.
+pub const REDACTED: &str = "[redacted]";
+
+/// Token of a webhook. Displays as `[redacted]`, the actual value is only accessible through
+/// `Token::expose`.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct Token(String);
+
+impl Token {
+    /// Wraps the token of a webhook.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Token of the webhook.
+    pub fn new(token: &str) -> Self {
+        Self(token.to_string())
+    }
+
+    /// Actual value of the token. Avoid logging or displaying this value.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Checks if the token is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Token").field(&REDACTED).finish()
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl From<&str> for Token {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Token {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Token {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
+/// Serialized with the actual value, as required when sending a webhook back to the API.
+impl Serialize for Token {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Token {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self(String::deserialize(deserializer)?))
+    }
+}
+
+/// Masks the token within a webhook URL, such as
+/// `https://discord.com/api/webhooks/111122223333/[redacted]/messages/444455556666`. Text that
+/// does not contain a webhook path is returned unchanged.
+///
+/// # Arguments
+///
+/// * `url` - URL or path that may contain a webhook token.
+pub fn redact_url(url: &str) -> String {
+    let start = match url.find("/webhooks/") {
+        Some(index) => index + "/webhooks/".len(),
+        None => return url.to_string(),
+    };
+
+    // Skip the webhook ID, the token follows it.
+    let token_start = match url[start..].find('/') {
+        Some(index) => start + index + 1,
+        None => return url.to_string(),
+    };
+
+    let token_end = url[token_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |index| token_start + index);
+
+    match token_start == token_end {
+        true => url.to_string(),
+        false => format!("{}{}{}", &url[..token_start], REDACTED, &url[token_end..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "AAAABBBBCCCC";
+
+    #[test]
+    fn never_displays_the_token() {
+        let token = Token::new(SECRET);
+        assert_eq!(token.to_string(), REDACTED);
+        assert_eq!(format!("{:?}", token), "Token(\"[redacted]\")");
+        assert!(!format!("{:#?}", token).contains(SECRET));
+        assert_eq!(token.expose(), SECRET);
+    }
+
+    #[test]
+    fn redacts_webhook_urls() {
+        assert_eq!(
+            redact_url("https://discord.com/api/webhooks/123/AAAABBBBCCCC/messages/456?wait=true"),
+            "https://discord.com/api/webhooks/123/[redacted]/messages/456?wait=true"
+        );
+        assert_eq!(
+            redact_url("/webhooks/123/AAAABBBBCCCC"),
+            "/webhooks/123/[redacted]"
+        );
+        assert_eq!(redact_url("/webhooks/123"), "/webhooks/123");
+        assert_eq!(redact_url("https://example.com"), "https://example.com");
+    }
+}
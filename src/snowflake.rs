@@ -0,0 +1,306 @@
+//! Strongly typed Discord IDs.
+//!
+//! `snowflake` contains the `Snowflake` used by Discord to uniquely identify objects, along with
+//! typed wrappers such as `MessageId` and `WebhookId` so IDs of different objects cannot be mixed
+//! up. Snowflakes are serialized as strings, can be parsed from either strings or numbers, and
+//! expose the time they were created at.
+//!
+//! ## References / Documentation
+//!
+//! <https://discord.com/developers/docs/reference#snowflakes>
+
+use crate::client::WebhookError;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// First second of 2015 in milliseconds since the Unix epoch, used as the base for timestamps.
+pub const DISCORD_EPOCH: u64 = 1_420_070_400_000;
+
+/// Unique ID used by Discord for messages, webhooks, channels, and every other object.
+///
+/// Snowflakes are ordered by the time they were created, so sorting them sorts the objects they
+/// identify from oldest to newest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Snowflake(u64);
+
+impl Snowflake {
+    /// Creates a new snowflake from its raw value. The value is not checked, unlike parsing or
+    /// deserializing a snowflake this accepts 0, which Discord never assigns to an object.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - Raw value of the snowflake.
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Creates the smallest snowflake for the time provided, useful for comparing against other
+    /// snowflakes or generating new IDs. Times before `DISCORD_EPOCH` are clamped to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Time the snowflake is created for.
+    pub fn from_time(time: SystemTime) -> Self {
+        let millis = match time.duration_since(UNIX_EPOCH) {
+            Ok(value) => value.as_millis() as u64,
+            Err(_) => 0,
+        };
+
+        Self(millis.saturating_sub(DISCORD_EPOCH) << 22)
+    }
+
+    /// Raw value of the snowflake.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Milliseconds since the Unix epoch that the snowflake was created.
+    pub const fn unix_millis(self) -> u64 {
+        (self.0 >> 22) + DISCORD_EPOCH
+    }
+
+    /// Time that the snowflake was created.
+    pub fn timestamp(self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.unix_millis())
+    }
+
+    /// Amount of time that has passed since the snowflake was created. Returns a duration of 0
+    /// if the snowflake was created in the future.
+    pub fn age(self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.timestamp())
+            .unwrap_or_default()
+    }
+
+    /// Internal worker ID that generated the snowflake.
+    pub const fn worker_id(self) -> u8 {
+        ((self.0 & 0x3E0000) >> 17) as u8
+    }
+
+    /// Internal process ID that generated the snowflake.
+    pub const fn process_id(self) -> u8 {
+        ((self.0 & 0x1F000) >> 12) as u8
+    }
+
+    /// Sequence number, incremented for every snowflake generated by the same process.
+    pub const fn sequence(self) -> u16 {
+        (self.0 & 0xFFF) as u16
+    }
+}
+
+impl From<u64> for Snowflake {
+    /// Converts the raw value without checking it, see `Snowflake::new`.
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Snowflake> for u64 {
+    fn from(value: Snowflake) -> Self {
+        value.0
+    }
+}
+
+impl FromStr for Snowflake {
+    type Err = WebhookError;
+
+    /// Parses a snowflake, only accepting non-zero decimal values that fit within 64 bits.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || WebhookError::BadParse(format!("snowflake '{}'", value));
+
+        // Only digits are allowed, `u64::from_str` also accepts a leading '+'.
+        if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(error());
+        }
+
+        match value.parse::<u64>() {
+            Ok(0) | Err(_) => Err(error()),
+            Ok(parsed) => Ok(Self(parsed)),
+        }
+    }
+}
+
+impl fmt::Display for Snowflake {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Snowflake {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Snowflake {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SnowflakeVisitor)
+    }
+}
+
+/// Deserializes a snowflake from either a string or a number.
+struct SnowflakeVisitor;
+
+impl Visitor<'_> for SnowflakeVisitor {
+    type Value = Snowflake;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a snowflake as a string or integer")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        match value {
+            0 => Err(E::custom("snowflake cannot be 0")),
+            _ => Ok(Snowflake(value)),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::custom("snowflake cannot be negative")),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+/// Creates a typed wrapper around a `Snowflake` for a specific kind of object.
+macro_rules! snowflake_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub Snowflake);
+
+        impl $name {
+            /// Creates a new ID from its raw value without checking it, see `Snowflake::new`.
+            pub const fn new(value: u64) -> Self {
+                Self(Snowflake::new(value))
+            }
+
+            /// Raw value of the ID.
+            pub const fn get(self) -> u64 {
+                self.0.get()
+            }
+        }
+
+        impl Deref for $name {
+            type Target = Snowflake;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl From<Snowflake> for $name {
+            fn from(value: Snowflake) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(value: u64) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = WebhookError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Ok(Self(value.parse()?))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
+snowflake_id!(
+    /// ID of a webhook.
+    WebhookId
+);
+
+snowflake_id!(
+    /// ID of a message.
+    MessageId
+);
+
+snowflake_id!(
+    /// ID of a channel, this includes threads.
+    ChannelId
+);
+
+snowflake_id!(
+    /// ID of a guild (server).
+    GuildId
+);
+
+snowflake_id!(
+    /// ID of a user.
+    UserId
+);
+
+snowflake_id!(
+    /// ID of a role within a guild.
+    RoleId
+);
+
+snowflake_id!(
+    /// ID of an application command.
+    CommandId
+);
+
+snowflake_id!(
+    /// ID of a custom emoji.
+    EmojiId
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_strings() {
+        let id: Snowflake = "175928847299117063".parse().unwrap();
+        assert_eq!(id.get(), 175928847299117063);
+        assert_eq!(id.unix_millis(), 1462015105796);
+        assert_eq!(id.to_string(), "175928847299117063");
+    }
+
+    #[test]
+    fn rejects_invalid_strings() {
+        for value in ["", "0", "+1", "-1", " 1", "1a", "18446744073709551616"] {
+            assert!(value.parse::<Snowflake>().is_err(), "accepted '{}'", value);
+        }
+    }
+
+    #[test]
+    fn parses_typed_ids() {
+        let id: WebhookId = "1234567890".parse().unwrap();
+        assert_eq!(id, WebhookId::new(1234567890));
+        assert!("0".parse::<MessageId>().is_err());
+    }
+
+    #[test]
+    fn deserializes_strings_and_numbers() {
+        let id: ChannelId = serde_json::from_str("\"42\"").unwrap();
+        assert_eq!(id.get(), 42);
+        let id: ChannelId = serde_json::from_str("42").unwrap();
+        assert_eq!(id.get(), 42);
+        assert!(serde_json::from_str::<ChannelId>("0").is_err());
+        assert!(serde_json::from_str::<ChannelId>("-42").is_err());
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"42\"");
+    }
+}
@@ -0,0 +1,509 @@
+//! Message templates with variables that are filled in from a context.
+//!
+//! `template` contains `Template`, a `MessageBuilder` or `Embed` layout whose strings contain
+//! placeholders rendered against any `serde::Serialize` context. After rendering, the message is
+//! truncated according to its policy and validated against `Limit`.
+//!
+//! * `{{service}}` inserts a value, nested values are accessed with `{{service.region}}`, items
+//!   of lists with `{{hosts.0}}`, and the amount of items with `{{hosts.length}}`.
+//! * `{{{service}}}` inserts a value without escaping Discord markdown.
+//! * `{{#each failures}}...{{/each}}` repeats text for every item, items are accessed with
+//!   `{{this}}` or by their keys, and `{{@index}}` is the position starting at 0.
+//! * Within an array, such as the fields of an embed, an element of the form
+//!   `{ "{{#each failures}}": { "name": "{{name}}", "value": "{{reason}}" } }` is expanded into
+//!   one element per item.
+//!
+//! Values inserted into markdown (the content, and the title, description, and fields of embeds)
+//! have markdown escaped with `markdown::escape`. A string containing only a placeholder, such as
+//! `"{{color}}"`, is replaced by the value itself, keeping numbers and booleans intact.
+
+use crate::attachment::File;
+use crate::client::{Result, WebhookError};
+use crate::embed::Embed;
+use crate::load::Format;
+use crate::markdown::escape;
+use crate::message::MessageBuilder;
+use crate::truncate::{self, Truncation};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Layout of a message or embed containing placeholders.
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// Layout with placeholders, in the shape of the JSON body.
+    layout: Value,
+    /// Files uploaded with every rendered message.
+    files: Vec<File>,
+    /// Truncation policy applied after rendering.
+    truncation: Truncation,
+}
+
+/// Part of a string, either text or a placeholder.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// Text copied as is.
+    Text(String),
+    /// Value inserted from the context, escaped unless raw.
+    Value { path: String, raw: bool },
+    /// Nodes repeated for every item of the value.
+    Each { path: String, body: Vec<Node> },
+}
+
+impl Template {
+    /// Creates a template from a message layout, keeping its files and truncation policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - Message containing placeholders.
+    pub fn new(message: &MessageBuilder) -> Self {
+        Self {
+            layout: serde_json::to_value(message).unwrap_or_default(),
+            files: message.files.clone(),
+            truncation: message.truncation,
+        }
+    }
+
+    /// Creates a template from an embed layout, rendered with `Template::render_embed`.
+    ///
+    /// # Arguments
+    ///
+    /// * `embed` - Embed containing placeholders.
+    pub fn embed(embed: &Embed) -> Self {
+        Self {
+            layout: serde_json::to_value(embed).unwrap_or_default(),
+            files: vec![],
+            truncation: embed.truncation.unwrap_or_default(),
+        }
+    }
+
+    /// Parses a template from a document, see `load` for the shape of the document.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - Contents of the document.
+    /// * `format` - Format of the document.
+    pub fn parse(text: &str, format: Format) -> Result<Self> {
+        let layout: Value = format.parse(text)?;
+        if !layout.is_object() {
+            return Err(WebhookError::BadParse(
+                "template, expected a table or object".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            layout,
+            files: vec![],
+            truncation: Truncation::default(),
+        })
+    }
+
+    /// Reads and parses a template from a file, the format is determined by its extension.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the document.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let format = Format::from_path(path)?;
+        let text = std::fs::read_to_string(path).map_err(|error| {
+            WebhookError::Unknown(format!("reading {}, {}", path.display(), error))
+        })?;
+
+        Self::parse(&text, format)
+    }
+
+    /// Sets the truncation policy applied to rendered messages before they are validated.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Policy for values exceeding their limit, see `Truncation`.
+    pub fn truncation(mut self, policy: Truncation) -> Self {
+        self.truncation = policy;
+        self
+    }
+
+    /// Renders the message, then truncates and validates it. Values that were cut are kept within
+    /// `MessageBuilder::truncated` and reported when the message is sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Values available to the placeholders.
+    pub fn render<C: Serialize>(&self, context: &C) -> Result<MessageBuilder> {
+        let mut message: MessageBuilder = self.render_as(context)?;
+        message.files = self.files.clone();
+        message.truncation = self.truncation;
+        message.truncate();
+        message.validate()?;
+        Ok(message)
+    }
+
+    /// Renders an embed, then truncates and validates it. Values that were cut are logged as
+    /// `tracing` warnings.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Values available to the placeholders.
+    pub fn render_embed<C: Serialize>(&self, context: &C) -> Result<Embed> {
+        let mut embed: Embed = self.render_as(context)?;
+        embed.truncation(self.truncation);
+        truncate::report(&embed.truncate());
+        embed.validate()?;
+        Ok(embed)
+    }
+
+    /// Renders the layout into any deserializable value without validating it.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - Values available to the placeholders.
+    pub fn render_as<T: DeserializeOwned, C: Serialize>(&self, context: &C) -> Result<T> {
+        let context = serde_json::to_value(context)
+            .map_err(|error| WebhookError::BadParse(format!("template context, {}", error)))?;
+
+        let mut scopes = Scopes {
+            values: vec![&context],
+            index: None,
+        };
+        let rendered = render_value(&self.layout, "", false, &mut scopes)?;
+
+        serde_path_to_error::deserialize(rendered)
+            .map_err(|error| WebhookError::BadParse(format!("rendered template, {}", error)))
+    }
+}
+
+/// Values available to placeholders, the innermost scope is last.
+struct Scopes<'a> {
+    /// Context followed by the items of each enclosing `{{#each}}`.
+    values: Vec<&'a Value>,
+    /// Position of the current item within the innermost `{{#each}}`.
+    index: Option<usize>,
+}
+
+impl<'a> Scopes<'a> {
+    /// Looks up a value by its path, searching from the innermost scope outwards.
+    fn lookup(&self, path: &str) -> Option<Value> {
+        if path == "@index" {
+            return self.index.map(Value::from);
+        }
+
+        let (first, rest) = match path.split_once('.') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (path, None),
+        };
+
+        let found = match first {
+            "this" => self.values.last().copied(),
+            _ => self.values.iter().rev().find_map(|scope| scope.get(first)),
+        }?;
+
+        // Items of lists are accessed by position, and `length` is the amount of items.
+        rest.into_iter()
+            .flat_map(|rest| rest.split('.'))
+            .try_fold(found.clone(), |value, key| match value {
+                Value::Array(items) if key == "length" => Some(Value::from(items.len())),
+                Value::Array(mut items) => {
+                    let index = key.parse::<usize>().ok().filter(|i| *i < items.len())?;
+                    Some(items.swap_remove(index))
+                }
+                Value::Object(mut map) => map.remove(key),
+                _ => None,
+            })
+    }
+
+    /// Looks up the items of an `{{#each}}`, erroring if they are not an array.
+    fn items(&self, path: &str, at: &str) -> Result<Vec<Value>> {
+        match self.lookup(path) {
+            Some(Value::Array(items)) => Ok(items),
+            Some(Value::Null) => Ok(vec![]),
+            Some(_) => Err(WebhookError::BadParse(format!(
+                "template {}, '{}' is not a list",
+                at, path
+            ))),
+            None => Err(missing(path, at)),
+        }
+    }
+}
+
+/// Renders every string within a value, expanding `{{#each}}` elements of arrays.
+fn render_value(value: &Value, at: &str, markdown: bool, scopes: &mut Scopes) -> Result<Value> {
+    match value {
+        Value::String(text) => render_string(text, at, markdown, scopes),
+        Value::Array(items) => {
+            let mut rendered: Vec<Value> = vec![];
+            for item in items {
+                let path = format!("{}[{}]", at, rendered.len());
+
+                // Elements of the form { "{{#each path}}": element } are repeated per item.
+                let each = item
+                    .as_object()
+                    .filter(|map| map.len() == 1)
+                    .and_then(|map| {
+                        let (key, element) = map.iter().next()?;
+                        let key = key.trim().strip_prefix("{{#each ")?.strip_suffix("}}")?;
+                        Some((key.trim().to_string(), element))
+                    });
+
+                match each {
+                    Some((key, element)) => {
+                        for (index, entry) in scopes.items(&key, &path)?.iter().enumerate() {
+                            let path = format!("{}[{}]", at, rendered.len());
+                            let value = with_item(scopes, entry, index, |scopes| {
+                                render_value(element, &path, markdown, scopes)
+                            })?;
+                            rendered.push(value);
+                        }
+                    }
+                    None => rendered.push(render_value(item, &path, markdown, scopes)?),
+                }
+            }
+            Ok(Value::Array(rendered))
+        }
+        Value::Object(map) => {
+            let mut rendered = Map::new();
+            for (key, value) in map {
+                let path = match at.is_empty() {
+                    true => key.clone(),
+                    false => format!("{}.{}", at, key),
+                };
+                let markdown = is_markdown(at, key);
+                rendered.insert(key.clone(), render_value(value, &path, markdown, scopes)?);
+            }
+            Ok(Value::Object(rendered))
+        }
+        value => Ok(value.clone()),
+    }
+}
+
+/// Renders a single string, a string containing only a placeholder keeps the type of its value.
+fn render_string(text: &str, at: &str, markdown: bool, scopes: &mut Scopes) -> Result<Value> {
+    let nodes = parse(text).map_err(|error| {
+        WebhookError::BadParse(format!("template {}, {}", display_path(at), error))
+    })?;
+
+    if let [Node::Value { path, .. }] = nodes.as_slice() {
+        match scopes.lookup(path) {
+            Some(value @ (Value::Number(_) | Value::Bool(_) | Value::Null)) => return Ok(value),
+            Some(_) => (),
+            None => return Err(missing(path, at)),
+        }
+    }
+
+    let mut output = String::new();
+    render_nodes(&nodes, at, markdown, scopes, &mut output)?;
+    Ok(Value::String(output))
+}
+
+/// Renders parsed nodes into the output.
+fn render_nodes(
+    nodes: &[Node],
+    at: &str,
+    markdown: bool,
+    scopes: &mut Scopes,
+    output: &mut String,
+) -> Result<()> {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Value { path, raw } => {
+                let text = match scopes.lookup(path) {
+                    Some(Value::String(text)) => text,
+                    Some(Value::Null) => String::new(),
+                    Some(value) => value.to_string(),
+                    None => return Err(missing(path, at)),
+                };
+                match markdown && !raw {
+                    true => output.push_str(&escape(&text)),
+                    false => output.push_str(&text),
+                }
+            }
+            Node::Each { path, body } => {
+                for (index, item) in scopes.items(path, at)?.iter().enumerate() {
+                    with_item(scopes, item, index, |scopes| {
+                        render_nodes(body, at, markdown, scopes, output)
+                    })?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Calls the function with the item as the innermost scope.
+fn with_item<T>(
+    scopes: &mut Scopes,
+    item: &Value,
+    index: usize,
+    func: impl FnOnce(&mut Scopes) -> Result<T>,
+) -> Result<T> {
+    let mut inner = Scopes {
+        values: scopes.values.clone(),
+        index: Some(index),
+    };
+    inner.values.push(item);
+    func(&mut inner)
+}
+
+/// Parses a string into text and placeholders.
+fn parse(text: &str) -> std::result::Result<Vec<Node>, String> {
+    let mut stack: Vec<(String, Vec<Node>)> = vec![];
+    let mut nodes: Vec<Node> = vec![];
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            nodes.push(Node::Text(rest[..start].to_string()));
+        }
+
+        let raw = rest[start..].starts_with("{{{");
+        let (open, close) = if raw { ("{{{", "}}}") } else { ("{{", "}}") };
+        let inner = &rest[start + open.len()..];
+        let end = match inner.find(close) {
+            Some(end) => end,
+            None => return Err(format!("unclosed '{}'", open)),
+        };
+        let tag = inner[..end].trim();
+        rest = &inner[end + close.len()..];
+
+        if let Some(path) = tag.strip_prefix("#each ") {
+            stack.push((path.trim().to_string(), std::mem::take(&mut nodes)));
+        } else if tag == "/each" {
+            let (path, outer) = match stack.pop() {
+                Some(value) => value,
+                None => return Err("'{{/each}}' without '{{#each}}'".to_string()),
+            };
+            let body = std::mem::replace(&mut nodes, outer);
+            nodes.push(Node::Each { path, body });
+        } else if tag.is_empty() || tag.starts_with(['#', '/']) {
+            return Err(format!("unknown placeholder '{}{}{}'", open, tag, close));
+        } else {
+            nodes.push(Node::Value {
+                path: tag.to_string(),
+                raw,
+            });
+        }
+    }
+
+    if !rest.is_empty() {
+        nodes.push(Node::Text(rest.to_string()));
+    }
+
+    match stack.pop() {
+        Some((path, _)) => Err(format!("'{{{{#each {}}}}}' is not closed", path)),
+        None => Ok(nodes),
+    }
+}
+
+/// Whether the value of a key is displayed as markdown by Discord.
+fn is_markdown(parent: &str, key: &str) -> bool {
+    match key {
+        "content" | "title" | "description" | "value" => true,
+        "name" => parent.ends_with(']') && parent.contains("fields["),
+        _ => false,
+    }
+}
+
+/// Error for a placeholder without a value within the context.
+fn missing(path: &str, at: &str) -> WebhookError {
+    WebhookError::BadParse(format!(
+        "template {}, no value for '{}'",
+        display_path(at),
+        path
+    ))
+}
+
+/// Path of a value for errors, the root is displayed as `.`.
+fn display_path(at: &str) -> &str {
+    match at.is_empty() {
+        true => ".",
+        false => at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Limit;
+    use crate::color::Color;
+    use serde_json::json;
+
+    /// Parses a JSON layout.
+    fn template(layout: &str) -> Template {
+        Template::parse(layout, Format::Json).unwrap()
+    }
+
+    #[test]
+    fn inserts_escaped_and_raw_values() {
+        let template = template(r#"{ "content": "{{service.name}} {{{service.name}}}" }"#);
+        let message = template
+            .render(&json!({ "service": { "name": "*api*" } }))
+            .unwrap();
+        assert_eq!(message.content.as_deref(), Some("\\*api\\* *api*"));
+    }
+
+    #[test]
+    fn repeats_each_blocks() {
+        let template = template(
+            r#"{ "content": "{{hosts.length}}:{{#each hosts}} {{@index}}={{this}}{{/each}}" }"#,
+        );
+        let message = template
+            .render(&json!({ "hosts": ["api-1", "api-2"] }))
+            .unwrap();
+        assert_eq!(message.content.as_deref(), Some("2: 0=api-1 1=api-2"));
+    }
+
+    #[test]
+    fn expands_array_elements_and_keeps_value_types() {
+        let template = template(
+            r#"{
+                "color": "{{color}}",
+                "fields": [
+                    { "name": "Status", "value": "{{status}}" },
+                    { "{{#each failures}}": { "name": "{{check}}", "value": "{{reason}}" } }
+                ]
+            }"#,
+        );
+        let context = json!({
+            "color": 0xED4245,
+            "status": "down",
+            "failures": [
+                { "check": "Latency", "reason": "slow" },
+                { "check": "Errors", "reason": "5xx" },
+            ],
+        });
+        let embed = template.render_embed(&context).unwrap();
+        assert_eq!(embed.color, Some(Color::new(0xED4245)));
+        let fields: Vec<(&str, &str)> = embed
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), field.value.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![("Status", "down"), ("Latency", "slow"), ("Errors", "5xx")]
+        );
+    }
+
+    #[test]
+    fn reports_missing_values() {
+        let template = template(r#"{ "content": "{{service}}" }"#);
+        assert!(template.render(&json!({})).is_err());
+    }
+
+    #[test]
+    fn validates_rendered_messages() {
+        let template = template(r#"{ "content": "{{text}}" }"#);
+        let context = json!({ "text": "x".repeat(Limit::CONTENT + 1) });
+        assert!(template.render(&context).is_err());
+
+        let message = template
+            .truncation(Truncation::Ellipsis)
+            .render(&context)
+            .unwrap();
+        assert_eq!(
+            Limit::length(message.content.as_deref().unwrap()),
+            Limit::CONTENT
+        );
+    }
+}
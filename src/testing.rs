@@ -0,0 +1,864 @@
+//! In-process mock of the Discord webhook API for tests.
+//!
+//! `testing` contains `MockServer`, an HTTP server bound to a local port that implements the
+//! webhook routes used by this crate: get, modify, and delete webhook, execute webhook (with
+//! `wait` and `thread_id`), and get, edit, and delete message. Messages are stored in memory,
+//! payloads are validated against the same limits as `MessageBuilder::validate`, and rate limits
+//! are enforced per webhook. Files uploaded as `multipart/form-data` are stored as attachments.
+//! Responses can be scripted to return errors or 429s, and every request is recorded for
+//! assertions.
+//!
+//! `MockServer::client` returns a `WebhookApi` already pointed at the server, other clients can
+//! be configured with `WebhookApi::base_url` and `MockServer::base_url`.
+
+use crate::attachment::File;
+use crate::client::{Result, WebhookError};
+use crate::component::Component;
+use crate::embed::Embed;
+use crate::message::{Message, MessageBuilder};
+use crate::secret::{redact_url, Token};
+use crate::snowflake::{ChannelId, GuildId, MessageId, Snowflake, WebhookId};
+use crate::timestamp::Timestamp;
+use crate::webhook::{Webhook, WebhookApi};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, HeaderMap, Method, Request, Response, Server, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::oneshot;
+
+/// Token assigned to the webhook created by `MockServer::start`.
+pub const MOCK_TOKEN: &str = "mock-token";
+
+/// Response returned by the mock server in place of handling the next request.
+#[derive(Debug, Clone)]
+pub enum Scripted {
+    /// Discord JSON error with the status, error code, and message provided.
+    Error {
+        /// HTTP status of the response.
+        status: u16,
+        /// JSON error code, such as 10015 for "Unknown Webhook".
+        code: u32,
+        /// Human readable description of the error.
+        message: String,
+    },
+    /// Rate limited (429) response.
+    RateLimited {
+        /// How long the client is told to wait before retrying.
+        retry_after: Duration,
+        /// Whether the rate limit is global.
+        global: bool,
+    },
+    /// Response with the HTTP status provided and an empty body.
+    Status(u16),
+}
+
+/// Request received by the mock server.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// HTTP method of the request.
+    pub method: Method,
+    /// Path of the request with the token masked.
+    pub path: String,
+    /// Query of the request, if any, such as `wait=true&thread_id=444455556666`.
+    pub query: Option<String>,
+    /// Headers of the request.
+    pub headers: HeaderMap,
+    /// Body of the request, the `payload_json` part if files were uploaded.
+    pub body: String,
+    /// Files uploaded with the request.
+    pub files: Vec<File>,
+    /// HTTP status the server responded with.
+    pub status: StatusCode,
+}
+
+impl RecordedRequest {
+    /// Parses the JSON body of the request.
+    pub fn json(&self) -> Result<Value> {
+        serde_json::from_str(&self.body)
+            .map_err(|error| WebhookError::BadParse(format!("recorded body, {}", error)))
+    }
+}
+
+/// Rate limit enforced per webhook.
+#[derive(Debug, Clone, Copy)]
+struct Rule {
+    /// Requests allowed within the window.
+    limit: u32,
+    /// Length of the window.
+    window: Duration,
+}
+
+/// Requests remaining for a webhook within the current window.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Requests remaining.
+    remaining: u32,
+    /// When the window resets.
+    reset: Instant,
+}
+
+/// Message stored by the mock server.
+#[derive(Debug, Clone)]
+struct Stored {
+    /// Webhook that created the message.
+    webhook_id: WebhookId,
+    /// Message as returned by the API.
+    value: Value,
+}
+
+/// Shared state of the mock server.
+#[derive(Debug)]
+struct State {
+    /// Webhooks that exist on the server.
+    webhooks: HashMap<WebhookId, Webhook>,
+    /// Messages created by the webhooks.
+    messages: BTreeMap<MessageId, Stored>,
+    /// Every request received, in order.
+    requests: Vec<RecordedRequest>,
+    /// Responses returned in place of the next requests.
+    script: VecDeque<Scripted>,
+    /// Rate limit enforced per webhook, if any.
+    rule: Option<Rule>,
+    /// Rate limit buckets per webhook.
+    buckets: HashMap<WebhookId, Bucket>,
+    /// Last snowflake generated.
+    last_id: u64,
+}
+
+impl State {
+    /// Generates a new snowflake for the current time, always greater than the last.
+    fn next_id(&mut self) -> Snowflake {
+        let now = Snowflake::from_time(SystemTime::now()).get();
+        self.last_id = now.max(self.last_id + 1);
+        Snowflake::new(self.last_id)
+    }
+}
+
+/// In-process mock of the Discord webhook API, shut down when dropped.
+///
+/// A webhook is created on start, see `MockServer::webhook_id` and `MOCK_TOKEN`. Rate limits
+/// default to Discord's 5 requests per 2 seconds per webhook.
+#[derive(Debug)]
+pub struct MockServer {
+    /// Address the server is bound to.
+    addr: SocketAddr,
+    /// ID of the webhook created on start.
+    webhook_id: WebhookId,
+    /// State shared with the server.
+    state: Arc<Mutex<State>>,
+    /// Signals the server to shut down.
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Starts a new server on a random local port, creating a webhook with the `MOCK_TOKEN`
+    /// token. Must be called within a Tokio runtime.
+    pub async fn start() -> Result<Self> {
+        let error =
+            |error: std::io::Error| WebhookError::Unknown(format!("mock server, {}", error));
+        let listener = TcpListener::bind("127.0.0.1:0").map_err(error)?;
+        listener.set_nonblocking(true).map_err(error)?;
+        let addr = listener.local_addr().map_err(error)?;
+
+        let mut state = State {
+            webhooks: HashMap::new(),
+            messages: BTreeMap::new(),
+            requests: vec![],
+            script: VecDeque::new(),
+            rule: Some(Rule {
+                limit: 5,
+                window: Duration::from_secs(2),
+            }),
+            buckets: HashMap::new(),
+            last_id: 0,
+        };
+
+        let webhook_id = WebhookId::from(state.next_id());
+        let webhook = Webhook {
+            id: webhook_id,
+            r#type: 1,
+            guild_id: Some(GuildId::from(state.next_id())),
+            channel_id: ChannelId::from(state.next_id()),
+            name: "Mock Webhook".to_string(),
+            avatar: None,
+            token: Token::new(MOCK_TOKEN),
+            url: format!(
+                "https://discord.com/api/webhooks/{}/{}",
+                webhook_id, MOCK_TOKEN
+            ),
+        };
+        state.webhooks.insert(webhook_id, webhook);
+
+        let state = Arc::new(Mutex::new(state));
+        let shared = state.clone();
+        let service = make_service_fn(move |_| {
+            let state = shared.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+
+        let server = Server::from_tcp(listener)
+            .map_err(|error| WebhookError::Unknown(format!("mock server, {}", error)))?
+            .serve(service);
+
+        let (shutdown, signal) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            signal.await.ok();
+        }));
+
+        Ok(Self {
+            addr,
+            webhook_id,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// Base URL of the server, used with `WebhookApi::base_url`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}/api/v10", self.addr)
+    }
+
+    /// ID of the webhook created on start, its token is `MOCK_TOKEN`.
+    pub fn webhook_id(&self) -> WebhookId {
+        self.webhook_id
+    }
+
+    /// Channel of the webhook created on start.
+    pub fn channel_id(&self) -> ChannelId {
+        self.lock().webhooks[&self.webhook_id].channel_id
+    }
+
+    /// Creates a client for the webhook created on start, pointed at the server.
+    pub fn client(&self) -> WebhookApi {
+        WebhookApi::new(self.webhook_id, MOCK_TOKEN).base_url(&self.base_url())
+    }
+
+    /// Every request received, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Removes every recorded request.
+    pub fn clear_requests(&self) {
+        self.lock().requests.clear();
+    }
+
+    /// Every message currently stored, ordered by ID.
+    pub fn messages(&self) -> Vec<Message> {
+        self.lock()
+            .messages
+            .values()
+            .filter_map(|stored| serde_json::from_value(stored.value.clone()).ok())
+            .collect()
+    }
+
+    /// Queues a response to be returned in place of handling the next request. Responses are
+    /// returned in the order they were queued.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - Response to return.
+    pub fn script(&self, response: Scripted) {
+        self.lock().script.push_back(response);
+    }
+
+    /// Enforces a rate limit per webhook, replacing the current one.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Requests allowed within the window.
+    /// * `window` - Length of the window.
+    pub fn rate_limit(&self, limit: u32, window: Duration) {
+        let mut state = self.lock();
+        state.rule = Some(Rule { limit, window });
+        state.buckets.clear();
+    }
+
+    /// Disables rate limits.
+    pub fn without_rate_limit(&self) {
+        let mut state = self.lock();
+        state.rule = None;
+        state.buckets.clear();
+    }
+
+    /// Locks the shared state, ignoring poisoning from a panicked test.
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+/// Payload of an execute or edit request.
+#[derive(Deserialize)]
+struct Payload {
+    username: Option<String>,
+    content: Option<String>,
+    tts: Option<bool>,
+    embeds: Option<Vec<Embed>>,
+    components: Option<Vec<Component>>,
+    attachments: Option<Vec<Value>>,
+}
+
+/// Response created by a route.
+struct Reply {
+    status: StatusCode,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl Reply {
+    /// Successful response with a JSON body.
+    fn json(value: &Value) -> Self {
+        Self {
+            status: StatusCode::OK,
+            headers: vec![],
+            body: value.to_string(),
+        }
+    }
+
+    /// Response with the status and an empty body.
+    fn status(status: StatusCode) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: String::new(),
+        }
+    }
+
+    /// Discord JSON error.
+    fn error(status: u16, code: u32, message: &str) -> Self {
+        Self {
+            status: StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            headers: vec![],
+            body: json!({ "code": code, "message": message }).to_string(),
+        }
+    }
+
+    /// Rate limited (429) response.
+    fn rate_limited(retry_after: Duration, global: bool) -> Self {
+        let mut headers = vec![
+            ("Retry-After", retry_after.as_secs_f64().ceil().to_string()),
+            ("X-RateLimit-Scope", "user".to_string()),
+        ];
+        if global {
+            headers.push(("X-RateLimit-Global", "true".to_string()));
+        }
+
+        Self {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            headers,
+            body: json!({
+                "message": "You are being rate limited.",
+                "retry_after": retry_after.as_secs_f64(),
+                "global": global,
+            })
+            .to_string(),
+        }
+    }
+}
+
+/// Handles a single request, recording it along with the response status.
+async fn handle(
+    state: Arc<Mutex<State>>,
+    req: Request<Body>,
+) -> std::result::Result<Response<Body>, Infallible> {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let query = req.uri().query().map(|query| query.to_string());
+    let headers = req.headers().clone();
+    let bytes = hyper::body::to_bytes(req.into_body())
+        .await
+        .unwrap_or_default();
+
+    // Multipart bodies carry the JSON payload and files as separate parts.
+    let boundary = headers
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("multipart/form-data; boundary="))
+        .map(|boundary| boundary.to_string());
+    let (body, files) = match boundary {
+        Some(boundary) => multipart(&bytes, &boundary),
+        None => (String::from_utf8_lossy(&bytes).into_owned(), vec![]),
+    };
+
+    let mut state = state.lock().unwrap_or_else(|error| error.into_inner());
+    let reply = route(&mut state, &method, &path, query.as_deref(), &body, &files);
+
+    state.requests.push(RecordedRequest {
+        method,
+        path: redact_url(&path),
+        query,
+        headers,
+        body,
+        files,
+        status: reply.status,
+    });
+
+    let mut response = Response::builder()
+        .status(reply.status)
+        .header("Content-Type", "application/json");
+    for (name, value) in reply.headers {
+        response = response.header(name, value);
+    }
+
+    Ok(response
+        .body(Body::from(reply.body))
+        .unwrap_or_else(|_| Response::new(Body::empty())))
+}
+
+/// Splits a `multipart/form-data` body into the `payload_json` part and uploaded files.
+fn multipart(body: &[u8], boundary: &str) -> (String, Vec<File>) {
+    let delimiter = format!("--{}", boundary);
+    let mut payload = String::new();
+    let mut files: Vec<File> = vec![];
+
+    for part in split(body, delimiter.as_bytes()) {
+        // Each part is headers, a blank line, then the contents followed by CRLF.
+        let part = part.strip_prefix(b"\r\n").unwrap_or(part);
+        let (head, data) = match position(part, b"\r\n\r\n") {
+            Some(index) => (&part[..index], &part[index + 4..]),
+            None => continue,
+        };
+        let data = data.strip_suffix(b"\r\n").unwrap_or(data);
+        let head = String::from_utf8_lossy(head);
+
+        let param = |name: &str| {
+            let pattern = format!("{}=\"", name);
+            head.split_once(&pattern)
+                .and_then(|(_, rest)| rest.split_once('"'))
+                .map(|(value, _)| value.to_string())
+        };
+        let content_type = head
+            .lines()
+            .find_map(|line| line.strip_prefix("Content-Type: "))
+            .unwrap_or("application/octet-stream");
+
+        match (param("name").as_deref(), param("filename")) {
+            (Some("payload_json"), _) => payload = String::from_utf8_lossy(data).into_owned(),
+            (_, Some(filename)) => files.push(File {
+                filename,
+                content_type: content_type.to_string(),
+                description: None,
+                data: data.to_vec(),
+            }),
+            _ => (),
+        }
+    }
+
+    (payload, files)
+}
+
+/// Splits bytes on every occurrence of the delimiter, dropping the preamble before the first.
+fn split<'a>(mut bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut parts = vec![];
+    if let Some(index) = position(bytes, delimiter) {
+        bytes = &bytes[index + delimiter.len()..];
+    }
+    while let Some(index) = position(bytes, delimiter) {
+        parts.push(&bytes[..index]);
+        bytes = &bytes[index + delimiter.len()..];
+    }
+    parts
+}
+
+/// Finds the first position of the needle within the bytes.
+fn position(bytes: &[u8], needle: &[u8]) -> Option<usize> {
+    bytes
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Routes a request, applying scripted responses and rate limits first.
+fn route(
+    state: &mut State,
+    method: &Method,
+    path: &str,
+    query: Option<&str>,
+    body: &str,
+    files: &[File],
+) -> Reply {
+    if let Some(scripted) = state.script.pop_front() {
+        return match scripted {
+            Scripted::Error {
+                status,
+                code,
+                message,
+            } => Reply::error(status, code, &message),
+            Scripted::RateLimited {
+                retry_after,
+                global,
+            } => Reply::rate_limited(retry_after, global),
+            Scripted::Status(status) => Reply::status(
+                StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            ),
+        };
+    }
+
+    // Path is /api[/vN]/webhooks/{id}/{token}[/messages/{message.id}]
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let segments = match segments.as_slice() {
+        ["api", version, rest @ ..] if version.starts_with('v') => rest,
+        ["api", rest @ ..] => rest,
+        _ => return Reply::error(404, 0, "404: Not Found"),
+    };
+
+    let (id, token, message) = match segments {
+        ["webhooks", id, token] => (id, token, None),
+        ["webhooks", id, token, "messages", message] => (id, token, Some(message)),
+        _ => return Reply::error(404, 0, "404: Not Found"),
+    };
+
+    // Verify the webhook exists and the token matches.
+    let id: WebhookId = match id.parse() {
+        Ok(id) => id,
+        Err(_) => return Reply::error(404, 10015, "Unknown Webhook"),
+    };
+    match state.webhooks.get(&id) {
+        None => return Reply::error(404, 10015, "Unknown Webhook"),
+        Some(webhook) if webhook.token.expose() != *token => {
+            return Reply::error(401, 50027, "Invalid Webhook Token")
+        }
+        Some(_) => (),
+    }
+
+    // Consume from the rate limit bucket of the webhook.
+    let mut headers = vec![];
+    if let Some(rule) = state.rule {
+        let now = Instant::now();
+        let bucket = state.buckets.entry(id).or_insert(Bucket {
+            remaining: rule.limit,
+            reset: now + rule.window,
+        });
+        if now >= bucket.reset {
+            bucket.remaining = rule.limit;
+            bucket.reset = now + rule.window;
+        }
+
+        let reset_after = bucket.reset - now;
+        if bucket.remaining == 0 {
+            let mut reply = Reply::rate_limited(reset_after, false);
+            reply
+                .headers
+                .extend(bucket_headers(id, rule, 0, reset_after));
+            return reply;
+        }
+
+        bucket.remaining -= 1;
+        headers = bucket_headers(id, rule, bucket.remaining, reset_after);
+    }
+
+    let params = parse_query(query);
+    let thread_id = match params.get("thread_id").map(|value| value.parse()) {
+        Some(Ok(thread)) => Some(thread),
+        Some(Err(_)) => return Reply::error(400, 50035, "Invalid Form Body"),
+        None => None,
+    };
+
+    let mut reply = match (method, message) {
+        (&Method::GET, None) => Reply::json(&webhook_json(&state.webhooks[&id])),
+        (&Method::PATCH, None) => modify_webhook(state, id, body),
+        (&Method::DELETE, None) => {
+            state.webhooks.remove(&id);
+            state.messages.retain(|_, stored| stored.webhook_id != id);
+            Reply::status(StatusCode::NO_CONTENT)
+        }
+        (&Method::POST, None) => {
+            let wait = params.get("wait").is_some_and(|value| value == "true");
+            execute(state, id, thread_id, wait, body, files)
+        }
+        (method, Some(message)) => match message.parse::<MessageId>() {
+            Ok(message) => match *method {
+                Method::GET => get_message(state, id, message, thread_id),
+                Method::PATCH => edit_message(state, id, message, thread_id, body, files),
+                Method::DELETE => delete_message(state, id, message, thread_id),
+                _ => Reply::error(405, 0, "405: Method Not Allowed"),
+            },
+            Err(_) => Reply::error(404, 10008, "Unknown Message"),
+        },
+        _ => Reply::error(405, 0, "405: Method Not Allowed"),
+    };
+
+    reply.headers.extend(headers);
+    reply
+}
+
+/// Rate limit headers for a webhook.
+fn bucket_headers(
+    id: WebhookId,
+    rule: Rule,
+    remaining: u32,
+    reset_after: Duration,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("X-RateLimit-Bucket", format!("webhook-{}", id)),
+        ("X-RateLimit-Limit", rule.limit.to_string()),
+        ("X-RateLimit-Remaining", remaining.to_string()),
+        (
+            "X-RateLimit-Reset-After",
+            format!("{:.3}", reset_after.as_secs_f64()),
+        ),
+    ]
+}
+
+/// Parses the parameters of a query.
+fn parse_query(query: Option<&str>) -> HashMap<String, String> {
+    query
+        .unwrap_or_default()
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Converts a webhook into the JSON returned by the API.
+fn webhook_json(webhook: &Webhook) -> Value {
+    serde_json::to_value(webhook).unwrap_or_default()
+}
+
+/// Changes the name and avatar of a webhook.
+fn modify_webhook(state: &mut State, id: WebhookId, body: &str) -> Reply {
+    let value: Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        Err(_) => return Reply::error(400, 50109, "The request body contains invalid JSON."),
+    };
+
+    let webhook = match state.webhooks.get_mut(&id) {
+        Some(webhook) => webhook,
+        None => return Reply::error(404, 10015, "Unknown Webhook"),
+    };
+
+    if let Some(name) = value.get("name").and_then(Value::as_str) {
+        if name.is_empty() || name.chars().count() > 80 {
+            return Reply::error(400, 50035, "Invalid Form Body");
+        }
+        webhook.name = name.to_string();
+    }
+    if let Some(avatar) = value.get("avatar") {
+        webhook.avatar = avatar.as_str().map(|avatar| avatar.to_string());
+    }
+
+    Reply::json(&webhook_json(webhook))
+}
+
+/// Parses and validates a message payload, the same way as `MessageBuilder::validate`.
+fn payload(body: &str, files: &[File]) -> std::result::Result<Payload, Reply> {
+    let payload: Payload = match serde_json::from_str(body) {
+        Ok(payload) => payload,
+        Err(_) => {
+            return Err(Reply::error(
+                400,
+                50109,
+                "The request body contains invalid JSON.",
+            ))
+        }
+    };
+
+    let builder = MessageBuilder {
+        username: payload.username.clone(),
+        content: payload.content.clone(),
+        tts: payload.tts,
+        embeds: payload.embeds.clone().unwrap_or_default(),
+        components: payload.components.clone().unwrap_or_default(),
+        files: files.to_vec(),
+        ..Default::default()
+    };
+
+    match builder.validate() {
+        Ok(_) => Ok(payload),
+        Err(error) => Err(Reply::error(
+            400,
+            50035,
+            &format!("Invalid Form Body, {}", error),
+        )),
+    }
+}
+
+/// Executes a webhook, creating a message.
+fn execute(
+    state: &mut State,
+    id: WebhookId,
+    thread_id: Option<ChannelId>,
+    wait: bool,
+    body: &str,
+    files: &[File],
+) -> Reply {
+    let payload = match payload(body, files) {
+        Ok(payload) => payload,
+        Err(reply) => return reply,
+    };
+
+    let content = payload.content.unwrap_or_default();
+    let embeds = payload.embeds.unwrap_or_default();
+    if content.is_empty() && embeds.is_empty() && files.is_empty() {
+        return Reply::error(400, 50006, "Cannot send an empty message");
+    }
+
+    let channel_id = thread_id.unwrap_or(state.webhooks[&id].channel_id);
+    let message_id = MessageId::from(state.next_id());
+    let attachments = attachments(state, channel_id, &payload.attachments, files);
+    let value = json!({
+        "id": message_id,
+        "channel_id": channel_id,
+        "content": content,
+        "timestamp": Timestamp::now(),
+        "edited_timestamp": null,
+        "tts": payload.tts.unwrap_or_default(),
+        "mention_everyone": content.contains("@everyone"),
+        "embeds": embeds,
+        "components": payload.components.unwrap_or_default(),
+        "attachments": attachments,
+        "pinned": false,
+        "webhook_id": id,
+        "type": 0,
+    });
+
+    state.messages.insert(
+        message_id,
+        Stored {
+            webhook_id: id,
+            value: value.clone(),
+        },
+    );
+
+    match wait {
+        true => Reply::json(&value),
+        false => Reply::status(StatusCode::NO_CONTENT),
+    }
+}
+
+/// Creates attachments for uploaded files, taking descriptions from the `attachments` payload.
+fn attachments(
+    state: &mut State,
+    channel_id: ChannelId,
+    payload: &Option<Vec<Value>>,
+    files: &[File],
+) -> Vec<Value> {
+    files
+        .iter()
+        .enumerate()
+        .map(|(index, file)| {
+            let description = payload
+                .iter()
+                .flatten()
+                .find(|attachment| attachment["id"] == json!(index))
+                .map(|attachment| attachment["description"].clone())
+                .unwrap_or(Value::Null);
+            let id = state.next_id();
+            let url = format!(
+                "https://cdn.discordapp.com/attachments/{}/{}/{}",
+                channel_id, id, file.filename
+            );
+            json!({
+                "id": id,
+                "filename": file.filename,
+                "description": description,
+                "content_type": file.content_type,
+                "size": file.data.len(),
+                "url": url,
+                "proxy_url": url.replace("cdn.discordapp.com", "media.discordapp.net"),
+            })
+        })
+        .collect()
+}
+
+/// Finds a message created by the webhook within the channel or thread.
+fn find(
+    state: &mut State,
+    id: WebhookId,
+    message: MessageId,
+    thread_id: Option<ChannelId>,
+) -> Option<&mut Stored> {
+    let channel_id = thread_id.unwrap_or(state.webhooks[&id].channel_id);
+    state
+        .messages
+        .get_mut(&message)
+        .filter(|stored| stored.webhook_id == id && stored.value["channel_id"] == json!(channel_id))
+}
+
+/// Obtains a message.
+fn get_message(
+    state: &mut State,
+    id: WebhookId,
+    message: MessageId,
+    thread_id: Option<ChannelId>,
+) -> Reply {
+    match find(state, id, message, thread_id) {
+        Some(stored) => Reply::json(&stored.value),
+        None => Reply::error(404, 10008, "Unknown Message"),
+    }
+}
+
+/// Edits a message, replacing only the values provided.
+fn edit_message(
+    state: &mut State,
+    id: WebhookId,
+    message: MessageId,
+    thread_id: Option<ChannelId>,
+    body: &str,
+    files: &[File],
+) -> Reply {
+    let payload = match payload(body, files) {
+        Ok(payload) => payload,
+        Err(reply) => return reply,
+    };
+
+    let channel_id = match find(state, id, message, thread_id) {
+        Some(stored) => serde_json::from_value(stored.value["channel_id"].clone()).ok(),
+        None => return Reply::error(404, 10008, "Unknown Message"),
+    };
+    let uploaded = match channel_id {
+        Some(channel_id) if !files.is_empty() => {
+            Some(attachments(state, channel_id, &payload.attachments, files))
+        }
+        _ => None,
+    };
+    let stored = match find(state, id, message, thread_id) {
+        Some(stored) => stored,
+        None => return Reply::error(404, 10008, "Unknown Message"),
+    };
+
+    if let Some(content) = payload.content {
+        stored.value["mention_everyone"] = json!(content.contains("@everyone"));
+        stored.value["content"] = json!(content);
+    }
+    if let Some(embeds) = payload.embeds {
+        stored.value["embeds"] = json!(embeds);
+    }
+    if let Some(components) = payload.components {
+        stored.value["components"] = json!(components);
+    }
+    if let Some(uploaded) = uploaded {
+        stored.value["attachments"] = json!(uploaded);
+    }
+    stored.value["edited_timestamp"] = json!(Timestamp::now());
+
+    Reply::json(&stored.value)
+}
+
+/// Deletes a message.
+fn delete_message(
+    state: &mut State,
+    id: WebhookId,
+    message: MessageId,
+    thread_id: Option<ChannelId>,
+) -> Reply {
+    match find(state, id, message, thread_id) {
+        Some(_) => {
+            state.messages.remove(&message);
+            Reply::status(StatusCode::NO_CONTENT)
+        }
+        None => Reply::error(404, 10008, "Unknown Message"),
+    }
+}
@@ -0,0 +1,311 @@
+//! ISO8601 timestamps used by embeds and messages.
+//!
+//! `timestamp` contains the `Timestamp` type used for `Embed::timestamp`, `Message::timestamp`,
+//! and `Message::edited_timestamp`. It converts to and from `SystemTime`, as well as `chrono` and
+//! `time` types when their respective features are enabled, and is serialized as the ISO8601
+//! string expected by the Discord API.
+
+use crate::client::{Result, WebhookError};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Seconds within a single day.
+const DAY: i64 = 86_400;
+
+/// Point in time, serialized as an ISO8601 string such as `2023-06-01T12:34:56.789Z`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(SystemTime);
+
+impl Timestamp {
+    /// Creates a timestamp for the current time.
+    pub fn now() -> Self {
+        Self(SystemTime::now())
+    }
+
+    /// Creates a timestamp from milliseconds since the Unix epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `millis` - Milliseconds since the Unix epoch, negative values are before it.
+    pub fn from_unix_millis(millis: i64) -> Self {
+        let offset = Duration::from_millis(millis.unsigned_abs());
+        match millis < 0 {
+            true => Self(UNIX_EPOCH - offset),
+            false => Self(UNIX_EPOCH + offset),
+        }
+    }
+
+    /// Milliseconds since the Unix epoch, negative values are before it.
+    pub fn unix_millis(&self) -> i64 {
+        match self.0.duration_since(UNIX_EPOCH) {
+            Ok(value) => value.as_millis() as i64,
+            Err(error) => -(error.duration().as_millis() as i64),
+        }
+    }
+
+    /// Time represented by the timestamp.
+    pub fn time(&self) -> SystemTime {
+        self.0
+    }
+
+    /// Formats the timestamp as ISO8601 in UTC with millisecond precision, such as
+    /// `2023-06-01T12:34:56.789Z`.
+    pub fn to_iso8601(&self) -> String {
+        let millis = self.unix_millis();
+        let secs = millis.div_euclid(1000);
+        let (year, month, day) = civil_from_days(secs.div_euclid(DAY));
+        let time = secs.rem_euclid(DAY);
+
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year,
+            month,
+            day,
+            time / 3600,
+            time % 3600 / 60,
+            time % 60,
+            millis.rem_euclid(1000)
+        )
+    }
+
+    /// Parses an ISO8601 timestamp, such as `2023-06-01T12:34:56.789000+00:00` as returned by
+    /// the Discord API. The fractional seconds and offset are optional, UTC is assumed if the
+    /// offset is missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - ISO8601 timestamp to parse.
+    pub fn parse(value: &str) -> Result<Self> {
+        match parse_iso8601(value.trim()) {
+            Some(millis) => Ok(Self::from_unix_millis(millis)),
+            None => Err(WebhookError::BadParse(format!("timestamp '{}'", value))),
+        }
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    fn from(value: SystemTime) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Timestamp> for SystemTime {
+    fn from(value: Timestamp) -> Self {
+        value.0
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for Timestamp {
+    fn from(value: chrono::DateTime<Tz>) -> Self {
+        Self::from_unix_millis(value.timestamp_millis())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for chrono::DateTime<chrono::Utc> {
+    fn from(value: Timestamp) -> Self {
+        value.0.into()
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for Timestamp {
+    fn from(value: time::OffsetDateTime) -> Self {
+        Self(value.into())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<Timestamp> for time::OffsetDateTime {
+    fn from(value: Timestamp) -> Self {
+        value.0.into()
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = WebhookError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::parse(value)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_iso8601())
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_iso8601())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parses an ISO8601 timestamp into milliseconds since the Unix epoch.
+fn parse_iso8601(value: &str) -> Option<i64> {
+    let number = |text: &str| -> Option<i64> {
+        match text.bytes().all(|b| b.is_ascii_digit()) && !text.is_empty() {
+            true => text.parse().ok(),
+            false => None,
+        }
+    };
+
+    // Date portion: YYYY-MM-DD
+    let (date, rest) = value.split_at_checked(10)?;
+    let (year, month, day) = match date.as_bytes() {
+        [_, _, _, _, b'-', _, _, b'-', _, _] => (
+            number(&date[..4])?,
+            number(&date[5..7])?,
+            number(&date[8..])?,
+        ),
+        _ => return None,
+    };
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    // Time portion: THH:MM:SS
+    let rest = rest.strip_prefix(['T', 't', ' '])?;
+    let (time, rest) = rest.split_at_checked(8)?;
+    let (hour, minute, second) = match time.as_bytes() {
+        [_, _, b':', _, _, b':', _, _] => (
+            number(&time[..2])?,
+            number(&time[3..5])?,
+            number(&time[6..])?,
+        ),
+        _ => return None,
+    };
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Fractional seconds, only millisecond precision is kept.
+    let (millis, rest) = match rest.strip_prefix(['.', ',']) {
+        Some(rest) => {
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let digits = &rest[..end];
+            let padded = format!("{:0<3}", &digits[..digits.len().min(3)]);
+            (number(&padded)?, &rest[end..])
+        }
+        None => (0, rest),
+    };
+
+    // Offset from UTC: Z, +HH:MM, +HHMM, or missing.
+    let offset = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+
+            let digits = rest[1..].replace(':', "");
+            if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+
+            let hours = number(&digits[..2])?;
+            let minutes = number(&digits[2..])?;
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    let secs = days_from_civil(year, month, day) * DAY + hour * 3600 + minute * 60 + second;
+    Some((secs - offset) * 1000 + millis)
+}
+
+/// Amount of days within the month of the year provided.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Converts a civil date into days since the Unix epoch.
+///
+/// <https://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Converts days since the Unix epoch into a civil date.
+///
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_discord_timestamps() {
+        let time: Timestamp = "2023-06-01T12:34:56.789000+00:00".parse().unwrap();
+        assert_eq!(time.to_iso8601(), "2023-06-01T12:34:56.789Z");
+    }
+
+    #[test]
+    fn applies_offsets() {
+        let utc = Timestamp::parse("2023-06-01T12:00:00Z").unwrap();
+        assert_eq!(Timestamp::parse("2023-06-01T14:00:00+02:00").unwrap(), utc);
+        assert_eq!(Timestamp::parse("2023-06-01T07:30:00-0430").unwrap(), utc);
+        assert_eq!(Timestamp::parse("2023-06-01T12:00:00").unwrap(), utc);
+    }
+
+    #[test]
+    fn rejects_invalid_timestamps() {
+        for value in [
+            "",
+            "2023-06-01",
+            "2023-13-01T00:00:00Z",
+            "2023-02-29T00:00:00Z",
+            "2023-06-01T24:00:00Z",
+            "2023-06-01T12:00:00Q",
+        ] {
+            assert!(Timestamp::parse(value).is_err(), "accepted '{}'", value);
+        }
+    }
+
+    #[test]
+    fn round_trips_unix_millis() {
+        let time = Timestamp::from_unix_millis(1_700_000_000_123);
+        assert_eq!(time.unix_millis(), 1_700_000_000_123);
+        assert_eq!(Timestamp::parse(&time.to_iso8601()).unwrap(), time);
+    }
+}
@@ -0,0 +1,182 @@
+//! Transports used to send requests to the Discord API.
+//!
+//! `transport` contains the `Transport` trait that performs the actual exchange of a request for a
+//! response, after middleware has been applied. `HyperTransport` sends requests over HTTPS and is
+//! used by default, other transports such as `Cassette` can be assigned with
+//! `WebhookApi::transport` to avoid the network entirely.
+
+use crate::attachment::File;
+use crate::client::{Result, WebhookError};
+use crate::middleware::{Request, Response};
+use crate::secret::redact_url;
+use futures::future::BoxFuture;
+use hyper::body::Buf;
+use hyper::client::{Client as HyperClient, HttpConnector};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request as HttpRequest};
+use hyper_tls::HttpsConnector;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Exchanges a request for a response.
+pub trait Transport: Send + Sync {
+    /// Sends the request, obtaining the status, headers, and body of the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Request to send, after middleware has been applied.
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// Transport that sends requests over HTTP and HTTPS using Hyper, used by default.
+#[derive(Debug, Clone)]
+pub struct HyperTransport {
+    /// HTTP client used to send requests to the API.
+    client: HyperClient<HttpsConnector<HttpConnector>>,
+}
+
+impl HyperTransport {
+    /// Creates a new transport with its own connection pool.
+    pub fn new() -> Self {
+        let connector = HttpsConnector::new();
+        Self {
+            client: HyperClient::builder().build::<_, Body>(connector),
+        }
+    }
+
+    /// Sends the request over HTTP, obtaining the status, headers, and body.
+    async fn transmit(&self, request: &Request) -> Result<Response> {
+        let url = request.url();
+
+        // Build the request for the Method.
+        let mut req = HttpRequest::builder()
+            .method(request.method.clone())
+            .uri(url);
+        if let Some(headers) = req.headers_mut() {
+            headers.extend(request.headers.clone());
+        }
+
+        // Files are uploaded alongside the JSON body as multipart/form-data.
+        let body = match request.files.is_empty() {
+            true => request.body.clone().into_bytes(),
+            false => {
+                let boundary = boundary();
+                if let Some(headers) = req.headers_mut() {
+                    let value = format!("multipart/form-data; boundary={}", boundary);
+                    if let Ok(value) = HeaderValue::from_str(&value) {
+                        headers.insert(CONTENT_TYPE, value);
+                    }
+                }
+                multipart(&boundary, &request.body, &request.files)
+            }
+        };
+
+        let req = match req.body(Body::from(body)) {
+            Ok(value) => value,
+            Err(error) => {
+                let url = request.redacted_url();
+                let error = redact_url(&error.to_string());
+                return Err(WebhookError::Unknown(format!(
+                    "building request to {}, {}",
+                    url, error
+                )));
+            }
+        };
+
+        // Send the request, obtaining the status, headers, and body.
+        let value = match self.client.request(req).await {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(WebhookError::Transport(format!(
+                    "request to {}, {}",
+                    request.redacted_url(),
+                    redact_url(&error.to_string())
+                )))
+            }
+        };
+
+        let status = value.status();
+        let headers = value.headers().clone();
+
+        // Convert the HTTP body stream to a &[u8]
+        let body = match hyper::body::to_bytes(value).await {
+            Ok(data) => data,
+            Err(_) => {
+                return Err(WebhookError::Transport(
+                    "unable to convert http body".to_string(),
+                ))
+            }
+        };
+
+        // Convert to a string to be parsed by calling function.
+        let body = match std::str::from_utf8(body.chunk()) {
+            Ok(data) => data.to_owned(),
+            Err(_) => {
+                return Err(WebhookError::Transport(
+                    "unable to convert to json".to_string(),
+                ))
+            }
+        };
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for HyperTransport {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(self.transmit(request))
+    }
+}
+
+/// Creates a boundary separating the parts of a multipart body.
+fn boundary() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    format!("yadwh-{:x}", nanos)
+}
+
+/// Encodes the JSON body and files as `multipart/form-data`, the body is sent as the
+/// `payload_json` part and each file as `files[n]`.
+///
+/// # Arguments
+///
+/// * `boundary` - Boundary separating the parts.
+/// * `body` - JSON body of the request.
+/// * `files` - Files to upload.
+fn multipart(boundary: &str, body: &str, files: &[File]) -> Vec<u8> {
+    let mut data: Vec<u8> = vec![];
+    data.extend(format!("--{}\r\n", boundary).as_bytes());
+    data.extend(b"Content-Disposition: form-data; name=\"payload_json\"\r\n");
+    data.extend(b"Content-Type: application/json\r\n\r\n");
+    data.extend(body.as_bytes());
+    data.extend(b"\r\n");
+
+    for (index, file) in files.iter().enumerate() {
+        let filename = file.filename.replace(['"', '\r', '\n'], "_");
+        data.extend(format!("--{}\r\n", boundary).as_bytes());
+        data.extend(
+            format!(
+                "Content-Disposition: form-data; name=\"files[{}]\"; filename=\"{}\"\r\n",
+                index, filename
+            )
+            .as_bytes(),
+        );
+        data.extend(format!("Content-Type: {}\r\n\r\n", file.content_type).as_bytes());
+        data.extend(&file.data);
+        data.extend(b"\r\n");
+    }
+
+    data.extend(format!("--{}--\r\n", boundary).as_bytes());
+    data
+}
@@ -0,0 +1,163 @@
+//! Opt-in truncation of values that exceed the limits enforced by the Discord API.
+//!
+//! `truncate` contains the `Truncation` policy that can be assigned to a `MessageBuilder` or an
+//! `Embed`. When enabled, values are clipped to their `Limit` at character boundaries instead of
+//! failing validation, and every value that was cut is reported with a `Truncated`.
+
+use crate::client::Limit;
+use std::fmt;
+
+/// Ellipsis appended to clipped values when using `Truncation::Ellipsis`.
+const ELLIPSIS: char = '…';
+
+/// Policy used when a value exceeds its limit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Truncation {
+    /// Fail validation, this is the default.
+    #[default]
+    Error,
+    /// Clip the value and append an ellipsis (…) to indicate it was cut.
+    Ellipsis,
+    /// Clip the value without any indication.
+    Silent,
+}
+
+/// Value that was cut in order to fit within its limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Truncated {
+    /// Path to the value that was cut, such as `embeds[0].fields[3].value`.
+    pub path: String,
+    /// Size of the value before it was cut, in characters.
+    pub size: usize,
+    /// Amount of characters kept, 0 if the value was dropped entirely.
+    pub kept: usize,
+}
+
+impl fmt::Display for Truncated {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kept {
+            0 => write!(f, "{} dropped, {} characters", self.path, self.size),
+            _ => write!(
+                f,
+                "{} truncated, {} of {} kept",
+                self.path, self.kept, self.size
+            ),
+        }
+    }
+}
+
+/// Logs every value that was cut as a `tracing` warning, does nothing without the `tracing`
+/// feature.
+///
+/// # Arguments
+///
+/// * `truncated` - Values that were cut.
+pub(crate) fn report(truncated: &[Truncated]) {
+    #[cfg(feature = "tracing")]
+    for value in truncated.iter() {
+        tracing::warn!(
+            path = %value.path,
+            size = value.size,
+            kept = value.kept,
+            "value truncated"
+        );
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = truncated;
+}
+
+/// Clips `text` to `max` characters according to the policy. Records what was cut, if anything.
+///
+/// # Arguments
+///
+/// * `path` - Path to the value being clipped.
+/// * `text` - Value to clip.
+/// * `max` - Maximum amount of characters to keep.
+/// * `policy` - Truncation policy to apply, `Truncation::Error` leaves the value untouched.
+/// * `truncated` - Collection that cut values are added to.
+pub(crate) fn clip(
+    path: &str,
+    text: &mut String,
+    max: usize,
+    policy: Truncation,
+    truncated: &mut Vec<Truncated>,
+) {
+    let size = Limit::length(text);
+    if size <= max || policy == Truncation::Error {
+        return;
+    }
+
+    // Leave room for the ellipsis if one is being added.
+    let keep = match policy {
+        Truncation::Ellipsis => max.saturating_sub(1),
+        _ => max,
+    };
+
+    let index = text.char_indices().nth(keep).map_or(text.len(), |(i, _)| i);
+    text.truncate(index);
+    if policy == Truncation::Ellipsis && max > 0 {
+        text.push(ELLIPSIS);
+    }
+
+    truncated.push(Truncated {
+        path: path.to_string(),
+        size,
+        kept: Limit::length(text),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clips_with_an_ellipsis() {
+        let mut text = "hello world".to_string();
+        let mut truncated = vec![];
+        clip(
+            "content",
+            &mut text,
+            5,
+            Truncation::Ellipsis,
+            &mut truncated,
+        );
+        assert_eq!(text, "hell…");
+        assert_eq!(
+            truncated,
+            vec![Truncated {
+                path: "content".to_string(),
+                size: 11,
+                kept: 5,
+            }]
+        );
+    }
+
+    #[test]
+    fn clips_silently_at_character_boundaries() {
+        let mut text = "ééééé".to_string();
+        let mut truncated = vec![];
+        clip("content", &mut text, 3, Truncation::Silent, &mut truncated);
+        assert_eq!(text, "ééé");
+        assert_eq!(truncated[0].kept, 3);
+    }
+
+    #[test]
+    fn leaves_values_within_the_limit_or_without_a_policy() {
+        let mut truncated = vec![];
+        let mut text = "hello".to_string();
+        clip(
+            "content",
+            &mut text,
+            5,
+            Truncation::Ellipsis,
+            &mut truncated,
+        );
+        assert_eq!(text, "hello");
+
+        let mut text = "hello world".to_string();
+        clip("content", &mut text, 5, Truncation::Error, &mut truncated);
+        assert_eq!(text, "hello world");
+        assert!(truncated.is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+//! Parsing and validation of Discord webhook URLs.
+//!
+//! `url` contains the `WebhookUrl` type that is used to parse the URL provided by Discord when a
+//! webhook is created. It accepts the `ptb.` and `canary.` hosts, the legacy `discordapp.com`
+//! domain, an explicit API version such as `/api/v10/`, a trailing slash, and a `?thread_id=`
+//! query. It always displays as the canonical URL.
+
+use crate::client::{Result, WebhookError};
+use crate::secret::{redact_url, Token};
+use crate::snowflake::{ChannelId, WebhookId};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Hosts that webhook URLs are accepted from.
+const HOSTS: [&str; 6] = [
+    "discord.com",
+    "ptb.discord.com",
+    "canary.discord.com",
+    "discordapp.com",
+    "ptb.discordapp.com",
+    "canary.discordapp.com",
+];
+
+/// Parsed and validated webhook URL. `Display` produces the canonical URL including the token,
+/// use `WebhookUrl::redacted` for logging.
+///
+/// Example:
+#[allow(rustdoc::bare_urls)]
+/// URL Supplied: https://canary.discord.com/api/v10/webhooks/111122223333/AAAABBBBCCCC/?thread_id=444455556666
+/// * Webhook ID: 111122223333
+/// * Webhook Token: AAAABBBBCCCC
+/// * Thread ID: 444455556666
+/// * Canonical: https://discord.com/api/webhooks/111122223333/AAAABBBBCCCC?thread_id=444455556666
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WebhookUrl {
+    /// ID of the webhook.
+    id: WebhookId,
+    /// Token of the webhook, redacted when debugging.
+    token: Token,
+    /// Thread that messages are sent to, if any.
+    thread_id: Option<ChannelId>,
+}
+
+impl WebhookUrl {
+    /// Creates a new webhook URL from its parts, validating the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - ID of the webhook.
+    /// * `token` - Token of the webhook, only ASCII letters, digits, '-', and '_' are allowed.
+    pub fn new(id: WebhookId, token: &str) -> Result<Self> {
+        let valid = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+        if token.is_empty() || !token.chars().all(valid) {
+            return Err(WebhookError::BadParse("webhook token".to_string()));
+        }
+
+        Ok(Self {
+            id,
+            token: Token::new(token),
+            thread_id: None,
+        })
+    }
+
+    /// Assigns the thread that messages are sent to.
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_id` - Thread within a Forum Channel or text channel.
+    pub fn with_thread(mut self, thread_id: Option<ChannelId>) -> Self {
+        self.thread_id = thread_id;
+        self
+    }
+
+    /// ID of the webhook.
+    pub fn id(&self) -> WebhookId {
+        self.id
+    }
+
+    /// Token of the webhook.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Canonical URL with the token masked, safe to be used within errors and logs.
+    pub fn redacted(&self) -> String {
+        redact_url(&self.to_string())
+    }
+
+    /// Thread that messages are sent to, if any.
+    pub fn thread_id(&self) -> Option<ChannelId> {
+        self.thread_id
+    }
+
+    /// Parses and validates a webhook URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full URL of the webhook.
+    pub fn parse(url: &str) -> Result<Self> {
+        let error = |reason: &str| WebhookError::BadParse(format!("webhook url, {}", reason));
+        let url = url.trim();
+
+        // Only HTTPS is supported by Discord.
+        let rest = match url.get(..8) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("https://") => &url[8..],
+            _ => return Err(error("expected https scheme")),
+        };
+
+        // Separate the fragment, query, path, and host.
+        let rest = rest.split('#').next().unwrap_or_default();
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+        let (host, path) = match rest.split_once('/') {
+            Some((host, path)) => (host, path),
+            None => return Err(error("missing path")),
+        };
+
+        if !HOSTS.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+            return Err(error("unknown host"));
+        }
+
+        // Path is /api[/vN]/webhooks/{id}/{token}[/]
+        let path = path.strip_suffix('/').unwrap_or(path);
+        let segments: Vec<&str> = path.split('/').collect();
+        let segments = match segments.as_slice() {
+            ["api", version, rest @ ..] if is_version(version) => rest,
+            ["api", rest @ ..] => rest,
+            _ => return Err(error("expected /api/ path")),
+        };
+
+        let (id, token) = match segments {
+            ["webhooks", id, token] => (id, token),
+            _ => return Err(error("expected /webhooks/{id}/{token}")),
+        };
+
+        let id: WebhookId = id.parse().map_err(|_| error("invalid webhook id"))?;
+        let mut webhook = Self::new(id, token).map_err(|_| error("invalid webhook token"))?;
+
+        // Preserve the thread, other parameters (such as 'wait') are ignored.
+        for (key, value) in query
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|p| p.split_once('='))
+        {
+            if key == "thread_id" {
+                let thread: ChannelId = value.parse().map_err(|_| error("invalid thread id"))?;
+                webhook.thread_id = Some(thread);
+            }
+        }
+
+        Ok(webhook)
+    }
+}
+
+/// Checks if the path segment is an API version, such as 'v10'.
+fn is_version(segment: &str) -> bool {
+    match segment.strip_prefix('v') {
+        Some(number) => !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()),
+        None => false,
+    }
+}
+
+impl FromStr for WebhookUrl {
+    type Err = WebhookError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::parse(value)
+    }
+}
+
+impl fmt::Display for WebhookUrl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "https://discord.com/api/webhooks/{}/{}",
+            self.id,
+            self.token.expose()
+        )?;
+        match self.thread_id {
+            Some(thread) => write!(f, "?thread_id={}", thread),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Serialize for WebhookUrl {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for WebhookUrl {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_webhook_urls() {
+        let url: WebhookUrl = "https://discord.com/api/webhooks/123/abc".parse().unwrap();
+        assert_eq!(url.id(), WebhookId::new(123));
+        assert_eq!(url.token().expose(), "abc");
+        assert_eq!(url.thread_id(), None);
+
+        let url: WebhookUrl = "https://canary.discord.com/api/v10/webhooks/123/abc/"
+            .parse()
+            .unwrap();
+        assert_eq!(url.id(), WebhookId::new(123));
+        assert_eq!(url.token().expose(), "abc");
+    }
+
+    #[test]
+    fn keeps_the_thread() {
+        let url: WebhookUrl = "https://discord.com/api/webhooks/123/abc?wait=true&thread_id=456"
+            .parse()
+            .unwrap();
+        assert_eq!(url.thread_id(), Some(ChannelId::new(456)));
+    }
+
+    #[test]
+    fn rejects_invalid_urls() {
+        for value in [
+            "http://discord.com/api/webhooks/123/abc",
+            "https://example.com/api/webhooks/123/abc",
+            "https://discord.com/webhooks/123/abc",
+            "https://discord.com/api/webhooks/123",
+            "https://discord.com/api/webhooks/0/abc",
+            "https://discord.com/api/webhooks/abc/abc",
+            "https://discord.com/api/webhooks/123/abc?thread_id=x",
+        ] {
+            assert!(value.parse::<WebhookUrl>().is_err(), "accepted '{}'", value);
+        }
+    }
+    #[test]
+    fn masks_the_token_outside_of_display() {
+        let url: WebhookUrl = "https://discord.com/api/webhooks/123/AAAABBBBCCCC?thread_id=456"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            url.redacted(),
+            "https://discord.com/api/webhooks/123/[redacted]?thread_id=456"
+        );
+        assert!(!format!("{:?}", url).contains("AAAABBBBCCCC"));
+        assert!(!format!("{:#?}", url).contains("AAAABBBBCCCC"));
+        assert!(!format!("{:?}", url.token()).contains("AAAABBBBCCCC"));
+        assert!(!url.token().to_string().contains("AAAABBBBCCCC"));
+    }
+
+    #[test]
+    fn errors_never_contain_the_token() {
+        for value in [
+            "http://discord.com/api/webhooks/123/AAAABBBBCCCC",
+            "https://example.com/api/webhooks/123/AAAABBBBCCCC",
+            "https://discord.com/api/webhooks/abc/AAAABBBBCCCC",
+            "https://discord.com/api/webhooks/123/AAAABBBBCCCC!",
+            "https://discord.com/api/webhooks/123/AAAABBBBCCCC?thread_id=x",
+        ] {
+            let error = value.parse::<WebhookUrl>().unwrap_err();
+            assert!(
+                !error.to_string().contains("AAAABBBBCCCC"),
+                "leaked by '{}'",
+                value
+            );
+            assert!(
+                !format!("{:?}", error).contains("AAAABBBBCCCC"),
+                "leaked by '{}'",
+                value
+            );
+        }
+
+        let error = WebhookUrl::new(WebhookId::new(123), "AAAA BBBB").unwrap_err();
+        assert!(!error.to_string().contains("AAAA BBBB"));
+    }
+}
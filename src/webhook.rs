@@ -3,36 +3,60 @@
 //! `webhook` bundles up the required authentication parameters and creates a HTTP client that is
 //! used to interact with the Discord API. All authentication for each request is handled for the user.
 
-use crate::client::{Client, Result, WebhookError};
+use crate::client::{Client, Operation, Result, WebhookError};
+use crate::dryrun::DryRun;
 use crate::message::MessageApi;
-use hyper::{Body, Method};
+use crate::middleware::Middleware;
+use crate::observe::Observer;
+use crate::secret::{redact_url, Token};
+use crate::snowflake::{ChannelId, GuildId, WebhookId};
+use crate::transport::Transport;
+use crate::url::WebhookUrl;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
 
 /// Webhook object that contains all of the information regarding a Discord Webhook.
 ///
 /// ## References / Documentation
 ///
 /// <https://discord.com/developers/docs/resources/webhook#webhook-object-webhook-structure>
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize)]
 pub struct Webhook {
     /// ID of the webhook.
-    pub id: String,
+    pub id: WebhookId,
     /// Type of the webhook.
     pub r#type: u8,
     /// Guild ID this webhook is for, if any.
-    pub guild_id: String,
-    /// Channel ID this webhook is for, if any.
-    pub channel_id: String,
+    pub guild_id: Option<GuildId>,
+    /// Channel ID this webhook is for.
+    pub channel_id: ChannelId,
     /// Default name of the webhook.
     pub name: String,
     /// Default user avatar hash of the webhook.
     pub avatar: Option<String>,
     /// Secure token of the webhook (returned for Incoming webhooks.)
-    pub token: String,
+    pub token: Token,
     /// URL used for executing the webhook (returned by the webhooks OAuth2 flow.)
     pub url: String,
 }
 
+/// Debugging masks the token, including the one within `url`.
+impl fmt::Debug for Webhook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Webhook")
+            .field("id", &self.id)
+            .field("type", &self.r#type)
+            .field("guild_id", &self.guild_id)
+            .field("channel_id", &self.channel_id)
+            .field("name", &self.name)
+            .field("avatar", &self.avatar)
+            .field("token", &self.token)
+            .field("url", &redact_url(&self.url))
+            .finish()
+    }
+}
+
 /// WebhookApi is a client that is responsible for making requests to the Discord API.
 /// Requires a webhook ID and Token. You can find these requirements in the URL provided for the
 /// webhook.
@@ -56,13 +80,15 @@ impl WebhookApi {
     ///
     /// * `webhook_id` - ID of the webhook.
     /// * `webhook_token` - Token of the webhook.
-    pub fn new(webhook_id: &str, webhook_token: &str) -> Self {
+    pub fn new(webhook_id: WebhookId, webhook_token: &str) -> Self {
         let client: Client = Client::new(webhook_id, webhook_token);
         let message: MessageApi = MessageApi::new(&client);
         Self { client, message }
     }
 
-    /// Parses a Discord webhook URL and creates a new `WebhookApi` client.
+    /// Parses a Discord webhook URL and creates a new `WebhookApi` client. See `WebhookUrl` for
+    /// the accepted forms. If the URL contains a `thread_id`, messages are sent to that thread by
+    /// default.
     ///
     /// # Arguments
     ///
@@ -72,15 +98,85 @@ impl WebhookApi {
     ///
     /// A `WebhookApi` instance if the URL is valid, otherwise returns an error.
     pub fn from_url(url: &str) -> Result<Self> {
-        let parts: Vec<&str> = url.split('/').collect();
-        if parts.len() < 7 {
-            return Err(WebhookError::BadParse("webhook url".to_string()));
-        }
+        Ok(Self::from_webhook_url(&url.parse()?))
+    }
+
+    /// Creates a new `WebhookApi` client from an already parsed webhook URL. If the URL contains a
+    /// `thread_id`, messages are sent to that thread by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Parsed URL of the webhook.
+    pub fn from_webhook_url(url: &WebhookUrl) -> Self {
+        let mut webhook = Self::new(url.id(), url.token().expose());
+        webhook.message = webhook.message.in_thread(url.thread_id());
+        webhook
+    }
+
+    /// Sets the maximum amount of times a rate limited (429) request is retried, waiting as long
+    /// as the API instructs between attempts. Defaults to 0, returning `WebhookError::RateLimited`.
+    ///
+    /// # Arguments
+    ///
+    /// * `retries` - Maximum amount of retries per request.
+    pub fn max_retries(self, retries: u32) -> Self {
+        self.configure(|client| client.max_retries = retries)
+    }
+
+    /// Sets the base URL of the API, such as a mock server or proxy used in place of Discord.
+    /// Defaults to `https://discord.com/api/v10`, webhooks are accessed beneath `/webhooks`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - Base URL of the API, a trailing '/' is ignored.
+    pub fn base_url(self, url: &str) -> Self {
+        let url = url.trim_end_matches('/').to_string();
+        self.configure(|client| client.base_url = url)
+    }
+
+    /// Sets the transport used to send requests, such as a `Cassette` to record and replay
+    /// responses. Defaults to `HyperTransport`.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - Transport that exchanges requests for responses.
+    pub fn transport<T: Transport + 'static>(self, transport: T) -> Self {
+        self.configure(|client| client.transport = Arc::new(transport))
+    }
+
+    /// Enables a dry run: messages are still truncated, validated, and serialized, but nothing is
+    /// sent. Synthetic responses with generated IDs and timestamps are returned instead. Use
+    /// `WebhookApi::transport` with a clone of a `DryRun` to inspect the captured requests.
+    pub fn dry_run(self) -> Self {
+        self.transport(DryRun::new())
+    }
 
-        let webhook_id = parts[parts.len() - 2];
-        let webhook_token = parts[parts.len() - 1];
+    /// Adds an observer that is notified after every request with its outcome, latency, and rate
+    /// limits. Observers are called in the order they were added.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - Observer to notify, such as a closure taking a `&RequestEvent`.
+    pub fn observer<O: Observer + 'static>(self, observer: O) -> Self {
+        self.configure(|client| client.observers.push(Arc::new(observer)))
+    }
+
+    /// Adds middleware that intercepts every request and response, including retries.
+    /// `before_send` is called in the order middleware was added, and `after_response` in the
+    /// reverse order.
+    ///
+    /// # Arguments
+    ///
+    /// * `middleware` - Middleware to add to the end of the chain.
+    pub fn middleware<M: Middleware + 'static>(self, middleware: M) -> Self {
+        self.configure(|client| client.middleware.push(Arc::new(middleware)))
+    }
 
-        Ok(Self::new(webhook_id, webhook_token))
+    /// Applies a change to the HTTP client, keeping the client used by `MessageApi` in sync.
+    fn configure<F: FnOnce(&mut Client)>(mut self, func: F) -> Self {
+        func(&mut self.client);
+        self.message.client = self.client.clone();
+        self
     }
 
     /// Obtains an existing webhook. This will error if it no longer exists.
@@ -88,9 +184,17 @@ impl WebhookApi {
     /// ## References / Documentation
     ///
     /// <https://discord.com/developers/docs/resources/webhook#get-webhook-with-token>
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "yadwh.webhook.get", skip_all, fields(webhook.id = %self.client.id))
+    )]
     pub async fn get(&self) -> Result<Webhook> {
         // Send a GET request to obtain an existing webhook.
-        match self.client.send(Method::GET, "", Body::from("")).await {
+        match self
+            .client
+            .send(Operation::GetWebhook, "", String::new())
+            .await
+        {
             Ok(value) => match serde_json::from_str(&value) {
                 Ok(resp) => Ok(resp),
                 Err(_) => Err(WebhookError::BadParse("get response".to_string())),
@@ -108,12 +212,16 @@ impl WebhookApi {
     /// ## References / Documentation
     ///
     /// <https://discord.com/developers/docs/resources/webhook#modify-webhook-with-token>
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "yadwh.webhook.modify", skip_all, fields(webhook.id = %self.client.id))
+    )]
     pub async fn modify(&self, webhook: &Webhook) -> Result<Webhook> {
         // Webhook converted to an HTTP Body.
-        let body = Body::from(serde_json::to_string(webhook).unwrap());
+        let body = serde_json::to_string(webhook).unwrap();
 
         // Send a PATCH request to change an existing webhook message.
-        match self.client.send(Method::PATCH, "", body).await {
+        match self.client.send(Operation::ModifyWebhook, "", body).await {
             Ok(value) => match serde_json::from_str(&value) {
                 Ok(resp) => Ok(resp),
                 Err(_) => Err(WebhookError::BadParse("edit response".to_string())),
@@ -127,9 +235,17 @@ impl WebhookApi {
     /// ## References / Documentation
     ///
     /// <https://discord.com/developers/docs/resources/webhook#delete-webhook-with-token>
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "yadwh.webhook.delete", skip_all, fields(webhook.id = %self.client.id))
+    )]
     pub async fn delete(&self) -> Result<()> {
         // Send a DELETE request to remove an existing webhook.
-        match self.client.send(Method::DELETE, "", Body::from("")).await {
+        match self
+            .client
+            .send(Operation::DeleteWebhook, "", String::new())
+            .await
+        {
             Ok(_) => Ok(()),
             Err(error) => match error {
                 WebhookError::NoContent => Ok(()),
@@ -0,0 +1,587 @@
+//! Ready-made embed widgets for reporting progress.
+//!
+//! `widget` contains `ProgressBar`, a text progress bar with a percentage and estimated time
+//! remaining, `Checklist`, a list of steps marked pending, running, done, or failed, and
+//! `StatusTable`, key/value pairs laid out as inline `EmbedField`s. They are intended to be
+//! rendered again on every change of a `LiveMessage`.
+//!
+//! Every widget stays within its `Limit`: the progress bar fits within a field value, the
+//! checklist collapses finished steps and clips itself to its limit, and the status table never
+//! exceeds the field count or total size of the embed it is added to. Text is used as markdown
+//! and is not escaped, pass untrusted text through `markdown::escape` first.
+
+use crate::client::Limit;
+use crate::embed::{Embed, EmbedField};
+use crate::truncate::{self, Truncation};
+use std::time::{Duration, Instant};
+
+/// Zero width space, used for empty field names and values which Discord rejects.
+const ZERO_WIDTH: &str = "\u{200b}";
+
+/// Maximum width of a progress bar, in characters.
+const MAX_WIDTH: usize = 100;
+
+/// Maximum length of the label of a progress bar, keeping it within `Limit::FIELD_VALUE`.
+const MAX_LABEL: usize = 256;
+
+/// Clips text to `max` characters with an ellipsis.
+fn clip(text: &str, max: usize) -> String {
+    let mut text = text.to_string();
+    truncate::clip("", &mut text, max, Truncation::Ellipsis, &mut vec![]);
+    text
+}
+
+/// Formats a duration coarsely, such as `1h 5m`, `3m 20s`, or `45s`.
+fn duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// Text progress bar with a percentage and estimated time remaining, such as
+/// ``Uploading `██████░░░░░░░░░░░░░░` 30% · ETA 1m 10s``.
+///
+/// The time remaining is estimated from the progress made since the bar was created.
+#[derive(Debug, Clone)]
+pub struct ProgressBar {
+    /// Amount of work completed.
+    current: u64,
+    /// Total amount of work.
+    total: u64,
+    /// Width of the bar in characters.
+    width: usize,
+    /// Text displayed before the bar.
+    label: Option<String>,
+    /// When the work started.
+    started: Instant,
+}
+
+impl ProgressBar {
+    /// Creates an empty progress bar, 20 characters wide. The estimate starts from now.
+    ///
+    /// # Arguments
+    ///
+    /// * `total` - Total amount of work, such as steps or bytes.
+    pub fn new(total: u64) -> Self {
+        Self {
+            current: 0,
+            total,
+            width: 20,
+            label: None,
+            started: Instant::now(),
+        }
+    }
+
+    /// Sets the width of the bar, between 1 and 100 characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - Width of the bar in characters.
+    pub fn width(&mut self, width: usize) -> &mut Self {
+        self.width = width.clamp(1, MAX_WIDTH);
+        self
+    }
+
+    /// Sets the text displayed before the bar, clipped to 256 characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - Text displayed before the bar, such as the task being performed.
+    pub fn label(&mut self, label: &str) -> &mut Self {
+        self.label = Some(clip(label, MAX_LABEL));
+        self
+    }
+
+    /// Sets the amount of work completed, capped at the total.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Amount of work completed.
+    pub fn set(&mut self, current: u64) -> &mut Self {
+        self.current = current.min(self.total);
+        self
+    }
+
+    /// Adds to the amount of work completed, capped at the total.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - Amount of work completed since the last update.
+    pub fn advance(&mut self, amount: u64) -> &mut Self {
+        self.set(self.current.saturating_add(amount))
+    }
+
+    /// Percentage of work completed, from 0 to 100. Work with a total of 0 is complete.
+    pub fn percent(&self) -> u8 {
+        match self.total {
+            0 => 100,
+            total => (self.current as u128 * 100 / total as u128) as u8,
+        }
+    }
+
+    /// Estimated time remaining, `None` until progress is made or once the work is complete.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.current == 0 || self.current >= self.total {
+            return None;
+        }
+
+        let remaining = (self.total - self.current) as f64 / self.current as f64;
+        Some(self.started.elapsed().mul_f64(remaining))
+    }
+
+    /// Renders the progress bar, always within `Limit::FIELD_VALUE`.
+    pub fn render(&self) -> String {
+        let filled = match self.total {
+            0 => self.width,
+            total => (self.current as u128 * self.width as u128 / total as u128) as usize,
+        };
+        let bar = format!(
+            "`{}{}` {}%",
+            "█".repeat(filled),
+            "░".repeat(self.width - filled),
+            self.percent()
+        );
+
+        let mut text = match &self.label {
+            Some(label) => format!("{} {}", label, bar),
+            None => bar,
+        };
+        if let Some(eta) = self.eta() {
+            text.push_str(&format!(" · ETA {}", duration(eta)));
+        }
+        text
+    }
+}
+
+/// Status of a step within a `Checklist`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StepStatus {
+    /// Not started yet.
+    #[default]
+    Pending,
+    /// Currently in progress.
+    Running,
+    /// Finished successfully.
+    Done,
+    /// Finished unsuccessfully.
+    Failed,
+}
+
+impl StepStatus {
+    /// Marker displayed before the step.
+    pub fn marker(self) -> &'static str {
+        match self {
+            Self::Pending => "⬜",
+            Self::Running => "🔄",
+            Self::Done => "✅",
+            Self::Failed => "❌",
+        }
+    }
+}
+
+/// Step within a `Checklist`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Name of the step.
+    pub name: String,
+    /// Status of the step.
+    pub status: StepStatus,
+    /// Detail displayed after the name, such as how long the step took or why it failed.
+    pub note: Option<String>,
+}
+
+/// Multi-step checklist, one line per step with a marker for its status, such as `✅ Build`.
+///
+/// The checklist is limited to `Limit::DESCRIPTION` by default. When it does not fit, the
+/// earliest finished steps are collapsed into a single line, then it is clipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checklist {
+    /// Steps in the order they are displayed.
+    steps: Vec<Step>,
+    /// Maximum length of the rendered checklist.
+    limit: usize,
+}
+
+impl Checklist {
+    /// Creates an empty checklist limited to `Limit::DESCRIPTION`.
+    pub fn new() -> Self {
+        Self {
+            steps: vec![],
+            limit: Limit::DESCRIPTION,
+        }
+    }
+
+    /// Sets the maximum length, such as `Limit::FIELD_VALUE` when rendered within a field.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum length in characters.
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Adds a pending step.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the step.
+    pub fn step(&mut self, name: &str) -> &mut Self {
+        self.steps.push(Step {
+            name: name.to_string(),
+            status: StepStatus::Pending,
+            note: None,
+        });
+        self
+    }
+
+    /// Steps in the order they are displayed.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Sets the status of a step, steps that do not exist are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the step, starting at 0.
+    /// * `status` - New status of the step.
+    pub fn set(&mut self, index: usize, status: StepStatus) -> &mut Self {
+        if let Some(step) = self.steps.get_mut(index) {
+            step.status = status;
+        }
+        self
+    }
+
+    /// Sets the detail displayed after the name of a step, steps that do not exist are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Position of the step, starting at 0.
+    /// * `note` - Detail such as how long the step took or why it failed.
+    pub fn note(&mut self, index: usize, note: &str) -> &mut Self {
+        if let Some(step) = self.steps.get_mut(index) {
+            step.note = Some(note.to_string());
+        }
+        self
+    }
+
+    /// Whether every step is done.
+    pub fn is_done(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| step.status == StepStatus::Done)
+    }
+
+    /// Whether any step failed.
+    pub fn is_failed(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| step.status == StepStatus::Failed)
+    }
+
+    /// Renders the checklist, always within its limit.
+    pub fn render(&self) -> String {
+        let mut lines: Vec<String> = self
+            .steps
+            .iter()
+            .map(|step| match &step.note {
+                Some(note) => format!("{} {} — {}", step.status.marker(), step.name, note),
+                None => format!("{} {}", step.status.marker(), step.name),
+            })
+            .collect();
+
+        // Collapse the earliest finished steps until the rest fits along with the summary.
+        let summary = |collapsed: usize| match collapsed {
+            0 => None,
+            1 => Some(format!("{} 1 earlier step done", StepStatus::Done.marker())),
+            n => Some(format!(
+                "{} {} earlier steps done",
+                StepStatus::Done.marker(),
+                n
+            )),
+        };
+        let mut size: usize = lines.iter().map(|line| Limit::length(line) + 1).sum();
+        let mut collapsed = 0;
+        while size + summary(collapsed).map_or(0, |line| Limit::length(&line) + 1) > self.limit + 1
+            && collapsed < lines.len()
+            && self.steps[collapsed].status == StepStatus::Done
+        {
+            size -= Limit::length(&lines[collapsed]) + 1;
+            collapsed += 1;
+        }
+
+        let lines: Vec<String> = summary(collapsed)
+            .into_iter()
+            .chain(lines.split_off(collapsed))
+            .collect();
+        clip(&lines.join("\n"), self.limit)
+    }
+}
+
+impl Default for Checklist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Key/value pairs displayed as embed fields in 1 to 3 columns.
+///
+/// Discord places up to three inline fields on each row, so two columns are created by adding
+/// an empty field after every pair. Names and values are clipped to their limits with an
+/// ellipsis, and rows that do not fit within the embed are replaced with a count of those left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTable {
+    /// Key/value pairs in the order they are displayed.
+    rows: Vec<(String, String)>,
+    /// Amount of pairs on each row.
+    columns: usize,
+}
+
+impl StatusTable {
+    /// Creates an empty table with 3 columns.
+    pub fn new() -> Self {
+        Self {
+            rows: vec![],
+            columns: 3,
+        }
+    }
+
+    /// Sets the amount of pairs on each row, between 1 and 3. A single column does not use
+    /// inline fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - Amount of pairs on each row.
+    pub fn columns(&mut self, columns: usize) -> &mut Self {
+        self.columns = columns.clamp(1, 3);
+        self
+    }
+
+    /// Adds a key/value pair, or replaces the value if the key already exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Displayed as the name of the field.
+    /// * `value` - Displayed as the value of the field.
+    pub fn set(&mut self, key: &str, value: &str) -> &mut Self {
+        match self.rows.iter_mut().find(|(name, _)| name == key) {
+            Some((_, current)) => *current = value.to_string(),
+            None => self.rows.push((key.to_string(), value.to_string())),
+        }
+        self
+    }
+
+    /// Removes a key/value pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Key of the pair to remove.
+    pub fn remove(&mut self, key: &str) -> &mut Self {
+        self.rows.retain(|(name, _)| name != key);
+        self
+    }
+
+    /// Creates the fields for an embed without any other fields, see `StatusTable::apply`.
+    pub fn fields(&self) -> Vec<EmbedField> {
+        let mut embed = Embed::new();
+        self.apply(&mut embed);
+        embed.fields
+    }
+
+    /// Adds the fields to an embed after any it already has, staying within `Limit::FIELDS` and
+    /// `Limit::EMBED_TOTAL`. Pairs that do not fit are replaced by a field counting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `embed` - Embed to add the fields to.
+    pub fn apply(&self, embed: &mut Embed) {
+        let inline = match self.columns {
+            1 => None,
+            _ => Some(true),
+        };
+        let field = |name: &str, value: &str| EmbedField {
+            name: match name.is_empty() {
+                true => ZERO_WIDTH.to_string(),
+                false => clip(name, Limit::FIELD_NAME),
+            },
+            value: match value.is_empty() {
+                true => ZERO_WIDTH.to_string(),
+                false => clip(value, Limit::FIELD_VALUE),
+            },
+            inline,
+        };
+        let spacer = || field("", "");
+
+        let mut size = embed.length();
+        let length = |field: &EmbedField| Limit::length(&field.name) + Limit::length(&field.value);
+
+        // Start on a new row, inline fields would otherwise join the row of earlier fields. Only
+        // done if there are rows to add and room for at least one field after the spacer.
+        let mut fields: Vec<EmbedField> = vec![];
+        let previous = embed.fields.last().and_then(|field| field.inline);
+        if inline.is_some()
+            && previous == Some(true)
+            && !self.rows.is_empty()
+            && embed.fields.len() + 1 < Limit::FIELDS
+        {
+            let separator = EmbedField {
+                inline: None,
+                ..spacer()
+            };
+            size += length(&separator);
+            fields.push(separator);
+        }
+        let mut added = 0;
+        for (i, (name, value)) in self.rows.iter().enumerate() {
+            let mut row = vec![field(name, value)];
+            if self.columns == 2 && (i + 1) % 2 == 0 {
+                row.push(spacer());
+            }
+
+            // Leave room for a field counting the pairs that are left out.
+            let reserve = usize::from(i + 1 < self.rows.len());
+            let count = embed.fields.len() + fields.len() + row.len() + reserve;
+            let bytes: usize = row.iter().map(length).sum::<usize>() + reserve * 32;
+            if count > Limit::FIELDS || size + bytes > Limit::EMBED_TOTAL {
+                break;
+            }
+
+            size += row.iter().map(length).sum::<usize>();
+            fields.extend(row);
+            added += 1;
+        }
+
+        let omitted = self.rows.len() - added;
+        if omitted > 0 && embed.fields.len() + fields.len() < Limit::FIELDS {
+            fields.push(EmbedField {
+                inline: None,
+                ..field("…", &format!("{} more", omitted))
+            });
+        }
+
+        embed.fields.extend(fields);
+    }
+}
+
+impl Default for StatusTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_bar_fits_within_a_field() {
+        let mut bar = ProgressBar::new(200);
+        bar.width(MAX_WIDTH).label(&"x".repeat(1000)).advance(50);
+        assert_eq!(bar.percent(), 25);
+        assert!(Limit::length(&bar.render()) <= Limit::FIELD_VALUE);
+
+        bar.advance(500);
+        assert_eq!(bar.percent(), 100);
+    }
+
+    #[test]
+    fn checklist_collapses_finished_steps() {
+        let mut checklist = Checklist::new();
+        checklist.limit(120);
+        for i in 0..10 {
+            checklist.step(&format!("Step number {}", i));
+        }
+        for i in 0..8 {
+            checklist.set(i, StepStatus::Done);
+        }
+        checklist.set(8, StepStatus::Running);
+
+        let rendered = checklist.render();
+        assert!(Limit::length(&rendered) <= 120);
+        assert!(rendered.contains("earlier steps done"));
+        assert!(rendered.contains("Step number 8"));
+        assert!(rendered.contains("Step number 9"));
+        assert!(!checklist.is_done());
+    }
+
+    #[test]
+    fn checklist_is_clipped_to_its_limit() {
+        let mut checklist = Checklist::new();
+        checklist.limit(50);
+        checklist.step(&"x".repeat(200)).note(0, "note");
+        assert_eq!(Limit::length(&checklist.render()), 50);
+    }
+
+    #[test]
+    fn status_table_respects_the_field_limit() {
+        let mut table = StatusTable::new();
+        for i in 0..40 {
+            table.set(&format!("Key {}", i), "value");
+        }
+
+        let mut embed = Embed::new();
+        embed.field("Existing", "value", None);
+        table.apply(&mut embed);
+        assert_eq!(embed.fields.len(), Limit::FIELDS);
+        assert_eq!(embed.fields.last().unwrap().value, "17 more");
+        assert!(embed.validate().is_ok());
+    }
+
+    #[test]
+    fn status_table_pads_two_columns() {
+        let mut table = StatusTable::new();
+        table.columns(2).set("A", "1").set("B", "2").set("C", "3");
+
+        let fields = table.fields();
+        let names: Vec<&str> = fields.iter().map(|field| field.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "B", ZERO_WIDTH, "C"]);
+    }
+
+    #[test]
+    fn status_table_spacer_stays_within_limits() {
+        // An empty table adds nothing, even after inline fields.
+        let mut embed = Embed::new();
+        embed.field("Inline", "value", Some(true));
+        StatusTable::new().apply(&mut embed);
+        assert_eq!(embed.fields.len(), 1);
+
+        // No spacer is added when it would leave no room for the rows.
+        let mut embed = Embed::new();
+        for i in 0..Limit::FIELDS - 1 {
+            embed.field(&format!("Inline {}", i), "value", Some(true));
+        }
+        let mut table = StatusTable::new();
+        table.set("Key", "value");
+        table.apply(&mut embed);
+        assert_eq!(embed.fields.len(), Limit::FIELDS);
+        assert_eq!(embed.fields.last().unwrap().name, "Key");
+
+        // The spacer counts towards the total size of the embed. Five rows of 1026 characters
+        // after the spacer (2) land exactly on the limit, one more character leaves a row out.
+        let rows = 5 * (2 + Limit::FIELD_VALUE);
+        for (extra, kept) in [(0, 5), (1, 4)] {
+            let value = "v".repeat(Limit::EMBED_TOTAL - "Inline".len() - 2 - rows + extra);
+            let mut embed = Embed::new();
+            embed.field("Inline", &value, Some(true));
+
+            let mut table = StatusTable::new();
+            for i in 0..5 {
+                table.set(&format!("K{}", i), &"v".repeat(Limit::FIELD_VALUE));
+            }
+            table.apply(&mut embed);
+
+            let names: Vec<&str> = embed.fields.iter().map(|f| f.name.as_str()).collect();
+            assert_eq!(names[1], ZERO_WIDTH);
+            assert_eq!(names.iter().filter(|n| n.starts_with('K')).count(), kept);
+            assert!(embed.validate().is_ok());
+            match extra {
+                0 => assert_eq!(embed.length(), Limit::EMBED_TOTAL),
+                _ => assert_eq!(names.last(), Some(&"…")),
+            }
+        }
+    }
+}
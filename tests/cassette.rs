@@ -0,0 +1,91 @@
+//! Record requests against the mock Discord webhook API and replay them without the server.
+//!
+//! cargo test --test cassette --features testing
+
+use serde_json::Value;
+use std::path::PathBuf;
+use yadwh::cassette::{Cassette, Matching};
+use yadwh::message::MessageBuilder;
+use yadwh::testing::{MockServer, MOCK_TOKEN};
+use yadwh::transport::HyperTransport;
+use yadwh::{WebhookApi, WebhookError};
+
+/// Creates, obtains, and deletes a message, returning every response as JSON.
+async fn exchange(webhook: &WebhookApi) -> Result<Vec<Value>, WebhookError> {
+    let found = webhook.get().await?;
+    let message = MessageBuilder::new().content("Recorded.")?;
+    let created = webhook.message.create(&message, None).await?;
+    let obtained = webhook.message.get(created.id).await?;
+    webhook.message.delete(created.id).await?;
+
+    let json = |value: Result<Value, serde_json::Error>| value.unwrap_or_default();
+    Ok(vec![
+        json(serde_json::to_value(&found)),
+        json(serde_json::to_value(&created)),
+        json(serde_json::to_value(&obtained)),
+    ])
+}
+
+fn path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("yadwh-{}-{}.json", name, std::process::id()))
+}
+
+#[tokio::test]
+async fn records_without_the_token_and_replays_identically() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    let path = path("round-trip");
+
+    // Record against the server.
+    let recording = Cassette::record_with(&path, HyperTransport::new());
+    let webhook = server.client().transport(recording);
+    let recorded = exchange(&webhook).await?;
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(!text.contains(MOCK_TOKEN), "token written to the cassette");
+    assert!(text.contains("[redacted]"));
+
+    // Replay without the server, the responses are the same.
+    let server_id = server.webhook_id();
+    let base_url = server.base_url();
+    drop(server);
+
+    let replaying = Cassette::replay(&path, Matching::Strict)?;
+    assert_eq!(replaying.interactions().len(), 4);
+    let webhook = WebhookApi::new(server_id, MOCK_TOKEN)
+        .base_url(&base_url)
+        .transport(replaying);
+    let replayed = exchange(&webhook).await?;
+
+    // The token within the webhook itself is masked by the recording.
+    let expected = Value::from(recorded)
+        .to_string()
+        .replace(MOCK_TOKEN, "[redacted]");
+    assert_eq!(Value::from(replayed).to_string(), expected);
+
+    std::fs::remove_file(&path).ok();
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_requests_that_were_not_recorded() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    let path = path("mismatch");
+
+    let webhook = server
+        .client()
+        .transport(Cassette::record_with(&path, HyperTransport::new()));
+    let message = MessageBuilder::new().content("Recorded.")?;
+    webhook.message.create(&message, None).await?;
+
+    let webhook = server
+        .client()
+        .transport(Cassette::replay(&path, Matching::Strict)?);
+    let message = MessageBuilder::new().content("Different.")?;
+    assert!(matches!(
+        webhook.message.create(&message, None).await,
+        Err(WebhookError::Unknown(_))
+    ));
+
+    std::fs::remove_file(&path).ok();
+    Ok(())
+}
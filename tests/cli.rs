@@ -0,0 +1,106 @@
+//! Run the `yadwh` command-line tool against the mock Discord webhook API.
+//!
+//! cargo test --test cli --features cli,testing
+
+use serde_json::Value;
+use std::process::{Command, Output};
+use yadwh::message::MessageBuilder;
+use yadwh::testing::{MockServer, MOCK_TOKEN};
+use yadwh::WebhookError;
+
+/// Runs the tool with the webhook of the server, on a blocking thread so the server keeps running.
+async fn yadwh(server: &MockServer, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_yadwh"));
+    command
+        .arg("--url")
+        .arg(format!(
+            "https://discord.com/api/webhooks/{}/{}",
+            server.webhook_id(),
+            MOCK_TOKEN
+        ))
+        .arg("--base-url")
+        .arg(server.base_url())
+        .arg("--json")
+        .args(args);
+
+    tokio::task::spawn_blocking(move || command.output().expect("running yadwh"))
+        .await
+        .expect("waiting for yadwh")
+}
+
+/// Parses the JSON printed by the tool.
+fn stdout(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("json output")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sends_messages_with_embeds() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+
+    let args = [
+        "message",
+        "send",
+        "--content",
+        "Deployed.",
+        "--title",
+        "Release",
+        "--field",
+        "Version=1.2.3",
+    ];
+    let sent = stdout(&yadwh(&server, &args).await);
+    assert_eq!(sent["content"], "Deployed.");
+    assert_eq!(sent["embeds"][0]["title"], "Release");
+    assert_eq!(sent["embeds"][0]["fields"][0]["name"], "Version");
+    assert_eq!(sent["embeds"][0]["fields"][0]["value"], "1.2.3");
+    assert_eq!(server.messages().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn edits_keep_existing_embeds() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    let message = MessageBuilder::new()
+        .content("Created.")?
+        .embed(|embed| embed.title("Title"));
+    let created = server.client().message.create(&message, None).await?;
+    server.clear_requests();
+
+    // Only the content is sent, the embeds are left as they are.
+    let id = created.id.to_string();
+    let edited = stdout(&yadwh(&server, &["message", "edit", &id, "--content", "Edited."]).await);
+    assert_eq!(edited["content"], "Edited.");
+    assert_eq!(edited["embeds"][0]["title"], "Title");
+
+    let payload = server.requests()[0].json()?;
+    assert_eq!(payload["content"], "Edited.");
+    assert!(payload.get("embeds").is_none(), "embeds sent: {}", payload);
+
+    // Embed flags replace the embeds.
+    let args = ["message", "edit", &id, "--title", "Replaced"];
+    let edited = stdout(&yadwh(&server, &args).await);
+    assert_eq!(edited["content"], "Edited.");
+    assert_eq!(edited["embeds"][0]["title"], "Replaced");
+
+    let obtained = server.client().message.get(created.id).await?;
+    assert_eq!(obtained.embeds[0].title.as_deref(), Some("Replaced"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn requires_confirmation_to_delete_the_webhook() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+
+    let output = yadwh(&server, &["webhook", "delete"]).await;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--yes"));
+    assert!(server.requests().is_empty());
+
+    Ok(())
+}
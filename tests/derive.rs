@@ -0,0 +1,171 @@
+//! Convert structs into embeds with `#[derive(IntoEmbed)]`, and check the diagnostics of invalid
+//! attributes.
+//!
+//! cargo test --test derive --features derive
+
+use std::time::{Duration, SystemTime};
+use yadwh::color::Color;
+use yadwh::embed::IntoEmbed;
+use yadwh::timestamp::Timestamp;
+use yadwh::Limit;
+
+#[derive(IntoEmbed)]
+#[embed(
+    title = "Static",
+    description = "Static",
+    footer = "Footer",
+    color = "#57F287"
+)]
+struct Report {
+    #[embed(title, format = "Report {}")]
+    name: String,
+    #[embed(description)]
+    notes: Option<String>,
+    #[embed(url)]
+    link: Option<String>,
+    #[embed(author)]
+    owner: &'static str,
+    #[embed(color)]
+    color: Option<Color>,
+    #[embed(timestamp)]
+    finished: SystemTime,
+    error_count: u32,
+    #[embed(rename = "Region", inline)]
+    region_name: String,
+    #[embed(format = "{:.1}s")]
+    duration: f64,
+    retries: Option<u32>,
+    empty: String,
+    #[embed(skip)]
+    #[allow(dead_code)]
+    internal: u64,
+}
+
+fn report() -> Report {
+    Report {
+        name: "nightly".to_string(),
+        notes: None,
+        link: Some("https://example.com".to_string()),
+        owner: "CI",
+        color: None,
+        finished: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        error_count: 3,
+        region_name: "eu-west".to_string(),
+        duration: 83.25,
+        retries: None,
+        empty: String::new(),
+        internal: 42,
+    }
+}
+
+#[test]
+fn converts_struct_fields() {
+    let embed = report().to_embed();
+
+    // Struct fields override static values, `None` leaves the static value in place.
+    assert_eq!(embed.title.as_deref(), Some("Report nightly"));
+    assert_eq!(embed.description.as_deref(), Some("Static"));
+    assert_eq!(embed.url.as_deref(), Some("https://example.com"));
+    assert_eq!(embed.author.as_ref().map(|a| a.name.as_str()), Some("CI"));
+    assert_eq!(
+        embed.footer.as_ref().map(|f| f.text.as_str()),
+        Some("Footer")
+    );
+    assert_eq!(embed.color, Some(Color::GREEN));
+    assert_eq!(embed.timestamp, Some(Timestamp::from(report().finished)));
+
+    // Names are in sentence case unless renamed, skipped and `None` values are left out.
+    let fields: Vec<(&str, &str, Option<bool>)> = embed
+        .fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.value.as_str(), field.inline))
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            ("Error count", "3", None),
+            ("Region", "eu-west", Some(true)),
+            ("Duration", "83.2s", None),
+            ("Empty", "\u{200b}", None),
+        ]
+    );
+}
+
+#[test]
+fn adds_values_within_some() {
+    let mut report = report();
+    report.notes = Some("Notes".to_string());
+    report.color = Some(Color::RED);
+    report.retries = Some(2);
+
+    let embed = report.to_embed();
+    assert_eq!(embed.description.as_deref(), Some("Notes"));
+    assert_eq!(embed.color, Some(Color::RED));
+    assert!(embed
+        .fields
+        .iter()
+        .any(|field| field.name == "Retries" && field.value == "2"));
+}
+
+#[derive(IntoEmbed)]
+#[embed(inline)]
+struct Inline {
+    first: u8,
+    #[embed(inline = false)]
+    second: u8,
+}
+
+#[test]
+fn makes_fields_inline_by_default() {
+    let embed = Inline {
+        first: 1,
+        second: 2,
+    }
+    .to_embed();
+    assert_eq!(embed.fields[0].inline, Some(true));
+    assert_eq!(embed.fields[1].inline, None);
+}
+
+#[derive(IntoEmbed)]
+struct Oversized {
+    #[embed(title)]
+    title: String,
+    a: String,
+    b: String,
+    c: String,
+    d: String,
+    e: String,
+    f: String,
+    g: String,
+}
+
+#[test]
+fn clips_values_exceeding_limits() {
+    let long = "x".repeat(3000);
+    let value = Oversized {
+        title: long.clone(),
+        a: long.clone(),
+        b: long.clone(),
+        c: long.clone(),
+        d: long.clone(),
+        e: long.clone(),
+        f: long.clone(),
+        g: long,
+    };
+
+    let embed = value.to_embed();
+    assert!(embed.validate().is_ok());
+    assert!(embed.truncation.is_none());
+    assert!(embed.length() <= Limit::EMBED_TOTAL);
+
+    let title = embed.title.as_deref().unwrap();
+    assert_eq!(title.chars().count(), Limit::TITLE);
+    assert!(title.ends_with('…'));
+    assert_eq!(embed.fields[0].value.chars().count(), Limit::FIELD_VALUE);
+}
+
+#[test]
+fn reports_invalid_attributes() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,118 @@
+//! Create, edit, and delete messages within a dry run, inspecting the captured requests.
+//!
+//! cargo test --test dry_run
+
+use std::time::Duration;
+use yadwh::dryrun::DryRun;
+use yadwh::message::MessageBuilder;
+use yadwh::observe::Operation;
+use yadwh::snowflake::{ChannelId, MessageId, Snowflake, WebhookId};
+use yadwh::timestamp::Timestamp;
+use yadwh::{WebhookApi, WebhookError};
+
+fn webhook(dry_run: &DryRun) -> WebhookApi {
+    WebhookApi::new(WebhookId::new(1), "secret-token").transport(dry_run.clone())
+}
+
+#[tokio::test]
+async fn creates_edits_and_deletes_messages() -> Result<(), WebhookError> {
+    let dry_run = DryRun::new().channel(ChannelId::new(42));
+    let webhook = webhook(&dry_run);
+
+    // Created messages receive increasing IDs and are stored.
+    let message = MessageBuilder::new().content("Created.")?;
+    let first = webhook.message.create(&message, None).await?;
+    let second = webhook
+        .message
+        .create(&message, Some(ChannelId::new(7)))
+        .await?;
+    assert!(second.id > first.id);
+    assert_eq!(first.content, "Created.");
+    assert_eq!(first.channel_id, ChannelId::new(42));
+    assert_eq!(second.channel_id, ChannelId::new(7));
+    assert_eq!(first.webhook_id, WebhookId::new(1));
+    assert!(first.edited_timestamp.is_none());
+
+    // Edits are applied to the stored message.
+    let edited = MessageBuilder::new().content("Edited @everyone.")?;
+    webhook.message.edit(first.id, &edited).await?;
+    let obtained = webhook.message.get(first.id).await?;
+    assert_eq!(obtained.content, "Edited @everyone.");
+    assert!(obtained.mention_everyone);
+    assert!(obtained.edited_timestamp.is_some());
+    assert_eq!(obtained.timestamp, first.timestamp);
+
+    // Deleted messages are unknown afterwards.
+    webhook.message.delete(first.id).await?;
+    match webhook.message.get(first.id).await {
+        Err(WebhookError::Discord(error)) => assert_eq!((error.status, error.code), (404, 10008)),
+        other => panic!("expected Unknown Message, got {:?}", other),
+    }
+    webhook.message.get(second.id).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn synthesizes_unknown_messages_when_edited() -> Result<(), WebhookError> {
+    let dry_run = DryRun::new().channel(ChannelId::new(42));
+    let webhook = webhook(&dry_run);
+
+    // A message sent before the dry run, created an hour ago.
+    let sent = std::time::SystemTime::now() - Duration::from_secs(3600);
+    let snowflake = Snowflake::from_time(sent);
+    let id = MessageId::from(snowflake);
+    assert!(webhook.message.get(id).await.is_err());
+
+    let edited = MessageBuilder::new().content("Edited.")?;
+    let message = webhook.message.edit(id, &edited).await?;
+    assert_eq!(message.id, id);
+    assert_eq!(message.content, "Edited.");
+    assert_eq!(message.channel_id, ChannelId::new(42));
+    assert_eq!(message.timestamp, Timestamp::from(snowflake.timestamp()));
+    assert!(message.edited_timestamp.is_some());
+
+    // The synthesized message is kept, later requests see the edit.
+    let obtained = webhook.message.get(id).await?;
+    assert_eq!(obtained.content, "Edited.");
+    webhook.message.delete(id).await?;
+    assert!(webhook.message.get(id).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn captures_every_request_without_the_token() -> Result<(), WebhookError> {
+    let dry_run = DryRun::new();
+    let webhook = webhook(&dry_run);
+
+    let message = MessageBuilder::new()
+        .content("Captured.")?
+        .embed(|embed| embed.title("Title"));
+    let created = webhook.message.create(&message, None).await?;
+    webhook.message.delete(created.id).await?;
+
+    let captured = dry_run.captured();
+    let operations: Vec<Operation> = captured.iter().map(|request| request.operation).collect();
+    assert_eq!(
+        operations,
+        [Operation::CreateMessage, Operation::DeleteMessage]
+    );
+
+    // The payload is captured as it would have been sent.
+    let payload = captured[0].json()?;
+    assert_eq!(payload["content"], "Captured.");
+    assert_eq!(payload["embeds"][0]["title"], "Title");
+    assert!(captured[1].body.is_empty());
+    assert!(captured[1]
+        .url
+        .ends_with(&format!("/webhooks/1/[redacted]/messages/{}", created.id)));
+    assert!(captured
+        .iter()
+        .all(|request| !request.url.contains("secret-token")));
+
+    dry_run.clear();
+    assert!(dry_run.captured().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+//! Fail over between webhooks of the mock Discord webhook API.
+//!
+//! cargo test --test failover --features testing
+
+use std::time::Duration;
+use yadwh::failover::{ErrorClass, Failover};
+use yadwh::group::Target;
+use yadwh::message::MessageBuilder;
+use yadwh::testing::{MockServer, Scripted, MOCK_TOKEN};
+use yadwh::{Result, WebhookApi, WebhookError};
+
+fn message() -> MessageBuilder {
+    MessageBuilder::new().content("Alert.").unwrap()
+}
+
+fn unknown_webhook() -> Scripted {
+    Scripted::Error {
+        status: 404,
+        code: 10015,
+        message: "Unknown Webhook".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn fails_over_and_skips_targets_cooling_down() -> Result<()> {
+    let primary = MockServer::start().await?;
+    let backup = MockServer::start().await?;
+    let failover = Failover::new(Target::new("primary", primary.client()))
+        .backup(Target::new("backup", backup.client()))
+        .cooldown(Duration::from_millis(200));
+
+    // The primary was deleted, the backup delivers the message.
+    primary.script(unknown_webhook());
+    let routed = failover.send(&message()).await?;
+    assert_eq!((routed.target.as_str(), routed.index), ("backup", 1));
+    assert!(routed.is_backup());
+    assert_eq!(routed.attempts.len(), 1);
+    assert_eq!(routed.attempts[0].target, "primary");
+    assert_eq!(routed.attempts[0].class, ErrorClass::UnknownWebhook);
+    assert!(matches!(
+        routed.attempts[0].error,
+        WebhookError::Discord(ref error) if error.code == 10015
+    ));
+
+    // While cooling down the primary is skipped entirely.
+    let unhealthy = failover.unhealthy();
+    assert_eq!(unhealthy.len(), 1);
+    assert_eq!(unhealthy[0].0, "primary");
+    let routed = failover.send(&message()).await?;
+    assert_eq!(routed.index, 1);
+    assert!(routed.attempts.is_empty());
+    assert_eq!(primary.requests().len(), 1);
+
+    // Once the cool-down passes the primary is tried again.
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let routed = failover.send(&message()).await?;
+    assert_eq!(routed.index, 0);
+    assert!(!routed.is_backup());
+    assert!(failover.unhealthy().is_empty());
+    assert_eq!(backup.messages().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tries_every_target_when_all_are_unhealthy() -> Result<()> {
+    let primary = MockServer::start().await?;
+    let backup = MockServer::start().await?;
+    let failover = Failover::new(Target::new("primary", primary.client()))
+        .backup(Target::new("backup", backup.client()));
+
+    // Both fail, the error of the last target tried is returned.
+    primary.script(Scripted::Status(503));
+    backup.script(Scripted::Error {
+        status: 403,
+        code: 50013,
+        message: "Missing Permissions".to_string(),
+    });
+    match failover.send(&message()).await {
+        Err(WebhookError::Discord(error)) => assert_eq!(error.code, 50013),
+        other => panic!("expected Missing Permissions, got {:?}", other),
+    }
+    assert_eq!(failover.unhealthy().len(), 2);
+
+    // Rather than dropping the message, every target is tried in order.
+    primary.script(Scripted::Status(500));
+    let routed = failover.send(&message()).await?;
+    assert_eq!(routed.index, 1);
+    assert_eq!(routed.attempts.len(), 1);
+    assert_eq!(routed.attempts[0].class, ErrorClass::ServerError);
+    assert_eq!(primary.requests().len(), 2);
+    assert_eq!(failover.unhealthy().len(), 1);
+
+    failover.reset();
+    assert!(failover.unhealthy().is_empty());
+    assert_eq!(failover.send(&message()).await?.index, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn returns_unconfigured_errors_immediately() -> Result<()> {
+    let primary = MockServer::start().await?;
+    let backup = MockServer::start().await?;
+    let failover = Failover::new(Target::new("primary", primary.client()))
+        .backup(Target::new("backup", backup.client()));
+
+    // Errors a backup would repeat are not failed over.
+    primary.script(Scripted::Error {
+        status: 400,
+        code: 50035,
+        message: "Invalid Form Body".to_string(),
+    });
+    assert!(matches!(
+        failover.send(&message()).await,
+        Err(WebhookError::Discord(_))
+    ));
+
+    // Rate limits are only failed over when configured.
+    primary.script(Scripted::RateLimited {
+        retry_after: Duration::from_secs(30),
+        global: false,
+    });
+    assert!(matches!(
+        failover.send(&message()).await,
+        Err(WebhookError::RateLimited(_))
+    ));
+    assert!(backup.requests().is_empty());
+    assert!(failover.unhealthy().is_empty());
+
+    // Rate limited targets are skipped until the rate limit resets.
+    let failover = failover.fail_on(&[ErrorClass::RateLimited]);
+    primary.script(Scripted::RateLimited {
+        retry_after: Duration::from_secs(30),
+        global: false,
+    });
+    let routed = failover.send(&message()).await?;
+    assert_eq!(routed.index, 1);
+    assert_eq!(routed.attempts[0].class, ErrorClass::RateLimited);
+    let (_, remaining) = failover.unhealthy()[0];
+    assert!(remaining > Duration::from_secs(25) && remaining <= Duration::from_secs(30));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fails_over_on_network_errors() -> Result<()> {
+    let backup = MockServer::start().await?;
+
+    // Nothing listens on the address of a stopped server.
+    let stopped = MockServer::start().await?;
+    let unreachable =
+        WebhookApi::new(stopped.webhook_id(), MOCK_TOKEN).base_url(&stopped.base_url());
+    drop(stopped);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let failover = Failover::new(Target::new("primary", unreachable))
+        .backup(Target::new("backup", backup.client()));
+    let routed = failover.send(&message()).await?;
+    assert_eq!(routed.index, 1);
+    assert_eq!(routed.attempts[0].class, ErrorClass::Network);
+    assert!(matches!(
+        routed.attempts[0].error,
+        WebhookError::Transport(_)
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,252 @@
+//! Send one message to several webhooks of the mock Discord webhook API.
+//!
+//! cargo test --test group --features testing
+
+use futures::future::BoxFuture;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use yadwh::group::{GroupPolicy, Outcome, Target, WebhookGroup};
+use yadwh::message::MessageBuilder;
+use yadwh::middleware::{Middleware, Request, Response};
+use yadwh::observe::Operation;
+use yadwh::snowflake::ChannelId;
+use yadwh::testing::{MockServer, Scripted};
+use yadwh::transport::{HyperTransport, Transport};
+use yadwh::{Result, WebhookError};
+
+/// Transport that tracks the most requests in flight at once.
+#[derive(Clone, Default)]
+struct Counting {
+    inner: HyperTransport,
+    in_flight: Arc<AtomicUsize>,
+    most: Arc<AtomicUsize>,
+}
+
+impl Transport for Counting {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.most.fetch_max(current, Ordering::SeqCst);
+
+            // Hold the request long enough for others to start.
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let response = self.inner.send(request).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            response
+        })
+    }
+}
+
+/// Rejects every attempt to delete a message.
+struct DenyDeletes;
+
+impl Middleware for DenyDeletes {
+    fn before_send(&self, request: &mut Request) -> Result<()> {
+        match request.operation {
+            Operation::DeleteMessage => Err(WebhookError::Unknown("delete denied".to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn message() -> MessageBuilder {
+    MessageBuilder::new().content("Alert.").unwrap()
+}
+
+fn outcomes(report: &yadwh::group::GroupReport) -> Vec<(&str, &'static str)> {
+    report
+        .deliveries
+        .iter()
+        .map(|delivery| {
+            let outcome = match delivery.outcome {
+                Outcome::Delivered(_) => "delivered",
+                Outcome::Failed(_) => "failed",
+                Outcome::Skipped => "skipped",
+                Outcome::RolledBack(_) => "rolled back",
+            };
+            (delivery.target.as_str(), outcome)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn bounds_requests_in_flight() -> Result<()> {
+    let server = MockServer::start().await?;
+    server.without_rate_limit();
+
+    let transport = Counting::default();
+    let mut group = WebhookGroup::new().concurrency(2);
+    for i in 0..5 {
+        let api = server.client().transport(transport.clone());
+        group = group.webhook(&format!("target {}", i), api);
+    }
+
+    let report = group.send(&message()).await;
+    assert!(report.is_success());
+    assert_eq!(report.delivered().count(), 5);
+    assert_eq!(server.messages().len(), 5);
+    assert_eq!(transport.most.load(Ordering::SeqCst), 2);
+
+    // Targets are reported in the order they were added.
+    let names: Vec<&str> = report
+        .deliveries
+        .iter()
+        .map(|d| d.target.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        ["target 0", "target 1", "target 2", "target 3", "target 4"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn applies_target_overrides() -> Result<()> {
+    let server = MockServer::start().await?;
+    let thread = ChannelId::new(444455556666);
+    let group = WebhookGroup::new()
+        .concurrency(1)
+        .webhook("plain", server.client())
+        .target(
+            Target::new("custom", server.client())
+                .thread(thread)
+                .username("Mirror")
+                .avatar_url("https://example.com/avatar.png"),
+        );
+
+    let report = group.send(&message().username("Original")?).await;
+    let messages = report.into_result()?;
+    assert_eq!(messages[0].channel_id, server.channel_id());
+    assert_eq!(messages[1].channel_id, thread);
+
+    let requests = server.requests();
+    let plain = requests[0].json()?;
+    assert_eq!(plain["username"], "Original");
+    assert!(plain.get("avatar_url").is_none());
+
+    let custom = requests[1].json()?;
+    assert_eq!(custom["username"], "Mirror");
+    assert_eq!(custom["avatar_url"], "https://example.com/avatar.png");
+    assert!(requests[1]
+        .query
+        .as_deref()
+        .is_some_and(|query| query.contains("thread_id=444455556666")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reports_failures_with_best_effort() -> Result<()> {
+    let first = MockServer::start().await?;
+    let second = MockServer::start().await?;
+    second.script(Scripted::Error {
+        status: 403,
+        code: 50013,
+        message: "Missing Permissions".to_string(),
+    });
+
+    let report = WebhookGroup::new()
+        .webhook("first", first.client())
+        .webhook("second", second.client())
+        .send(&message())
+        .await;
+    assert!(!report.is_success());
+    assert_eq!(
+        outcomes(&report),
+        [("first", "delivered"), ("second", "failed")]
+    );
+    assert_eq!(report.failed().next().map(|(name, _)| name), Some("second"));
+    assert_eq!(first.messages().len(), 1);
+
+    match report.into_result() {
+        Err(WebhookError::Discord(error)) => assert_eq!(error.code, 50013),
+        other => panic!("expected Missing Permissions, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rolls_back_and_skips_with_all_or_nothing() -> Result<()> {
+    let servers = [
+        MockServer::start().await?,
+        MockServer::start().await?,
+        MockServer::start().await?,
+    ];
+    servers[1].script(Scripted::Status(503));
+
+    // Sent one at a time, so the third target is skipped after the second fails.
+    let thread = ChannelId::new(444455556666);
+    let report = WebhookGroup::new()
+        .concurrency(1)
+        .policy(GroupPolicy::AllOrNothing)
+        .target(Target::new("first", servers[0].client()).thread(thread))
+        .webhook("second", servers[1].client())
+        .webhook("third", servers[2].client())
+        .send(&message())
+        .await;
+
+    assert_eq!(
+        outcomes(&report),
+        [
+            ("first", "rolled back"),
+            ("second", "failed"),
+            ("third", "skipped")
+        ]
+    );
+    assert!(servers.iter().all(|server| server.messages().is_empty()));
+    assert!(servers[2].requests().is_empty());
+    assert!(matches!(
+        report.into_result(),
+        Err(WebhookError::BadStatus(_))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn keeps_messages_that_cannot_be_rolled_back() -> Result<()> {
+    let first = MockServer::start().await?;
+    let second = MockServer::start().await?;
+    second.script(Scripted::Status(500));
+
+    let report = WebhookGroup::new()
+        .concurrency(1)
+        .policy(GroupPolicy::AllOrNothing)
+        .webhook("first", first.client().middleware(DenyDeletes))
+        .webhook("second", second.client())
+        .send(&message())
+        .await;
+
+    assert_eq!(
+        outcomes(&report),
+        [("first", "delivered"), ("second", "failed")]
+    );
+    assert_eq!(first.messages().len(), 1);
+    assert_eq!(report.delivered().count(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sends_nothing_when_invalid_for_any_target() -> Result<()> {
+    let server = MockServer::start().await?;
+    let report = WebhookGroup::new()
+        .policy(GroupPolicy::AllOrNothing)
+        .webhook("valid", server.client())
+        .target(Target::new("invalid", server.client()).username(&"x".repeat(81)))
+        .send(&message())
+        .await;
+
+    assert_eq!(
+        outcomes(&report),
+        [("valid", "skipped"), ("invalid", "failed")]
+    );
+    let error = report.into_result().unwrap_err();
+    assert!(matches!(error, WebhookError::TooBig(..)), "{:?}", error);
+    assert!(server.requests().is_empty());
+
+    Ok(())
+}
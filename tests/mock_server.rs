@@ -0,0 +1,182 @@
+//! Create, edit, and delete messages against the mock Discord webhook API.
+//!
+//! cargo test --test mock_server --features testing
+
+use std::time::Duration;
+use yadwh::message::MessageBuilder;
+use yadwh::testing::{MockServer, Scripted};
+use yadwh::WebhookError;
+
+#[tokio::test]
+async fn creates_edits_and_deletes_messages() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    let webhook = server.client();
+
+    // Create the message, it is stored by the server.
+    let message = MessageBuilder::new()
+        .username("Mock")?
+        .content("Created.")?
+        .embed(|embed| embed.title("Title").description("Description"));
+    let created = webhook.message.create(&message, None).await?;
+    assert_eq!(created.content, "Created.");
+    assert_eq!(created.embeds[0].title.as_deref(), Some("Title"));
+    assert_eq!(server.messages().len(), 1);
+
+    // Edit it, only the content is changed.
+    let edited = MessageBuilder::new().content("Edited.")?;
+    webhook.message.edit(created.id, &edited).await?;
+    let obtained = webhook.message.get(created.id).await?;
+    assert_eq!(obtained.id, created.id);
+    assert_eq!(obtained.content, "Edited.");
+    assert_eq!(obtained.embeds[0].title.as_deref(), Some("Title"));
+    assert!(obtained.edited_timestamp.is_some());
+
+    // Delete it, afterwards it is unknown.
+    webhook.message.delete(created.id).await?;
+    assert!(server.messages().is_empty());
+    match webhook.message.get(created.id).await {
+        Err(WebhookError::Discord(error)) => assert_eq!(error.code, 10008),
+        other => panic!("expected Unknown Message, got {:?}", other),
+    }
+
+    // Every request was recorded with the token masked.
+    let methods: Vec<String> = server
+        .requests()
+        .iter()
+        .map(|request| request.method.to_string())
+        .collect();
+    assert_eq!(methods, ["POST", "PATCH", "GET", "DELETE", "GET"]);
+    assert!(server
+        .requests()
+        .iter()
+        .all(|request| !request.path.contains(yadwh::testing::MOCK_TOKEN)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_messages_and_scripted_errors() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    let webhook = server.client();
+
+    // Invalid messages are rejected before anything is sent.
+    let mut message = MessageBuilder::new();
+    message.content = Some("x".repeat(2001));
+    assert!(matches!(
+        webhook.message.create(&message, None).await,
+        Err(WebhookError::Invalid(_))
+    ));
+    assert!(server.requests().is_empty());
+
+    // Scripted errors are returned as Discord errors.
+    server.script(Scripted::Error {
+        status: 404,
+        code: 10015,
+        message: "Unknown Webhook".to_string(),
+    });
+    let message = MessageBuilder::new().content("Hello.")?;
+    match webhook.message.create(&message, None).await {
+        Err(WebhookError::Discord(error)) => {
+            assert_eq!(error.status, 404);
+            assert_eq!(error.code, 10015);
+        }
+        other => panic!("expected Unknown Webhook, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_scripted_rate_limits() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    let message = MessageBuilder::new().content("Hello.")?;
+
+    // Without retries the rate limit is returned.
+    server.script(Scripted::RateLimited {
+        retry_after: Duration::from_millis(50),
+        global: true,
+    });
+    match server.client().message.create(&message, None).await {
+        Err(WebhookError::RateLimited(limit)) => {
+            assert_eq!(limit.retry_after, Some(Duration::from_millis(50)));
+            assert!(limit.global);
+        }
+        other => panic!("expected a rate limit, got {:?}", other),
+    }
+
+    // With retries the request is sent again after waiting.
+    server.clear_requests();
+    server.script(Scripted::RateLimited {
+        retry_after: Duration::from_millis(50),
+        global: false,
+    });
+    let webhook = server.client().max_retries(1);
+    let created = webhook.message.create(&message, None).await?;
+    assert_eq!(created.content, "Hello.");
+
+    let statuses: Vec<u16> = server
+        .requests()
+        .iter()
+        .map(|request| request.status.as_u16())
+        .collect();
+    assert_eq!(statuses, [429, 200]);
+    assert_eq!(server.messages().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn waits_for_exhausted_buckets() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    server.rate_limit(1, Duration::from_millis(200));
+    let webhook = server.client().max_retries(2);
+
+    let message = MessageBuilder::new().content("Hello.")?;
+    webhook.message.create(&message, None).await?;
+    webhook.message.create(&message, None).await?;
+
+    let statuses: Vec<u16> = server
+        .requests()
+        .iter()
+        .map(|request| request.status.as_u16())
+        .collect();
+    assert_eq!(statuses, [200, 429, 200]);
+    assert_eq!(server.messages().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn returns_scripted_responses_in_order() -> Result<(), WebhookError> {
+    let server = MockServer::start().await?;
+    let webhook = server.client();
+    let message = MessageBuilder::new().content("Hello.")?;
+    let created = webhook.message.create(&message, None).await?;
+
+    server.script(Scripted::Status(503));
+    server.script(Scripted::Error {
+        status: 403,
+        code: 50013,
+        message: "Missing Permissions".to_string(),
+    });
+
+    match webhook.message.edit(created.id, &message).await {
+        Err(WebhookError::BadStatus(status)) => assert_eq!(status, "Status Code: 503"),
+        other => panic!("expected a bad status, got {:?}", other),
+    }
+    match webhook.message.delete(created.id).await {
+        Err(WebhookError::Discord(error)) => {
+            assert_eq!((error.status, error.code), (403, 50013));
+            assert_eq!(error.message, "Missing Permissions");
+        }
+        other => panic!("expected Missing Permissions, got {:?}", other),
+    }
+
+    // Scripted responses replace handling, the message was left untouched.
+    assert_eq!(server.messages().len(), 1);
+    assert!(server.messages()[0].edited_timestamp.is_none());
+    webhook.message.delete(created.id).await?;
+    assert!(server.messages().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use yadwh::color::Color;
+use yadwh::embed::IntoEmbed;
+
+#[derive(IntoEmbed)]
+struct Colored {
+    #[embed(color, format = "{}")]
+    color: Color,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use yadwh::embed::IntoEmbed;
+
+#[derive(IntoEmbed)]
+enum Status {
+    Up,
+    Down,
+}
+
+fn main() {}
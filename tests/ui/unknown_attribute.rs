@@ -0,0 +1,9 @@
+use yadwh::embed::IntoEmbed;
+
+#[derive(IntoEmbed)]
+struct Unknown {
+    #[embed(heading)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,345 @@
+//! Derive macros for `yadwh`, Yet Another Discord Webhook Crate.
+//!
+//! `IntoEmbed` implements `yadwh::embed::IntoEmbed` for structs with named fields. Every struct
+//! field becomes an embed field unless it is marked otherwise with `#[embed(...)]`:
+//!
+//! * `title`, `description`, `url`, `footer`, `author`: Sets the part of the embed to the value,
+//!   formatted with `Display` or `format = "..."`.
+//! * `color`: Sets the color to the value, anything that converts into a `Color`.
+//! * `timestamp`: Sets the timestamp to the value, anything that converts into a `Timestamp`.
+//! * `skip`: Leaves the value out of the embed.
+//! * `inline`, `rename = "..."`, `format = "..."`: Options for embed fields. Names default to the
+//!   name of the struct field in sentence case, such as `error_count` becoming "Error count".
+//!
+//! Values within an `Option` are only added when they are `Some`. On the struct itself,
+//! `#[embed(title = "...", description = "...", url = "...", footer = "...", author = "...",
+//! color = 0x5865F2, inline)]` sets static values that struct fields override, and `inline` makes
+//! every embed field inline by default.
+//!
+//! Values exceeding their limits are clipped with `Truncation::Ellipsis`, so the result passes
+//! `Embed::validate`. The embed returned has no truncation policy of its own.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitBool, LitInt, LitStr, Type};
+
+/// Maximum amount of fields within an embed, `Limit::FIELDS`.
+const FIELDS: usize = 25;
+
+/// Implements `IntoEmbed` for a struct with named fields.
+#[proc_macro_derive(IntoEmbed, attributes(embed))]
+pub fn derive_into_embed(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Static values set by attributes on the struct.
+#[derive(Default)]
+struct Container {
+    title: Option<LitStr>,
+    description: Option<LitStr>,
+    url: Option<LitStr>,
+    footer: Option<LitStr>,
+    author: Option<LitStr>,
+    color: Option<u32>,
+    inline: bool,
+}
+
+/// Part of the embed a struct field is used for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Field,
+    Title,
+    Description,
+    Url,
+    Footer,
+    Author,
+    Color,
+    Timestamp,
+    Skip,
+}
+
+/// Options from the attributes of a struct field.
+struct Member {
+    role: Role,
+    inline: Option<bool>,
+    rename: Option<LitStr>,
+    format: Option<LitStr>,
+}
+
+/// Generates the implementation.
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(unsupported(input)),
+        },
+        _ => return Err(unsupported(input)),
+    };
+
+    let container = container(input)?;
+    let mut statements: Vec<TokenStream2> = vec![];
+
+    // Static values are set first so struct fields override them.
+    let text = [
+        (&container.title, quote!(title)),
+        (&container.description, quote!(description)),
+        (&container.url, quote!(url)),
+    ];
+    for (value, method) in text {
+        if let Some(value) = value {
+            statements.push(quote!(embed.#method(#value);));
+        }
+    }
+    if let Some(value) = &container.footer {
+        statements.push(quote!(embed.footer(#value, None, None);));
+    }
+    if let Some(value) = &container.author {
+        statements.push(quote!(embed.author(#value, None, None, None);));
+    }
+    if let Some(value) = container.color {
+        statements.push(quote!(embed.color(::yadwh::color::Color::new(#value));));
+    }
+
+    let mut count = 0;
+    for field in fields {
+        let member = member(field)?;
+        if member.role == Role::Skip {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("named field");
+        if member.role == Role::Field {
+            count += 1;
+            if count > FIELDS {
+                return Err(Error::new(
+                    field.span(),
+                    format!("an embed cannot contain more than {} fields", FIELDS),
+                ));
+            }
+        }
+
+        let text = match &member.format {
+            Some(format) => quote!(::std::format!(#format, value)),
+            None => quote!(::std::string::ToString::to_string(value)),
+        };
+
+        let statement = match member.role {
+            Role::Title => quote!(embed.title(&#text);),
+            Role::Description => quote!(embed.description(&#text);),
+            Role::Url => quote!(embed.url(&#text);),
+            Role::Footer => quote!(embed.footer(&#text, None, None);),
+            Role::Author => quote!(embed.author(&#text, None, None, None);),
+            Role::Color => quote!(embed.color(::core::clone::Clone::clone(value));),
+            Role::Timestamp => quote!(embed.timestamp(::core::clone::Clone::clone(value));),
+            Role::Field => {
+                let name = match &member.rename {
+                    Some(name) => name.clone(),
+                    None => LitStr::new(&sentence(&ident.to_string()), ident.span()),
+                };
+                let inline = match member.inline.unwrap_or(container.inline) {
+                    true => quote!(Some(true)),
+                    false => quote!(None),
+                };
+
+                // Discord rejects empty values, a zero width space is used in their place.
+                quote! {
+                    let text = #text;
+                    let text = if text.is_empty() { "\u{200b}".to_string() } else { text };
+                    embed.field(#name, &text, #inline);
+                }
+            }
+            Role::Skip => unreachable!(),
+        };
+
+        statements.push(match is_option(&field.ty) {
+            true => quote!(if let Some(value) = &self.#ident { #statement }),
+            false => quote!({ let value = &self.#ident; #statement }),
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::yadwh::embed::IntoEmbed for #name #ty_generics #where_clause {
+            fn to_embed(&self) -> ::yadwh::embed::Embed {
+                let mut embed = ::yadwh::embed::Embed::new();
+                #(#statements)*
+                embed.truncation(::yadwh::truncate::Truncation::Ellipsis);
+                embed.truncate();
+                embed.truncation = None;
+                embed
+            }
+        }
+    })
+}
+
+/// Parses the attributes on the struct.
+fn container(input: &DeriveInput) -> syn::Result<Container> {
+    let mut container = Container::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("embed"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let text = |meta: &ParseNestedMeta| -> syn::Result<Option<LitStr>> {
+                Ok(Some(meta.value()?.parse()?))
+            };
+
+            if meta.path.is_ident("title") {
+                container.title = text(&meta)?;
+            } else if meta.path.is_ident("description") {
+                container.description = text(&meta)?;
+            } else if meta.path.is_ident("url") {
+                container.url = text(&meta)?;
+            } else if meta.path.is_ident("footer") {
+                container.footer = text(&meta)?;
+            } else if meta.path.is_ident("author") {
+                container.author = text(&meta)?;
+            } else if meta.path.is_ident("color") {
+                container.color = Some(color(&meta)?);
+            } else if meta.path.is_ident("inline") {
+                container.inline = flag(&meta)?;
+            } else {
+                return Err(meta.error("unknown embed attribute, expected one of title, description, url, footer, author, color, or inline"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(container)
+}
+
+/// Parses the attributes on a struct field.
+fn member(field: &syn::Field) -> syn::Result<Member> {
+    let mut member = Member {
+        role: Role::Field,
+        inline: None,
+        rename: None,
+        format: None,
+    };
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("embed"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let roles = [
+                ("title", Role::Title),
+                ("description", Role::Description),
+                ("url", Role::Url),
+                ("footer", Role::Footer),
+                ("author", Role::Author),
+                ("color", Role::Color),
+                ("timestamp", Role::Timestamp),
+                ("skip", Role::Skip),
+            ];
+
+            if let Some((_, role)) = roles.iter().find(|(name, _)| meta.path.is_ident(name)) {
+                if member.role != Role::Field {
+                    return Err(meta.error("only one of title, description, url, footer, author, color, timestamp, or skip may be used"));
+                }
+                member.role = *role;
+            } else if meta.path.is_ident("inline") {
+                member.inline = Some(flag(&meta)?);
+            } else if meta.path.is_ident("rename") {
+                member.rename = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("format") {
+                member.format = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error("unknown embed attribute, expected one of title, description, url, footer, author, color, timestamp, skip, inline, rename, or format"));
+            }
+            Ok(())
+        })?;
+    }
+
+    // Options that only apply to some roles.
+    let field_only = member.inline.is_some() || member.rename.is_some();
+    if field_only && member.role != Role::Field {
+        return Err(Error::new(
+            field.span(),
+            "inline and rename only apply to embed fields",
+        ));
+    }
+    if member.format.is_some() && matches!(member.role, Role::Color | Role::Timestamp) {
+        return Err(Error::new(
+            field.span(),
+            "format does not apply to color or timestamp",
+        ));
+    }
+
+    Ok(member)
+}
+
+/// Parses a flag that is either present, such as `inline`, or assigned, such as `inline = false`.
+fn flag(meta: &ParseNestedMeta) -> syn::Result<bool> {
+    match meta.input.peek(syn::Token![=]) {
+        true => Ok(meta.value()?.parse::<LitBool>()?.value),
+        false => Ok(true),
+    }
+}
+
+/// Parses a color, either an integer such as `0x5865F2` or a hex string such as `"#5865F2"`.
+fn color(meta: &ParseNestedMeta) -> syn::Result<u32> {
+    let value = meta.value()?;
+    let color = match value.peek(LitStr) {
+        true => {
+            let text: LitStr = value.parse()?;
+            let hex = text.value();
+            let hex = hex.trim_start_matches('#');
+            match hex.len() == 6 {
+                true => u32::from_str_radix(hex, 16).map_err(|_| text.span()),
+                false => Err(text.span()),
+            }
+        }
+        false => {
+            let number: LitInt = value.parse()?;
+            number.base10_parse::<u32>().map_err(|_| number.span())
+        }
+    };
+
+    match color {
+        Ok(color) if color <= 0xFFFFFF => Ok(color),
+        Ok(_) => Err(meta.error("color exceeds 0xFFFFFF")),
+        Err(span) => Err(Error::new(
+            span,
+            "expected a color such as 0x5865F2 or \"#5865F2\"",
+        )),
+    }
+}
+
+/// Whether the type is an `Option`.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Converts the name of a struct field into sentence case, such as `error_count` to "Error count".
+fn sentence(name: &str) -> String {
+    let name = name.trim_start_matches("r#").replace('_', " ");
+    let mut chars = name.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Error for inputs other than structs with named fields.
+fn unsupported(input: &DeriveInput) -> Error {
+    Error::new(
+        input.ident.span(),
+        "IntoEmbed can only be derived for structs with named fields",
+    )
+}